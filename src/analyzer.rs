@@ -1,32 +1,173 @@
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::time::Duration;
-use serde::{Deserialize, Serialize};
-use anyhow::{Result, Context};
+use std::str::FromStr;
+use serde::{Deserialize, Serialize, Serializer};
+use anyhow::{Result, Context, bail};
+use ratatui::style::Color;
+use crate::bootstrap::Bootstrap;
+use crate::language::Language;
+use crate::segment::SegmentStats;
+use crate::tokens::{classify, TokenClass};
+
+/// Name of the tag whose word list is swapped for the detected language.
+pub const STOPWORDS_TAG_NAME: &str = "Stop Words";
+
+/// Name of the built-in tag for programming-language keywords (code tokenizer).
+pub const KEYWORDS_TAG_NAME: &str = "Keywords";
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Tag {
     pub name: String,
+    pub letter: char,
     pub color: Option<String>,
     pub description: Option<String>,
 }
 
-#[derive(Debug, Clone)]
+impl Tag {
+    /// Terminal color for this tag's indicator, falling back to gray when unset.
+    pub fn display_color(&self) -> Color {
+        self.color
+            .as_deref()
+            .and_then(|color| Color::from_str(color).ok())
+            .unwrap_or(Color::Gray)
+    }
+
+    /// CSS hex color matching `display_color`, for HTML output.
+    pub fn css_color(&self) -> String {
+        let (r, g, b) = match self.display_color() {
+            Color::Black => (0x00, 0x00, 0x00),
+            Color::Red => (0xcd, 0x31, 0x31),
+            Color::Green => (0x0d, 0xbc, 0x79),
+            Color::Yellow => (0xc8, 0xa6, 0x00),
+            Color::Blue => (0x24, 0x72, 0xc8),
+            Color::Magenta => (0xbc, 0x3f, 0xbc),
+            Color::Cyan => (0x11, 0xa8, 0xcd),
+            Color::DarkGray => (0x66, 0x66, 0x66),
+            Color::LightRed => (0xf1, 0x4c, 0x4c),
+            Color::LightGreen => (0x23, 0xd1, 0x8b),
+            Color::LightYellow => (0xe5, 0xc5, 0x10),
+            Color::LightBlue => (0x3b, 0x8e, 0xea),
+            Color::LightMagenta => (0xd6, 0x70, 0xd6),
+            Color::LightCyan => (0x29, 0xb8, 0xdb),
+            Color::White => (0xe5, 0xe5, 0xe5),
+            Color::Rgb(r, g, b) => (r, g, b),
+            _ => (0x90, 0x90, 0x90),
+        };
+        format!("#{:02x}{:02x}{:02x}", r, g, b)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct WordCount {
     pub word: String,
     pub count: usize,
     pub rank: usize,
+    #[serde(serialize_with = "serialize_tag_names")]
     pub tags: HashSet<Tag>,
 }
 
-#[derive(Debug, Clone)]
+/// Serializes as dataset metadata only; word lists are written by the
+/// exporters, which know about filtering and percentages.
+#[derive(Debug, Clone, Serialize)]
 pub struct Dataset {
     pub name: String,
+    #[serde(skip)]
     pub word_counts: Vec<WordCount>,
-    pub total_words: usize,
+    #[serde(skip)]
+    pub total_words: usize, // Exported by `export::DatasetSummary`, which leaves it out for ratio datasets
     pub unique_words: usize,
+    #[serde(rename = "parse_seconds", serialize_with = "serialize_seconds")]
     pub parse_duration: Duration,
+    #[serde(rename = "analyze_seconds", serialize_with = "serialize_seconds")]
     pub analyze_duration: Duration,
+    pub language: Option<Language>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub segments: Option<SegmentStats>, // Sentences and paragraphs; None for source code and derived datasets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bootstrap: Option<Bootstrap>, // Confidence intervals with --bootstrap; None for derived datasets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sampled_from: Option<usize>, // Tokens before --sample-tokens cut them down
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub series: Option<String>, // Input a split dataset (segment, chapter, record group) came from
+    pub kind: DatasetKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unscored_words: Option<usize>, // Ratio datasets: words found only in the divisor, which have no ratio
+}
+
+/// What a dataset's counts measure.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DatasetKind {
+    #[default]
+    Counts, // Token counts of one or more texts
+    Ratio,  // Relative-frequency ratios of two datasets × `combine::RATIO_SCALE`; not a frequency distribution
+}
+
+impl Dataset {
+    /// Dataset of already counted and ranked words; totals come from the counts, everything
+    /// else starts empty.
+    pub fn from_words(name: &str, word_counts: Vec<WordCount>) -> Self {
+        Self {
+            name: name.to_string(),
+            total_words: word_counts.iter().map(|wc| wc.count).sum(),
+            unique_words: word_counts.len(),
+            word_counts,
+            parse_duration: Duration::ZERO,
+            analyze_duration: Duration::ZERO,
+            language: None,
+            segments: None,
+            bootstrap: None,
+            sampled_from: None,
+            series: None,
+            kind: DatasetKind::Counts,
+            unscored_words: None,
+        }
+    }
+
+    /// Ratio datasets hold scores, not token counts: frequency statistics (Zipf fits,
+    /// percentages, diversity, intervals, rarefaction) do not apply to them.
+    pub fn is_ratio(&self) -> bool {
+        self.kind == DatasetKind::Ratio
+    }
+
+    /// Share of the dataset's tokens taken by `count` occurrences, in percent; None for ratio datasets.
+    pub fn percentage(&self, count: usize) -> Option<f64> {
+        if self.is_ratio() {
+            None
+        } else if self.total_words > 0 {
+            Some(count as f64 / self.total_words as f64 * 100.0)
+        } else {
+            Some(0.0)
+        }
+    }
+
+    /// Test fixture: the whitespace-separated words of `text`, counted, ranked and tagged.
+    #[cfg(test)]
+    pub fn from_text(name: &str, text: &str) -> Self {
+        Self::from_words(name, WordAnalyzer::new().analyze(text.split_whitespace().map(String::from).collect()))
+    }
+}
+
+/// Test fixture: untagged words `w0`, `w1`, ... ranked in order with the given counts.
+#[cfg(test)]
+pub fn ranked(counts: &[usize]) -> Vec<WordCount> {
+    counts
+        .iter()
+        .enumerate()
+        .map(|(i, &count)| WordCount { word: format!("w{}", i), count, rank: i + 1, tags: HashSet::new() })
+        .collect()
+}
+
+// Tags serialize as their names, sorted for stable output
+fn serialize_tag_names<S: Serializer>(tags: &HashSet<Tag>, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    let mut names: Vec<&str> = tags.iter().map(|tag| tag.name.as_str()).collect();
+    names.sort_unstable();
+    serializer.collect_seq(names)
+}
+
+fn serialize_seconds<S: Serializer>(duration: &Duration, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
 }
 
 #[derive(Debug, Deserialize)]
@@ -37,6 +178,8 @@ struct TagConfig {
 #[derive(Debug, Deserialize)]
 struct TagDefinition {
     name: String,
+    #[serde(alias = "short")]
+    letter: Option<String>,
     color: Option<String>,
     description: Option<String>,
     words: Vec<String>,
@@ -45,14 +188,18 @@ struct TagDefinition {
 #[derive(Clone)]
 pub struct TagMatcher {
     word_to_tags: HashMap<String, HashSet<Tag>>,
+    exact_word_tags: HashMap<String, HashSet<Tag>>, // Matched only as written (case-sensitive keywords)
     available_tags: Vec<Tag>,
+    class_tags: Vec<(TokenClass, Tag)>, // Tags given to every token of a class (numbers, URLs, ...)
 }
 
 impl TagMatcher {
     pub fn new() -> Self {
         Self {
             word_to_tags: HashMap::new(),
+            exact_word_tags: HashMap::new(),
             available_tags: Vec::new(),
+            class_tags: Vec::new(),
         }
     }
 
@@ -60,15 +207,47 @@ impl TagMatcher {
         let config_content = std::fs::read_to_string(config_path)
             .context("Failed to read tags configuration file")?;
         
-        let config: TagConfig = toml::from_str(&config_content)
+        Self::from_toml_str(&config_content)
+    }
+
+    pub fn from_toml_str(config_content: &str) -> Result<Self> {
+        let config: TagConfig = toml::from_str(config_content)
             .context("Failed to parse tags configuration")?;
         
         let mut word_to_tags: HashMap<String, HashSet<Tag>> = HashMap::new();
-        let mut available_tags = Vec::new();
+        let mut available_tags: Vec<Tag> = Vec::new();
         
-        for (_tag_id, tag_def) in config.tags {
+        for (tag_id, tag_def) in config.tags {
+            let letter = match tag_def.letter.as_deref() {
+                Some(letter) => {
+                    let mut chars = letter.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(c), None) if !c.is_whitespace() => c,
+                        _ => bail!("Tag '{}' has invalid letter '{}' (expected a single character)", tag_id, letter),
+                    }
+                }
+                None => tag_def.name.chars().next().unwrap_or('?').to_ascii_uppercase(),
+            };
+            
+            if let Some(color) = tag_def.color.as_deref() {
+                if Color::from_str(color).is_err() {
+                    bail!("Tag '{}' has invalid color '{}' (expected a color name or #rrggbb)", tag_id, color);
+                }
+            }
+            
+            if let Some(existing) = available_tags
+                .iter()
+                .find(|tag| tag.letter.to_lowercase().eq(letter.to_lowercase()))
+            {
+                bail!(
+                    "Tags '{}' and '{}' both use the letter '{}'; set a distinct `letter` for one of them",
+                    existing.name, tag_def.name, letter
+                );
+            }
+            
             let tag = Tag {
                 name: tag_def.name,
+                letter,
                 color: tag_def.color,
                 description: tag_def.description,
             };
@@ -78,31 +257,148 @@ impl TagMatcher {
             for word in tag_def.words {
                 word_to_tags
                     .entry(word.to_lowercase())
-                    .or_insert_with(HashSet::new)
+                    .or_default()
                     .insert(tag.clone());
             }
         }
         
         Ok(Self {
             word_to_tags,
+            exact_word_tags: HashMap::new(),
             available_tags,
+            class_tags: Vec::new(),
         })
     }
 
     pub fn get_tags(&self, word: &str) -> HashSet<Tag> {
-        self.word_to_tags
+        let mut tags = self.word_to_tags
             .get(&word.to_lowercase())
             .cloned()
-            .unwrap_or_default()
+            .unwrap_or_default();
+        if let Some(exact) = self.exact_word_tags.get(word) {
+            tags.extend(exact.iter().cloned());
+        }
+        if !self.class_tags.is_empty() {
+            if let Some(class) = classify(word) {
+                tags.extend(self.class_tags.iter().filter(|(tagged, _)| *tagged == class).map(|(_, tag)| tag.clone()));
+            }
+        }
+        tags
     }
 
     pub fn available_tags(&self) -> &[Tag] {
         &self.available_tags
     }
 
+    /// Number of distinct words carrying `tag`.
+    pub fn words_with_tag(&self, tag: &Tag) -> usize {
+        let mut words: HashSet<String> = self.word_to_tags.iter()
+            .filter(|(_, tags)| tags.contains(tag))
+            .map(|(word, _)| word.clone())
+            .collect();
+        words.extend(self.exact_word_tags.iter().filter(|(_, tags)| tags.contains(tag)).map(|(word, _)| word.clone()));
+        words.len()
+    }
+
     pub fn get_tag_by_name(&self, name: &str) -> Option<&Tag> {
         self.available_tags.iter().find(|tag| tag.name == name)
     }
+
+    /// Copy of this matcher whose stop-word tag covers `stopwords` instead of its configured list.
+    /// A `Stop Words` tag from the configuration keeps its letter and color; otherwise a built-in
+    /// tag is added. Other tags are untouched.
+    pub fn with_stopwords(&self, stopwords: &[&str]) -> Self {
+        let mut matcher = self.clone();
+        let stopword_tag = matcher.builtin_tag(STOPWORDS_TAG_NAME, 'S', "gray", "Common function words of the detected language");
+        matcher.clear_tag(&stopword_tag);
+
+        for word in stopwords {
+            matcher.word_to_tags
+                .entry(word.to_lowercase())
+                .or_default()
+                .insert(stopword_tag.clone());
+        }
+
+        matcher
+    }
+
+    /// Copy of this matcher with a keyword tag covering `keywords`. A `Keywords` tag from the
+    /// configuration keeps its letter, color and words; otherwise a built-in tag is added.
+    /// Keywords match only as written unless `ignore_case` (for languages like SQL).
+    pub fn with_keywords(&self, keywords: &[&str], ignore_case: bool) -> Self {
+        let mut matcher = self.clone();
+        let keyword_tag = matcher.builtin_tag(KEYWORDS_TAG_NAME, 'K', "magenta", "Keywords of the source language");
+
+        for keyword in keywords {
+            let (words, keyword) = if ignore_case {
+                (&mut matcher.word_to_tags, keyword.to_lowercase())
+            } else {
+                (&mut matcher.exact_word_tags, keyword.to_string())
+            };
+            words.entry(keyword).or_default().insert(keyword_tag.clone());
+        }
+
+        matcher
+    }
+
+    /// Copy of this matcher tagging every token of `classes` (kept numbers, URLs, ...) and their
+    /// placeholders with the class's tag, e.g. `Numbers`. Configured tags of that name are reused,
+    /// words and all.
+    pub fn with_token_classes(&self, classes: &[TokenClass]) -> Self {
+        let mut matcher = self.clone();
+        for &class in classes {
+            let name = class.tag_name();
+            let letter = name.chars().next().unwrap_or('#');
+            let tag = matcher.builtin_tag(name, letter, class.tag_color(), &format!("{} in the text", name));
+            matcher.class_tags.push((class, tag));
+        }
+        matcher
+    }
+
+    /// The configured tag called `name`, with its configured words, or else a new built-in tag with
+    /// the first letter from `preferred` on (wrapping around the alphabet) that no other tag uses.
+    fn builtin_tag(&mut self, name: &str, preferred: char, color: &str, description: &str) -> Tag {
+        if let Some(tag) = self.get_tag_by_name(name) {
+            return tag.clone();
+        }
+
+        let taken = |letter: char| self.available_tags.iter().any(|tag| tag.letter.eq_ignore_ascii_case(&letter));
+        let preferred = preferred.to_ascii_uppercase();
+        let letter = (preferred..='Z').chain('A'..preferred).find(|&letter| !taken(letter)).unwrap_or('#');
+        let tag = Tag {
+            name: name.to_string(),
+            letter,
+            color: Some(color.to_string()),
+            description: Some(format!("{} (built in)", description)),
+        };
+        self.available_tags.push(tag.clone());
+        tag
+    }
+
+    /// Untag every word tagged with `tag`, for lists that replace the configured words.
+    fn clear_tag(&mut self, tag: &Tag) {
+        for words in [&mut self.word_to_tags, &mut self.exact_word_tags] {
+            for tags in words.values_mut() {
+                tags.remove(tag);
+            }
+            words.retain(|_, tags| !tags.is_empty());
+        }
+    }
+}
+
+/// The tag matcher for text in `language`: when the language has bundled stop words, they replace
+/// the configured list, and a stop-word tag is added if none is configured.
+pub fn language_tag_matcher(tag_matcher: Option<&TagMatcher>, language: Option<&Language>) -> Option<TagMatcher> {
+    match language.and_then(Language::stopwords) {
+        Some(stopwords) => Some(tag_matcher.cloned().unwrap_or_default().with_stopwords(&stopwords)),
+        None => tag_matcher.cloned(),
+    }
+}
+
+impl Default for TagMatcher {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 pub struct WordAnalyzer {
@@ -135,13 +431,25 @@ impl WordAnalyzer {
         self.get_ranked_words()
     }
 
+    /// Rank and tag words from precomputed counts (e.g. a snapshot); repeated words are summed.
+    pub fn analyze_counts(&mut self, counts: impl IntoIterator<Item = (String, usize)>) -> Vec<WordCount> {
+        self.word_counts.clear();
+
+        for (word, count) in counts {
+            *self.word_counts.entry(word).or_insert(0) += count;
+        }
+
+        self.get_ranked_words()
+    }
+
     fn get_ranked_words(&self) -> Vec<WordCount> {
         let mut word_counts: Vec<(String, usize)> = self.word_counts
             .iter()
             .map(|(word, count)| (word.clone(), *count))
             .collect();
 
-        word_counts.sort_by(|a, b| b.1.cmp(&a.1));
+        // Equal counts rank alphabetically, so rankings do not depend on hash order
+        word_counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
 
         word_counts
             .into_iter()
@@ -212,4 +520,119 @@ mod tests {
         assert_eq!(analyzer.total_words(), 10);
         assert_eq!(analyzer.unique_words(), 8);
     }
+
+    #[test]
+    fn test_tag_letters_and_colors() {
+        let config = r##"
+            [tags.stopwords]
+            name = "Stop Words"
+            color = "#808080"
+            words = ["the"]
+
+            [tags.sentiment_positive]
+            name = "Positive"
+            letter = "+"
+            color = "light green"
+            words = ["good"]
+        "##;
+
+        let matcher = TagMatcher::from_toml_str(config).unwrap();
+        let stop = matcher.get_tag_by_name("Stop Words").unwrap();
+        assert_eq!(stop.letter, 'S');
+        assert_eq!(stop.display_color(), Color::Rgb(0x80, 0x80, 0x80));
+
+        let positive = matcher.get_tag_by_name("Positive").unwrap();
+        assert_eq!(positive.letter, '+');
+        assert_eq!(positive.display_color(), Color::LightGreen);
+    }
+
+    #[test]
+    fn test_with_stopwords() {
+        let config = r#"
+            [tags.stopwords]
+            name = "Stop Words"
+            words = ["the", "and"]
+
+            [tags.temporal]
+            name = "Temporal"
+            words = ["now", "the"]
+        "#;
+
+        let matcher = TagMatcher::from_toml_str(config).unwrap().with_stopwords(&["le", "et"]);
+        let stop = matcher.get_tag_by_name(STOPWORDS_TAG_NAME).unwrap().clone();
+        assert!(matcher.get_tags("le").contains(&stop));
+        assert!(matcher.get_tags("and").is_empty());
+        // Other tags keep their words
+        assert_eq!(matcher.get_tags("the").len(), 1);
+        assert!(!matcher.get_tags("the").contains(&stop));
+
+        // Without a configured tag, a built-in one is added
+        let matcher = TagMatcher::new().with_stopwords(&["le"]);
+        let stop = matcher.get_tag_by_name(STOPWORDS_TAG_NAME).unwrap();
+        assert_eq!(stop.letter, 'S');
+        assert!(matcher.get_tags("le").contains(stop));
+    }
+
+    #[test]
+    fn test_keyword_case() {
+        let matcher = TagMatcher::new().with_keywords(&["type", "Self"], false).with_keywords(&["select"], true);
+        let keywords = matcher.get_tag_by_name("Keywords").unwrap().clone();
+        assert_eq!(matcher.available_tags().len(), 1);
+        assert!(matcher.get_tags("type").contains(&keywords));
+        assert!(matcher.get_tags("Self").contains(&keywords));
+        // Case-sensitive keywords do not tag identifiers that only differ in case
+        assert!(matcher.get_tags("Type").is_empty());
+        assert!(matcher.get_tags("self").is_empty());
+        assert!(matcher.get_tags("SELECT").contains(&keywords));
+        assert_eq!(matcher.words_with_tag(&keywords), 3);
+    }
+
+    #[test]
+    fn test_token_class_tags() {
+        let config = r#"
+            [tags.negative]
+            name = "Negative"
+            letter = "N"
+            words = ["bad"]
+
+            [tags.links]
+            name = "URLs"
+            letter = "L"
+            words = ["link"]
+        "#;
+
+        let matcher = TagMatcher::from_toml_str(config).unwrap().with_token_classes(&[TokenClass::Number, TokenClass::Url]);
+        let numbers = matcher.get_tag_by_name("Numbers").unwrap().clone();
+        let urls = matcher.get_tag_by_name("URLs").unwrap().clone();
+        // N is taken, so the built-in tag moves on; the configured URLs tag keeps its letter
+        assert_eq!((numbers.letter, urls.letter), ('O', 'L'));
+        assert!(matcher.get_tags("2024").contains(&numbers));
+        assert!(matcher.get_tags("<NUM>").contains(&numbers));
+        assert!(matcher.get_tags("https://example.org").contains(&urls));
+        // Configured words of a class tag stay tagged next to the built-in matches
+        assert!(matcher.get_tags("link").contains(&urls));
+        assert_eq!(matcher.get_tags("bad").len(), 1);
+    }
+
+    #[test]
+    fn test_tag_validation_errors() {
+        let collision = r#"
+            [tags.a]
+            name = "Stop Words"
+            words = []
+
+            [tags.b]
+            name = "Sentiment"
+            words = []
+        "#;
+        assert!(TagMatcher::from_toml_str(collision).is_err());
+
+        let bad_color = r#"
+            [tags.a]
+            name = "Stop Words"
+            color = "not-a-color"
+            words = []
+        "#;
+        assert!(TagMatcher::from_toml_str(bad_color).is_err());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,266 @@
+use crate::analyzer::WordCount;
+use crate::sample::seeded_rng;
+use crate::stats::{diversity, fit_counts, fit_zipf};
+use clap::ValueEnum;
+use rand::Rng;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Words that get their own intervals: the top ranks of each dataset. Further down the list
+/// words occur once or twice, and their intervals say little.
+pub const INTERVAL_WORDS: usize = 1000;
+
+/// What is drawn with replacement to build each resample.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Resample {
+    #[default]
+    Token, // Single tokens; assumes every occurrence is independent
+    Chunk, // Runs of consecutive tokens, so words that cluster in passages stay together
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BootstrapOptions {
+    pub samples: usize,
+    pub resample: Resample,
+    pub chunk_size: usize, // Tokens per chunk with `Resample::Chunk`
+    pub confidence: f64,   // Interval coverage, e.g. 0.95
+    pub seed: Option<u64>, // Random when None
+}
+
+/// A percentile interval over the resamples.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Interval {
+    pub low: f64,
+    pub high: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct WordInterval {
+    pub percentage: Interval, // Share of each resample's tokens
+    pub rank: Interval,       // Among all words of each resample; tied words share the best rank
+}
+
+/// Intervals for the fields of `stats::Diversity`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiversityIntervals {
+    pub types: Interval,
+    pub type_token_ratio: Interval,
+    pub hapax_ratio: Interval,
+    pub entropy: Interval,
+    pub yules_k: Interval,
+}
+
+/// Bootstrap confidence intervals of one dataset.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Bootstrap {
+    pub samples: usize,
+    pub resample: Resample,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chunk_size: Option<usize>,
+    pub confidence: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exponent: Option<Interval>, // Zipf exponent fitted over all ranks
+    // Types, TTR and the like are corrected for the bias of resampling; see `bootstrap`
+    pub diversity: DiversityIntervals,
+    #[serde(skip)] // Written next to each word by the exporters
+    pub words: HashMap<String, WordInterval>,
+}
+
+impl Bootstrap {
+    pub fn word(&self, word: &str) -> Option<&WordInterval> {
+        self.words.get(word)
+    }
+}
+
+/// What each resample is built from: word indices of single tokens, or (word index, count)
+/// lists of chunks.
+enum Units {
+    Tokens(Vec<u32>),
+    Chunks(Vec<Vec<(u32, usize)>>),
+}
+
+/// Resample a dataset to get intervals for word frequencies and ranks, the Zipf exponent and the
+/// diversity metrics. Token resampling needs only the counts; chunk resampling needs the tokens
+/// in text order, and gives None without them. Empty datasets give None. Tokens are laid out
+/// by word, so a seed gives the same intervals however ties in `word_counts` are ordered.
+pub fn bootstrap(word_counts: &[WordCount], tokens: Option<&[String]>, options: &BootstrapOptions) -> Option<Bootstrap> {
+    let units = match options.resample {
+        Resample::Token => {
+            let mut by_word: Vec<(usize, &WordCount)> = word_counts.iter().enumerate().collect();
+            by_word.sort_by(|(_, a), (_, b)| a.word.cmp(&b.word));
+            Units::Tokens(by_word.into_iter().flat_map(|(index, wc)| std::iter::repeat_n(index as u32, wc.count)).collect())
+        }
+        Resample::Chunk => {
+            let index: HashMap<&str, u32> = word_counts.iter().enumerate().map(|(i, wc)| (wc.word.as_str(), i as u32)).collect();
+            let chunks = tokens?
+                .chunks(options.chunk_size.max(1))
+                .map(|chunk| {
+                    let mut counts: HashMap<u32, usize> = HashMap::new();
+                    for id in chunk.iter().filter_map(|token| index.get(token.as_str())) {
+                        *counts.entry(*id).or_default() += 1;
+                    }
+                    counts.into_iter().collect()
+                })
+                .collect();
+            Units::Chunks(chunks)
+        }
+    };
+    let unit_count = match &units {
+        Units::Tokens(ids) => ids.len(),
+        Units::Chunks(chunks) => chunks.len(),
+    };
+    if unit_count == 0 {
+        return None;
+    }
+
+    let mut rng = seeded_rng(options.seed);
+    let tracked = word_counts.len().min(INTERVAL_WORDS);
+    let mut percentages = vec![Vec::with_capacity(options.samples); tracked];
+    let mut ranks = vec![Vec::with_capacity(options.samples); tracked];
+    let mut exponents = Vec::with_capacity(options.samples);
+    let mut metrics: [Vec<f64>; 5] = Default::default();
+
+    let mut counts = vec![0usize; word_counts.len()];
+    for _ in 0..options.samples {
+        counts.fill(0);
+        match &units {
+            Units::Tokens(ids) => {
+                for _ in 0..ids.len() {
+                    counts[ids[rng.random_range(0..ids.len())] as usize] += 1;
+                }
+            }
+            Units::Chunks(chunks) => {
+                for _ in 0..chunks.len() {
+                    for &(id, count) in &chunks[rng.random_range(0..chunks.len())] {
+                        counts[id as usize] += count;
+                    }
+                }
+            }
+        }
+
+        let mut sorted: Vec<usize> = counts.iter().copied().filter(|&count| count > 0).collect();
+        sorted.sort_unstable_by(|a, b| b.cmp(a));
+        let total = sorted.iter().sum::<usize>().max(1) as f64;
+        for (i, &count) in counts[..tracked].iter().enumerate() {
+            percentages[i].push(count as f64 / total * 100.0);
+            // Words missing from a resample rank after every word that is there
+            ranks[i].push((sorted.partition_point(|&other| other > count) + 1) as f64);
+        }
+
+        if let Some(fit) = fit_counts(&sorted) {
+            exponents.push(fit.exponent);
+        }
+        let sample = diversity(sorted.iter().copied());
+        for (values, value) in metrics.iter_mut().zip([
+            sample.types as f64,
+            sample.type_token_ratio,
+            sample.hapax_ratio,
+            sample.entropy,
+            sample.yules_k,
+        ]) {
+            values.push(value);
+        }
+    }
+
+    // Drawing with replacement repeats tokens, so resamples have fewer types and hapaxes than the
+    // text itself. Intervals of whole-text statistics are moved by that bias, so that their
+    // median resample lands on the observed value.
+    let interval = |values: &mut Vec<f64>| percentile_interval(values, options.confidence);
+    let corrected = |values: &mut Vec<f64>, observed: f64| {
+        let Interval { low, high } = percentile_interval(values, options.confidence);
+        let shift = observed - quantile(values, 0.5);
+        Interval { low: low + shift, high: high + shift }
+    };
+    let observed = diversity(word_counts.iter().map(|wc| wc.count));
+    let [types, type_token_ratio, hapax_ratio, entropy, yules_k] = &mut metrics;
+    Some(Bootstrap {
+        samples: options.samples,
+        resample: options.resample,
+        chunk_size: (options.resample == Resample::Chunk).then_some(options.chunk_size),
+        confidence: options.confidence,
+        exponent: fit_zipf(word_counts)
+            .filter(|_| !exponents.is_empty())
+            .map(|fit| corrected(&mut exponents, fit.exponent)),
+        diversity: DiversityIntervals {
+            types: corrected(types, observed.types as f64),
+            type_token_ratio: corrected(type_token_ratio, observed.type_token_ratio),
+            hapax_ratio: corrected(hapax_ratio, observed.hapax_ratio),
+            entropy: corrected(entropy, observed.entropy),
+            yules_k: corrected(yules_k, observed.yules_k),
+        },
+        words: word_counts
+            .iter()
+            .zip(percentages.iter_mut().zip(ranks.iter_mut()))
+            .map(|(wc, (percentage, rank))| {
+                (wc.word.clone(), WordInterval { percentage: interval(percentage), rank: interval(rank) })
+            })
+            .collect(),
+    })
+}
+
+/// The central `confidence` share of the values, interpolating between neighbours.
+fn percentile_interval(values: &mut [f64], confidence: f64) -> Interval {
+    values.sort_by(f64::total_cmp);
+    let tail = (1.0 - confidence) / 2.0;
+    Interval { low: quantile(values, tail), high: quantile(values, 1.0 - tail) }
+}
+
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let position = q * (sorted.len() - 1) as f64;
+    let (below, above) = (position.floor() as usize, position.ceil() as usize);
+    sorted[below] + (sorted[above] - sorted[below]) * (position - below as f64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::WordAnalyzer;
+
+    fn options(resample: Resample) -> BootstrapOptions {
+        BootstrapOptions { samples: 200, resample, chunk_size: 2, confidence: 0.95, seed: Some(7) }
+    }
+
+    #[test]
+    fn test_bootstrap_intervals() {
+        let tokens: Vec<String> = "a b a b a b a b c d a b a b a b".split(' ').map(String::from).collect();
+        let word_counts = WordAnalyzer::new().analyze(tokens.clone());
+
+        // Token resampling: intervals cover the observed values and are reproducible with a seed
+        let by_token = bootstrap(&word_counts, None, &options(Resample::Token)).unwrap();
+        let a = by_token.word("a").unwrap();
+        assert!(a.percentage.low < 43.75 && 43.75 < a.percentage.high);
+        assert!(a.rank.low >= 1.0 && a.rank.high <= 3.0);
+        // Whole-text intervals are corrected to hold the observed value
+        let entropy = by_token.diversity.entropy;
+        assert!(entropy.low < entropy.high && entropy.low <= 1.5436 && 1.5436 <= entropy.high);
+        assert_eq!(bootstrap(&word_counts, None, &options(Resample::Token)), Some(by_token));
+
+        // Every chunk but one is "a b", so a's share only varies with the number of "c d" chunks drawn
+        let by_chunk = bootstrap(&word_counts, Some(&tokens), &options(Resample::Chunk)).unwrap();
+        assert!(by_chunk.word("a").unwrap().percentage.high <= 50.0);
+        assert_eq!(by_chunk.chunk_size, Some(2));
+        assert_eq!(bootstrap(&word_counts, None, &options(Resample::Chunk)), None);
+        assert_eq!(bootstrap(&[], None, &options(Resample::Token)), None);
+
+        let mut values = vec![4.0, 1.0, 3.0, 2.0, 5.0];
+        assert_eq!(percentile_interval(&mut values, 0.5), Interval { low: 2.0, high: 4.0 });
+    }
+
+    #[test]
+    fn test_seeded_intervals_ignore_tie_order() {
+        let word_counts = WordAnalyzer::new().analyze("the the the cat cat dog dog was".split(' ').map(String::from).collect());
+        // The same counts with the tied words listed the other way round
+        let mut swapped = word_counts.clone();
+        swapped.swap(1, 2);
+        for (i, wc) in swapped.iter_mut().enumerate() {
+            wc.rank = i + 1;
+        }
+        assert_ne!(swapped[1].word, word_counts[1].word);
+
+        let original = bootstrap(&word_counts, None, &options(Resample::Token)).unwrap();
+        let reordered = bootstrap(&swapped, None, &options(Resample::Token)).unwrap();
+        assert_eq!(original.words, reordered.words);
+        assert_eq!(original.diversity, reordered.diversity);
+    }
+}
@@ -1,22 +1,544 @@
-use clap::Parser;
+use crate::bootstrap::{BootstrapOptions, Resample};
+use crate::code::{CodeLanguage, CodeOptions, Tokenizer};
+use crate::export::ExportFormat;
+use crate::filter::CrossDatasetFilter;
+use crate::input::{
+    CorpusOptions, DatePeriod, DecodeOptions, DirectoryLayout, InputFormat, MarkupOptions, PageRanges, PdfOptions, ReadOptions,
+    RecordOptions, SeriesOptions,
+};
+use crate::parser::Unit;
+use crate::plot::ChartFormat;
+use crate::sample::{SampleMode, SampleOptions};
+use crate::snapshot::ParseSettings;
+use crate::tokens::{ClassAction, TokenOptions};
+use anyhow::Context;
+use clap::{Args as ClapArgs, Parser, Subcommand, ValueEnum};
+use regex::Regex;
 
 #[derive(Parser, Debug)]
 #[command(name = "zipfr")]
 #[command(about = "A Zipfian text analysis tool with TUI interface")]
 #[command(version = "0.1.0")]
+#[command(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 pub struct Args {
-    #[arg(help = "Path(s) to the text file(s) to analyze", required = true)]
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Running `zipfr FILES...` without a subcommand is the same as `zipfr analyze FILES...`
+    #[command(flatten)]
+    pub analyze: AnalyzeArgs,
+
+    #[arg(long = "tags", global = true, default_value = "tags.toml", help = "Tag configuration file (ignored if missing)")]
+    pub tags_config: String,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Analyze word frequencies in the interactive TUI or as a text report (default)
+    Analyze(AnalyzeArgs),
+    /// Compare the top words of several datasets side by side
+    Compare(CompareArgs),
+    /// Fit a Zipf power law to each dataset's rank/frequency distribution
+    Fit(FitArgs),
+    /// Count word n-grams (bigrams, trigrams, ...)
+    Ngrams(NgramArgs),
+    /// List configured tags, with tag coverage per dataset when files are given
+    Tags(TagsArgs),
+    /// Export full frequency tables for further processing
+    Export(ExportArgs),
+    /// Render the rank/frequency chart to an SVG or PNG file
+    ExportChart(ChartArgs),
+}
+
+/// Inputs shared by every subcommand that reads text.
+#[derive(ClapArgs, Debug, Clone)]
+pub struct InputArgs {
+    #[arg(help = "Text files, directories, glob patterns or .zipfr snapshots to analyze", required_unless_present = "merges")]
     pub files: Vec<String>,
 
-    #[arg(short, long, help = "Display top N words", default_value = "20")]
-    pub top: usize,
+    #[arg(short = 'n', long = "name", help = "Custom names for datasets (one per dataset, overrides filenames)")]
+    pub names: Vec<String>,
+
+    #[arg(short, long, help = "Read directory inputs recursively")]
+    pub recursive: bool,
+
+    #[arg(long = "include", value_name = "GLOB", help = "Only read files in directories matching this glob, e.g. '*.txt' (repeatable)")]
+    pub includes: Vec<String>,
+
+    #[arg(long = "exclude", value_name = "GLOB", help = "Skip files and directories matching this glob (repeatable)")]
+    pub excludes: Vec<String>,
+
+    #[arg(long, value_enum, default_value = "tree", help = "Datasets made from a directory: the whole tree, each top-level subdirectory or each file")]
+    pub dataset_per: DirectoryLayout,
+
+    #[arg(long, help = "Language of the input(s): 'auto' to detect per file, 'none' to disable, or a code like 'fr'/'fra'", default_value = "auto")]
+    pub language: String,
+
+    #[arg(long, default_value = "auto", help = "Text encoding of the input(s): 'auto' (BOM, UTF-8, then detection) or a label like 'latin1', 'windows-1252', 'utf-16le'")]
+    pub encoding: String,
+
+    #[arg(long, help = "Replace invalid byte sequences instead of failing, and report how many were replaced")]
+    pub lossy: bool,
+
+    #[arg(long, value_enum, default_value = "auto", help = "Input format; 'auto' picks it by file extension (.html, .md, .xml, .epub, .docx, .odt, .pdf, .csv, .tsv, .json, .jsonl), plain text otherwise")]
+    pub input_format: InputFormat,
+
+    #[arg(long, help = "Read each EPUB chapter as a separate dataset")]
+    pub epub_chapters: bool,
+
+    #[arg(long, help = "Leave code blocks and inline code out of Markdown input")]
+    pub skip_code: bool,
+
+    #[arg(long = "xml-element", value_name = "NAME", help = "Only count text inside these XML elements, e.g. 'p' or 'chapter/p' (repeatable)")]
+    pub xml_elements: Vec<String>,
+
+    #[arg(long, value_name = "RANGES", help = "Only read these PDF pages, e.g. '1-10,15,20-'")]
+    pub pages: Option<PageRanges>,
+
+    #[arg(long, help = "Remove running headers and footers (lines repeated at the top or bottom of PDF pages)")]
+    pub strip_headers: bool,
+
+    #[arg(long, value_name = "NAME", help = "CSV/TSV column holding the text, by header name or 1-based number")]
+    pub column: Option<String>,
+
+    #[arg(long, value_name = "PATH", help = "JSON/JSONL field holding the text: a path like '.body' or 'user.bio', or a pointer like '/body'")]
+    pub field: Option<String>,
+
+    #[arg(long, value_name = "NAME", help = "Split CSV/TSV/JSON records into one dataset per value of this column or field")]
+    pub group_by: Option<String>,
+
+    #[arg(long, value_name = "NAME", help = "Split CSV/TSV/JSON records into one dataset per date in this column or field, oldest first")]
+    pub date_field: Option<String>,
+
+    #[arg(long, value_enum, default_value = "month", help = "Date bucket for --date-field")]
+    pub period: DatePeriod,
+
+    #[arg(long, value_name = "N", help = "Split each input into N segments of equal word count, one dataset each")]
+    pub segments: Option<usize>,
+
+    #[arg(long, value_name = "REGEX", help = "Split each input at lines matching this pattern (e.g. '^CHAPTER '), one dataset per part")]
+    pub split_on: Option<String>,
+
+    #[arg(long, value_enum, default_value = "words", help = "Tokens to count: natural-language words, or identifiers in source code")]
+    pub tokenizer: Tokenizer,
+
+    #[arg(long, default_value = "auto", help = "Source language for --tokenizer code: 'auto' by file extension, or a name like 'rust', 'python', 'js'")]
+    pub code_language: String,
+
+    #[arg(long, help = "Leave string literals out of source code (--tokenizer code)")]
+    pub skip_strings: bool,
+
+    #[arg(long, help = "Leave comments out of source code (--tokenizer code)")]
+    pub skip_comments: bool,
+
+    #[arg(long, help = "Keep identifiers whole instead of splitting camelCase, snake_case and kebab-case (--tokenizer code)")]
+    pub whole_identifiers: bool,
+
+    #[arg(long, default_value = "word", value_name = "UNIT", help = "What to count: 'word', 'char' (letters), 'charngram:N' (N-letter runs within words) or 'grapheme' (letters with their combining marks)")]
+    pub unit: Unit,
+
+    #[arg(long, value_enum, default_value = "keep", value_name = "ACTION", help = "Numbers (2024, 3.14, 50%): keep, drop or normalize to <NUM>; digits inside words (covid19) are kept unless dropped")]
+    pub numbers: ClassAction,
+
+    #[arg(long, value_enum, default_value = "drop", value_name = "ACTION", help = "URLs (https://..., www....): keep, drop or normalize to <URL>")]
+    pub urls: ClassAction,
+
+    #[arg(long, value_enum, default_value = "drop", value_name = "ACTION", help = "Email addresses: keep, drop or normalize to <EMAIL>")]
+    pub emails: ClassAction,
+
+    #[arg(long, value_enum, default_value = "drop", value_name = "ACTION", help = "@mentions: keep, drop or normalize to <MENTION>")]
+    pub mentions: ClassAction,
+
+    #[arg(long, value_enum, default_value = "drop", value_name = "ACTION", help = "#hashtags: keep, drop or normalize to <HASHTAG>")]
+    pub hashtags: ClassAction,
+
+    #[arg(long, value_enum, default_value = "drop", value_name = "ACTION", help = "Emoji: keep, drop or normalize to <EMOJI>")]
+    pub emoji: ClassAction,
+
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..), help = "Analyze a sample of N tokens of each dataset, to compare texts of different sizes")]
+    pub sample_tokens: Option<u32>,
+
+    #[arg(long, value_enum, default_value = "random", value_name = "MODE", help = "How --sample-tokens picks tokens: random (kept in text order) or contiguous (one run from a random start)")]
+    pub sample: SampleMode,
+
+    #[arg(long, help = "Add expected unique words at common sample sizes (rarefaction) to text reports and exports")]
+    pub rarefaction: bool,
+
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(2..), help = "Resample each dataset N times for confidence intervals (e.g. 1000)")]
+    pub bootstrap: Option<u32>,
+
+    #[arg(long, value_enum, default_value = "token", value_name = "UNIT", help = "What --bootstrap draws with replacement: single tokens, or chunks of --chunk-size consecutive tokens")]
+    pub resample: Resample,
+
+    #[arg(long, default_value = "1000", value_name = "N", value_parser = clap::value_parser!(u32).range(1..), help = "Tokens per chunk for --resample chunk")]
+    pub chunk_size: u32,
+
+    #[arg(long, default_value = "0.95", value_name = "LEVEL", value_parser = parse_confidence, help = "Coverage of --bootstrap intervals, between 0 and 1")]
+    pub confidence: f64,
+
+    #[arg(long, help = "Random seed for --sample-tokens and --bootstrap, for reproducible results")]
+    pub seed: Option<u64>,
+
+    #[arg(long, help = "Save each analyzed text file as a snapshot (FILE.zipfr) for fast reloading")]
+    pub save_snapshot: bool,
+
+    #[arg(long = "merge", value_name = "[NAME=]FILE,FILE...", help = "Combine files into one dataset by summing their counts (repeatable)")]
+    pub merges: Vec<String>,
+
+    #[arg(long = "difference", value_name = "A,B", help = "Add a dataset of A's counts minus B's (dataset names, repeatable)")]
+    pub differences: Vec<String>,
+
+    #[arg(long = "ratio", value_name = "A,B", help = "Add a dataset of A's relative frequencies divided by B's, x100 (repeatable)")]
+    pub ratios: Vec<String>,
+}
+
+/// Where and how much to write. Every subcommand writes its report to stdout
+/// unless `--output` is given, and `--top` always limits the rows per dataset.
+#[derive(ClapArgs, Debug, Clone)]
+pub struct OutputArgs {
+    #[arg(short, long, help = "Limit output to the top N words per dataset")]
+    pub top: Option<usize>,
+
+    #[arg(short, long, help = "Write the report to a file instead of stdout")]
+    pub output: Option<String>,
+}
+
+impl InputArgs {
+    pub fn corpus_options(&self) -> anyhow::Result<CorpusOptions> {
+        CorpusOptions::new(self.recursive, &self.includes, &self.excludes, self.dataset_per)
+    }
+
+    pub fn read_options(&self) -> anyhow::Result<ReadOptions> {
+        if self.tokenizer == Tokenizer::Code && self.unit != Unit::Word {
+            anyhow::bail!("--unit {} counts letters of words; it cannot be combined with --tokenizer code", self.unit);
+        }
+        Ok(ReadOptions {
+            decode: DecodeOptions::new(&self.encoding, self.lossy)?,
+            format: self.input_format,
+            markup: MarkupOptions { skip_code: self.skip_code, xml_elements: self.xml_elements.clone() },
+            pdf: PdfOptions { pages: self.pages.clone(), strip_running_lines: self.strip_headers },
+            records: RecordOptions {
+                column: self.column.clone(),
+                field: self.field.clone(),
+                group_by: self.group_by.clone(),
+                date_field: self.date_field.clone(),
+                period: self.period,
+            },
+            series: self.series_options()?,
+            code: match self.tokenizer {
+                Tokenizer::Words => None,
+                Tokenizer::Code => Some(CodeOptions {
+                    language: match self.code_language.as_str() {
+                        "auto" => None,
+                        name => Some(CodeLanguage::from_name(name)?),
+                    },
+                    skip_strings: self.skip_strings,
+                    skip_comments: self.skip_comments,
+                    split_identifiers: !self.whole_identifiers,
+                }),
+            },
+            unit: self.unit,
+            tokens: self.token_options(),
+        })
+    }
+
+    /// Everything recorded in a snapshot that changes the counts.
+    pub fn parse_settings(&self, ngram_size: usize) -> ParseSettings {
+        ParseSettings {
+            ngram_size,
+            unit: self.unit.to_string(),
+            tokens: self.token_options().to_string(),
+            encoding: self.encoding.clone(),
+            lossy: self.lossy,
+            format: value_name(self.input_format),
+            epub_chapters: self.epub_chapters,
+            skip_code: self.skip_code,
+            xml_elements: self.xml_elements.clone(),
+            pages: self.pages.as_ref().map(ToString::to_string),
+            strip_headers: self.strip_headers,
+            column: self.column.clone(),
+            field: self.field.clone(),
+            group_by: self.group_by.clone(),
+            date_field: self.date_field.clone(),
+            period: value_name(self.period),
+            segments: self.segments,
+            split_on: self.split_on.clone(),
+            tokenizer: value_name(self.tokenizer),
+            code_language: self.code_language.clone(),
+            skip_strings: self.skip_strings,
+            skip_comments: self.skip_comments,
+            whole_identifiers: self.whole_identifiers,
+        }
+    }
+
+    fn series_options(&self) -> anyhow::Result<SeriesOptions> {
+        if self.segments.is_some() && self.split_on.is_some() {
+            anyhow::bail!("Use either --segments or --split-on, not both");
+        }
+        if self.segments == Some(0) {
+            anyhow::bail!("--segments needs at least one segment");
+        }
+        let split_on = self
+            .split_on
+            .as_deref()
+            .map(|pattern| Regex::new(pattern).with_context(|| format!("Invalid --split-on pattern '{}'", pattern)))
+            .transpose()?;
+        Ok(SeriesOptions { segments: self.segments, split_on })
+    }
+
+    pub fn sample_options(&self) -> Option<SampleOptions> {
+        self.sample_tokens.map(|tokens| SampleOptions { tokens: tokens as usize, mode: self.sample, seed: self.seed })
+    }
+
+    pub fn bootstrap_options(&self) -> Option<BootstrapOptions> {
+        self.bootstrap.map(|samples| BootstrapOptions {
+            samples: samples as usize,
+            resample: self.resample,
+            chunk_size: self.chunk_size as usize,
+            confidence: self.confidence,
+            seed: self.seed,
+        })
+    }
+
+    pub fn token_options(&self) -> TokenOptions {
+        TokenOptions {
+            numbers: self.numbers,
+            urls: self.urls,
+            emails: self.emails,
+            mentions: self.mentions,
+            hashtags: self.hashtags,
+            emoji: self.emoji,
+        }
+    }
+}
+
+/// The command-line spelling of an option value, e.g. `code` for `Tokenizer::Code`.
+fn value_name(value: impl ValueEnum) -> String {
+    value.to_possible_value().map(|value| value.get_name().to_string()).unwrap_or_default()
+}
+
+fn parse_confidence(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(level) if level > 0.0 && level < 1.0 => Ok(level),
+        _ => Err(format!("expected a level between 0 and 1, like 0.95, got '{}'", value)),
+    }
+}
+
+impl OutputArgs {
+    /// Row limit, falling back to the subcommand's own default.
+    pub fn top_or(&self, default: usize) -> usize {
+        self.top.unwrap_or(default)
+    }
+}
+
+/// Word filters for non-interactive output, mirroring the TUI filter keys.
+#[derive(ClapArgs, Debug, Clone)]
+pub struct FilterArgs {
+    #[arg(long = "exclude-tag", value_name = "TAG", help = "Exclude words with this tag (name or letter, repeatable)")]
+    pub exclude_tags: Vec<String>,
+
+    #[arg(long = "include-tag", value_name = "TAG", help = "Keep only words with any of these tags (name or letter, repeatable)")]
+    pub include_tags: Vec<String>,
+
+    #[arg(long, help = "Exclude words that occur only once")]
+    pub exclude_single: bool,
+
+    #[arg(long, value_enum, default_value = "none", help = "Keep only words common to all datasets or unique to each")]
+    pub cross_dataset: CrossDatasetFilter,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+pub struct AnalyzeArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
+    #[arg(long, value_name = "FILE", help = "Also write a self-contained HTML report (e.g. report.html)")]
+    pub report: Option<String>,
 
     #[arg(long, help = "Disable interactive TUI mode (use CLI output)")]
     pub no_interactive: bool,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+pub struct CompareArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+pub struct FitArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
 
-    #[arg(short, long, help = "Output results to file")]
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+pub struct NgramArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    #[arg(short = 'N', long = "size", default_value = "2", value_parser = clap::value_parser!(u16).range(1..), help = "Number of words per n-gram")]
+    pub size: u16,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+// Files are optional here: without them only the configured tags are listed
+#[command(mut_arg("files", |arg| arg.required_unless_present(clap::builder::Resettable::Reset).help("Optional inputs to report tag coverage for")))]
+pub struct TagsArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    #[arg(short, long, help = "Write the report to a file instead of stdout")]
     pub output: Option<String>,
+}
 
-    #[arg(short = 'n', long = "name", help = "Custom names for datasets (one per file, overrides filenames)")]
-    pub names: Vec<String>,
-}
\ No newline at end of file
+#[derive(ClapArgs, Debug, Clone)]
+pub struct ExportArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
+    #[arg(short, long, value_enum, help = "Output format [default: from the --output extension, else csv]")]
+    pub format: Option<ExportFormat>,
+
+    #[arg(long, help = "With --format latex, add a pgfplots log-log figure after the table")]
+    pub pgfplots: bool,
+}
+
+#[derive(ClapArgs, Debug, Clone)]
+pub struct ChartArgs {
+    #[command(flatten)]
+    pub input: InputArgs,
+
+    #[command(flatten)]
+    pub output: OutputArgs,
+
+    #[command(flatten)]
+    pub filter: FilterArgs,
+
+    #[arg(short, long, value_enum, help = "Image format [default: from the --output extension, else svg]")]
+    pub format: Option<ChartFormat>,
+
+    #[arg(long, help = "Use log-log axes")]
+    pub log: bool,
+
+    #[arg(long, help = "Draw the ideal Zipf line (C / rank, C = top word's count) for each dataset")]
+    pub zipf: bool,
+
+    #[arg(long, help = "Plot percentages of each dataset's total words instead of raw counts")]
+    pub percentage: bool,
+
+    #[arg(long, help = "Draw each word's frequency interval from --bootstrap as a whisker")]
+    pub whiskers: bool,
+
+    #[arg(long, default_value = "800", value_parser = clap::value_parser!(u32).range(100..), help = "Image width in pixels")]
+    pub width: u32,
+
+    #[arg(long, default_value = "500", value_parser = clap::value_parser!(u32).range(100..), help = "Image height in pixels")]
+    pub height: u32,
+
+    #[arg(long, help = "Chart title")]
+    pub title: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_invocation_is_analyze() {
+        let args = Args::try_parse_from(["zipfr", "a.txt", "b.txt", "--top", "5", "--no-interactive"]).unwrap();
+        assert!(args.command.is_none());
+        assert_eq!(args.analyze.input.files, vec!["a.txt", "b.txt"]);
+        assert_eq!(args.analyze.output.top, Some(5));
+        assert!(args.analyze.no_interactive);
+
+        let args = Args::try_parse_from(["zipfr", "a.txt", "--report", "report.html", "--exclude-tag", "S"]).unwrap();
+        assert_eq!(args.analyze.report.as_deref(), Some("report.html"));
+        assert_eq!(args.analyze.filter.exclude_tags, vec!["S"]);
+
+        let args = Args::try_parse_from(["zipfr", "corpus/", "-r", "--include", "*.txt", "--dataset-per", "subdir"]).unwrap();
+        assert!(args.analyze.input.recursive);
+        assert_eq!(args.analyze.input.includes, vec!["*.txt"]);
+        assert_eq!(args.analyze.input.dataset_per, DirectoryLayout::Subdir);
+    }
+
+    #[test]
+    fn test_subcommands() {
+        let args = Args::try_parse_from(["zipfr", "ngrams", "-N", "3", "a.txt"]).unwrap();
+        match args.command {
+            Some(Command::Ngrams(ngrams)) => {
+                assert_eq!(ngrams.size, 3);
+                assert_eq!(ngrams.input.files, vec!["a.txt"]);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        let args = Args::try_parse_from(["zipfr", "tags", "--tags", "custom.toml"]).unwrap();
+        assert!(matches!(args.command, Some(Command::Tags(_))));
+        assert_eq!(args.tags_config, "custom.toml");
+
+        // Inputs are optional for `tags`, and take the same options as everywhere else
+        let args = Args::try_parse_from(["zipfr", "tags", "--language", "fr", "a.txt"]).unwrap();
+        match args.command {
+            Some(Command::Tags(tags)) => {
+                assert_eq!(tags.input.language, "fr");
+                assert_eq!(tags.input.files, vec!["a.txt"]);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        assert!(Args::try_parse_from(["zipfr", "fit"]).is_err());
+
+        let args = Args::try_parse_from(["zipfr", "fit", "--merge", "dickens=a.txt,b.txt", "--difference", "dickens,c"]).unwrap();
+        match args.command {
+            Some(Command::Fit(fit)) => {
+                assert!(fit.input.files.is_empty());
+                assert_eq!(fit.input.merges, vec!["dickens=a.txt,b.txt"]);
+                assert_eq!(fit.input.differences, vec!["dickens,c"]);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        let args = Args::try_parse_from([
+            "zipfr", "export", "a.txt", "--format", "ndjson", "--exclude-tag", "S", "--cross-dataset", "common",
+        ]).unwrap();
+        match args.command {
+            Some(Command::Export(export)) => {
+                assert_eq!(export.format, Some(ExportFormat::Ndjson));
+                assert_eq!(export.filter.exclude_tags, vec!["S"]);
+                assert_eq!(export.filter.cross_dataset, CrossDatasetFilter::CommonOnly);
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+
+        let args = Args::try_parse_from(["zipfr", "export-chart", "a.txt", "--log", "--width", "1200", "-o", "chart.png"]).unwrap();
+        match args.command {
+            Some(Command::ExportChart(chart)) => {
+                assert!(chart.log && !chart.zipf);
+                assert_eq!((chart.width, chart.height), (1200, 500));
+                assert_eq!(chart.output.output.as_deref(), Some("chart.png"));
+            }
+            other => panic!("unexpected command: {:?}", other),
+        }
+    }
+}
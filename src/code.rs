@@ -0,0 +1,440 @@
+use crate::input::strip_compression_extension;
+use anyhow::{bail, Result};
+use clap::ValueEnum;
+use std::path::Path;
+
+/// How parsed text is split into tokens.
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum Tokenizer {
+    #[default]
+    Words, // Alphabetic words, lowercased
+    Code,  // Source-code identifiers
+}
+
+/// Lexical rules of a programming language, enough to find identifiers, comments and strings.
+#[derive(Debug, PartialEq)]
+pub struct CodeLanguage {
+    pub name: &'static str,
+    extensions: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comments: &'static [(&'static str, &'static str)],
+    strings: &'static [&'static str], // Longest delimiters first (`"""` before `"`)
+    char_literals: bool,              // `'x'` is a character, `'a` a lifetime or label
+    kebab_case: bool,                 // `-` joins identifiers (`with-open`)
+    keywords: &'static str,
+    keywords_ignore_case: bool,       // `SELECT` and `select` are the same keyword
+}
+
+const C_COMMENTS: &[(&str, &str)] = &[("/*", "*/")];
+
+/// Languages with bundled keyword lists.
+const LANGUAGES: &[CodeLanguage] = &[
+    CodeLanguage {
+        name: "rust",
+        extensions: &["rs"],
+        line_comments: &["//"],
+        block_comments: C_COMMENTS,
+        strings: &["\""],
+        char_literals: true,
+        kebab_case: false,
+        keywords: include_str!("keywords/rust.txt"),
+        keywords_ignore_case: false,
+    },
+    CodeLanguage {
+        name: "python",
+        extensions: &["py", "pyi", "pyw"],
+        line_comments: &["#"],
+        block_comments: &[],
+        strings: &["\"\"\"", "'''", "\"", "'"],
+        char_literals: false,
+        kebab_case: false,
+        keywords: include_str!("keywords/python.txt"),
+        keywords_ignore_case: false,
+    },
+    CodeLanguage {
+        name: "javascript",
+        extensions: &["js", "jsx", "mjs", "cjs"],
+        line_comments: &["//"],
+        block_comments: C_COMMENTS,
+        strings: &["\"", "'", "`"],
+        char_literals: false,
+        kebab_case: false,
+        keywords: include_str!("keywords/javascript.txt"),
+        keywords_ignore_case: false,
+    },
+    CodeLanguage {
+        name: "typescript",
+        extensions: &["ts", "tsx", "mts", "cts"],
+        line_comments: &["//"],
+        block_comments: C_COMMENTS,
+        strings: &["\"", "'", "`"],
+        char_literals: false,
+        kebab_case: false,
+        keywords: include_str!("keywords/typescript.txt"),
+        keywords_ignore_case: false,
+    },
+    CodeLanguage {
+        name: "go",
+        extensions: &["go"],
+        line_comments: &["//"],
+        block_comments: C_COMMENTS,
+        strings: &["\"", "`"],
+        char_literals: true,
+        kebab_case: false,
+        keywords: include_str!("keywords/go.txt"),
+        keywords_ignore_case: false,
+    },
+    CodeLanguage {
+        name: "java",
+        extensions: &["java"],
+        line_comments: &["//"],
+        block_comments: C_COMMENTS,
+        strings: &["\"\"\"", "\""],
+        char_literals: true,
+        kebab_case: false,
+        keywords: include_str!("keywords/java.txt"),
+        keywords_ignore_case: false,
+    },
+    CodeLanguage {
+        name: "c",
+        extensions: &["c", "h"],
+        line_comments: &["//"],
+        block_comments: C_COMMENTS,
+        strings: &["\""],
+        char_literals: true,
+        kebab_case: false,
+        keywords: include_str!("keywords/c.txt"),
+        keywords_ignore_case: false,
+    },
+    CodeLanguage {
+        name: "cpp",
+        extensions: &["cpp", "cc", "cxx", "hpp", "hh", "hxx"],
+        line_comments: &["//"],
+        block_comments: C_COMMENTS,
+        strings: &["\""],
+        char_literals: true,
+        kebab_case: false,
+        keywords: include_str!("keywords/cpp.txt"),
+        keywords_ignore_case: false,
+    },
+    CodeLanguage {
+        name: "csharp",
+        extensions: &["cs"],
+        line_comments: &["//"],
+        block_comments: C_COMMENTS,
+        strings: &["\"\"\"", "\""],
+        char_literals: true,
+        kebab_case: false,
+        keywords: include_str!("keywords/csharp.txt"),
+        keywords_ignore_case: false,
+    },
+    CodeLanguage {
+        name: "ruby",
+        extensions: &["rb", "rake"],
+        line_comments: &["#"],
+        block_comments: &[],
+        strings: &["\"", "'"],
+        char_literals: false,
+        kebab_case: false,
+        keywords: include_str!("keywords/ruby.txt"),
+        keywords_ignore_case: false,
+    },
+    CodeLanguage {
+        name: "shell",
+        extensions: &["sh", "bash", "zsh"],
+        line_comments: &["#"],
+        block_comments: &[],
+        strings: &["\"", "'"],
+        char_literals: false,
+        kebab_case: false,
+        keywords: include_str!("keywords/shell.txt"),
+        keywords_ignore_case: false,
+    },
+    CodeLanguage {
+        name: "clojure",
+        extensions: &["clj", "cljs", "cljc", "edn"],
+        line_comments: &[";"],
+        block_comments: &[],
+        strings: &["\""],
+        char_literals: false,
+        kebab_case: true,
+        keywords: include_str!("keywords/clojure.txt"),
+        keywords_ignore_case: false,
+    },
+    CodeLanguage {
+        name: "sql",
+        extensions: &["sql"],
+        line_comments: &["--"],
+        block_comments: C_COMMENTS,
+        strings: &["'"], // Double quotes delimit identifiers
+        char_literals: false,
+        kebab_case: false,
+        keywords: include_str!("keywords/sql.txt"),
+        keywords_ignore_case: true,
+    },
+];
+
+/// Fallback for unknown extensions: the common comment and string syntaxes, no keywords.
+const GENERIC: CodeLanguage = CodeLanguage {
+    name: "generic",
+    extensions: &[],
+    line_comments: &["//", "#"],
+    block_comments: C_COMMENTS,
+    strings: &["\"", "'", "`"],
+    char_literals: false,
+    kebab_case: false,
+    keywords: "",
+    keywords_ignore_case: false,
+};
+
+/// What a comment or string literal spans.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Literal {
+    Comment,
+    String,
+}
+
+impl CodeLanguage {
+    /// Look a language up by name or file extension (`rust`, `rs`).
+    pub fn from_name(name: &str) -> Result<&'static CodeLanguage> {
+        let name = name.to_ascii_lowercase();
+        if name == GENERIC.name {
+            return Ok(&GENERIC);
+        }
+        match LANGUAGES.iter().find(|language| language.name == name || language.extensions.contains(&name.as_str())) {
+            Some(language) => Ok(language),
+            None => {
+                let names: Vec<&str> = LANGUAGES.iter().map(|language| language.name).collect();
+                bail!("Unknown code language '{}' (known: {}, generic)", name, names.join(", "))
+            }
+        }
+    }
+
+    /// Language of a source file by extension, ignoring a compression extension.
+    pub fn for_path(path: &str) -> &'static CodeLanguage {
+        let extension = Path::new(strip_compression_extension(path))
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        extension
+            .and_then(|extension| LANGUAGES.iter().find(|language| language.extensions.contains(&extension.as_str())))
+            .unwrap_or(&GENERIC)
+    }
+
+    /// Bundled keywords of this language.
+    pub fn keywords(&self) -> Vec<&'static str> {
+        self.keywords
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .flat_map(str::split_whitespace)
+            .collect()
+    }
+
+    /// Whether keywords match in any case (SQL) rather than only as written.
+    pub fn keywords_ignore_case(&self) -> bool {
+        self.keywords_ignore_case
+    }
+
+    /// The comment or string literal starting at `position`, with the byte offset just past it.
+    fn literal_at(&self, text: &str, position: usize) -> Option<(usize, Literal)> {
+        let rest = &text[position..];
+
+        for marker in self.line_comments {
+            // `#` only opens a comment at a word boundary (not in `$#` or `a#b`)
+            let boundary = *marker != "#" || text[..position].chars().next_back().is_none_or(char::is_whitespace);
+            if rest.starts_with(marker) && boundary {
+                return Some((rest.find('\n').map_or(text.len(), |end| position + end), Literal::Comment));
+            }
+        }
+        for (open, close) in self.block_comments {
+            if rest.starts_with(open) {
+                let body = position + open.len();
+                let end = text[body..].find(close).map_or(text.len(), |end| body + end + close.len());
+                return Some((end, Literal::Comment));
+            }
+        }
+        for delimiter in self.strings {
+            if rest.starts_with(delimiter) {
+                return Some((string_end(text, position + delimiter.len(), delimiter), Literal::String));
+            }
+        }
+        if self.char_literals && rest.starts_with('\'') {
+            return char_literal_len(rest).map(|length| (position + length, Literal::String));
+        }
+        None
+    }
+}
+
+/// Source-code tokenizer settings.
+#[derive(Debug, Clone, Default)]
+pub struct CodeOptions {
+    pub language: Option<&'static CodeLanguage>, // None: by file extension
+    pub skip_strings: bool,
+    pub skip_comments: bool,
+    pub split_identifiers: bool, // `parseHttpUrl`, `max_line_len` → subwords
+}
+
+impl CodeOptions {
+    pub fn language_for(&self, path: &str) -> &'static CodeLanguage {
+        self.language.unwrap_or_else(|| CodeLanguage::for_path(path))
+    }
+}
+
+/// Identifiers of a source file in order. Split subwords are lowercased; whole identifiers
+/// keep their case. Numbers and identifiers without letters are dropped.
+pub fn tokenize(text: &str, language: &CodeLanguage, options: &CodeOptions) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut identifier_start: Option<usize> = None;
+    let mut position = 0;
+
+    while let Some(c) = text[position..].chars().next() {
+        let kebab = language.kebab_case
+            && c == '-'
+            && identifier_start.is_some()
+            && text[position + 1..].chars().next().is_some_and(char::is_alphabetic);
+        if c.is_alphanumeric() || c == '_' || kebab {
+            identifier_start.get_or_insert(position);
+            position += c.len_utf8();
+            continue;
+        }
+
+        let literal = language.literal_at(text, position);
+        if let Some(start) = identifier_start.take() {
+            let identifier = &text[start..position];
+            // String prefixes (r"", b'', f"", u8"") are not identifiers
+            let prefix = matches!(literal, Some((_, Literal::String)))
+                && identifier.len() <= 2
+                && identifier.chars().all(|c| "rRbBfFuUL8".contains(c));
+            if !prefix {
+                push_identifier(identifier, options, &mut tokens);
+            }
+        }
+
+        match literal {
+            Some((end, kind)) => {
+                let skipped = match kind {
+                    Literal::Comment => options.skip_comments,
+                    Literal::String => options.skip_strings,
+                };
+                if !skipped {
+                    // Words inside comments and strings count like identifiers
+                    text[position..end]
+                        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+                        .for_each(|word| push_identifier(word, options, &mut tokens));
+                }
+                position = end;
+            }
+            None => position += c.len_utf8(),
+        }
+    }
+    if let Some(start) = identifier_start {
+        push_identifier(&text[start..], options, &mut tokens);
+    }
+
+    tokens
+}
+
+fn push_identifier(identifier: &str, options: &CodeOptions, tokens: &mut Vec<String>) {
+    if identifier.chars().next().is_none_or(|c| c.is_numeric()) || !identifier.chars().any(char::is_alphabetic) {
+        return;
+    }
+    if !options.split_identifiers {
+        tokens.push(identifier.to_string());
+        return;
+    }
+    tokens.extend(
+        split_identifier(identifier)
+            .into_iter()
+            .filter(|part| part.chars().any(char::is_alphabetic))
+            .map(str::to_lowercase),
+    );
+}
+
+/// Split on `_` and `-`, then at case changes: `parseHTTPResponse2` → parse, HTTP, Response2.
+fn split_identifier(identifier: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    for piece in identifier.split(['_', '-']).filter(|piece| !piece.is_empty()) {
+        let chars: Vec<(usize, char)> = piece.char_indices().collect();
+        let mut start = 0;
+        for k in 1..chars.len() {
+            let (index, c) = chars[k];
+            let previous = chars[k - 1].1;
+            let next = chars.get(k + 1).map(|&(_, next)| next);
+            let boundary = ((previous.is_lowercase() || previous.is_numeric()) && c.is_uppercase())
+                || (previous.is_uppercase() && c.is_uppercase() && next.is_some_and(char::is_lowercase));
+            if boundary {
+                parts.push(&piece[start..index]);
+                start = index;
+            }
+        }
+        parts.push(&piece[start..]);
+    }
+    parts
+}
+
+/// End of a string body starting at `body`, honouring backslash escapes; unterminated strings run to the end.
+fn string_end(text: &str, body: usize, close: &str) -> usize {
+    let mut chars = text[body..].char_indices();
+    while let Some((i, c)) = chars.next() {
+        if c == '\\' {
+            chars.next();
+        } else if text[body + i..].starts_with(close) {
+            return body + i + close.len();
+        }
+    }
+    text.len()
+}
+
+/// Length of a character literal (`'x'`, `'\n'`, `'\u{1F600}'`), or None for a lifetime or label.
+fn char_literal_len(rest: &str) -> Option<usize> {
+    let mut chars = rest.char_indices().skip(1);
+    match chars.next()? {
+        (_, '\\') => {
+            chars.next()?;
+            chars.take(10).find(|&(_, c)| c == '\'').map(|(i, _)| i + 1)
+        }
+        (_, '\'') => None,
+        _ => match chars.next()? {
+            (i, '\'') => Some(i + 1),
+            _ => None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_tokenizer() {
+        let rust = CodeLanguage::from_name("rs").unwrap();
+        let source = "// Parse the file\nfn parse_file<'a>(path: &'a str) -> HttpResult { let c = '\\''; let s = r\"raw text\"; /* block */ 42 }";
+
+        let whole = CodeOptions { skip_strings: true, skip_comments: true, ..Default::default() };
+        assert_eq!(
+            tokenize(source, rust, &whole),
+            vec!["fn", "parse_file", "a", "path", "a", "str", "HttpResult", "let", "c", "let", "s"]
+        );
+
+        let split = CodeOptions { split_identifiers: true, ..Default::default() };
+        let tokens = tokenize(source, rust, &split);
+        assert_eq!(&tokens[..7], ["parse", "the", "file", "fn", "parse", "file", "a"]);
+        assert!(tokens.contains(&"http".to_string()) && tokens.contains(&"raw".to_string()) && tokens.contains(&"block".to_string()));
+        assert!(!tokens.contains(&"r".to_string()));
+
+        assert_eq!(split_identifier("parseHTTPResponse2"), vec!["parse", "HTTP", "Response2"]);
+        assert_eq!(split_identifier("utf8String__max_len"), vec!["utf8", "String", "max", "len"]);
+
+        let clojure = CodeLanguage::for_path("core.clj.gz");
+        assert_eq!(tokenize("(defn with-open-file [x] (- x 1)) ; done", clojure, &split), vec!["defn", "with", "open", "file", "x", "x", "done"]);
+        assert_eq!(tokenize("echo $# # comment", CodeLanguage::for_path("run.sh"), &whole), vec!["echo"]);
+
+        assert!(rust.keywords().contains(&"fn"));
+        assert!(!rust.keywords().iter().any(|keyword| keyword.starts_with('#')));
+        assert!(CodeLanguage::from_name("cobol").is_err());
+
+        let sql = CodeLanguage::for_path("schema.sql");
+        assert_eq!(tokenize("SELECT name -- who\nFROM \"users\" WHERE id = 'x';", sql, &whole), vec!["SELECT", "name", "FROM", "users", "WHERE", "id"]);
+        assert!(sql.keywords_ignore_case() && !rust.keywords_ignore_case());
+    }
+}
@@ -0,0 +1,148 @@
+use crate::analyzer::{language_tag_matcher, Dataset, DatasetKind, Tag, TagMatcher, WordCount};
+use crate::segment::SegmentStats;
+use std::collections::{HashMap, HashSet};
+
+/// Ratio datasets store `ratio × RATIO_SCALE` as each word's count (100 = same relative frequency).
+pub const RATIO_SCALE: f64 = 100.0;
+
+/// Sum the counts of several datasets into one, re-ranked. Words are tagged afresh by
+/// `tag_matcher`, with the stop words of the merged language, as if the sources were one text;
+/// the sources' own tags (say, French stop words in a French source) do not carry over.
+pub fn merge_datasets(name: &str, datasets: &[&Dataset], tag_matcher: Option<&TagMatcher>) -> Dataset {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for dataset in datasets {
+        for wc in &dataset.word_counts {
+            *counts.entry(wc.word.as_str()).or_default() += wc.count;
+        }
+    }
+
+    // Keep the language only when every source agrees on it
+    let language = datasets.first().and_then(|first| first.language.clone()).filter(|language| {
+        datasets
+            .iter()
+            .all(|d| d.language.as_ref().is_some_and(|other| other.code == language.code))
+    });
+
+    let tag_matcher = language_tag_matcher(tag_matcher, language.as_ref());
+    let mut dataset = ranked_dataset(
+        name,
+        counts.into_iter().map(|(word, count)| {
+            let tags = tag_matcher.as_ref().map(|matcher| matcher.get_tags(word)).unwrap_or_default();
+            (word.to_string(), count, tags)
+        }),
+    );
+    dataset.parse_duration = datasets.iter().map(|d| d.parse_duration).sum();
+    dataset.analyze_duration = datasets.iter().map(|d| d.analyze_duration).sum();
+    dataset.language = language;
+    // Sentence statistics add up when every source has them
+    dataset.segments = datasets.iter().try_fold(SegmentStats::default(), |mut segments, d| {
+        segments.merge(d.segments.as_ref()?);
+        Some(segments)
+    });
+    dataset
+}
+
+/// `a` minus `b`: each word's count in `a` less its count in `b`; words that drop to zero go.
+pub fn difference_dataset(a: &Dataset, b: &Dataset) -> Dataset {
+    let b_counts = counts_by_word(b);
+    let mut dataset = ranked_dataset(
+        &format!("{} - {}", a.name, b.name),
+        a.word_counts.iter().filter_map(|wc| {
+            let count = wc.count.saturating_sub(b_counts.get(wc.word.as_str()).copied().unwrap_or(0));
+            (count > 0).then(|| (wc.word.clone(), count, wc.tags.clone()))
+        }),
+    );
+    dataset.language = a.language.clone();
+    dataset
+}
+
+/// Relative frequency of each word of `a` divided by its relative frequency in `b`,
+/// scaled by `RATIO_SCALE` and rounded, so ratios below 0.005 score 0 but stay listed.
+/// Words missing from `b` count as half an occurrence there; words found only in `b` have
+/// no ratio and are counted in `unscored_words`. The scores stand in for counts, so the
+/// dataset is marked `DatasetKind::Ratio` and has no token total.
+pub fn ratio_dataset(a: &Dataset, b: &Dataset) -> Dataset {
+    let b_counts = counts_by_word(b);
+    let (a_total, b_total) = (a.total_words.max(1) as f64, b.total_words.max(1) as f64);
+
+    let mut dataset = ranked_dataset(
+        &format!("{} / {}", a.name, b.name),
+        a.word_counts.iter().map(|wc| {
+            let b_count = b_counts.get(wc.word.as_str()).map(|&count| count as f64).unwrap_or(0.5);
+            let ratio = (wc.count as f64 / a_total) / (b_count / b_total);
+            (wc.word.clone(), (ratio * RATIO_SCALE).round() as usize, wc.tags.clone())
+        }),
+    );
+    let a_counts = counts_by_word(a);
+    dataset.total_words = 0;
+    dataset.unscored_words = Some(b_counts.keys().filter(|word| !a_counts.contains_key(*word)).count());
+    dataset.language = a.language.clone();
+    dataset.kind = DatasetKind::Ratio;
+    dataset
+}
+
+fn counts_by_word(dataset: &Dataset) -> HashMap<&str, usize> {
+    dataset.word_counts.iter().map(|wc| (wc.word.as_str(), wc.count)).collect()
+}
+
+/// Build a dataset from (word, count, tags), ranked by count (ties alphabetically).
+fn ranked_dataset(name: &str, words: impl Iterator<Item = (String, usize, HashSet<Tag>)>) -> Dataset {
+    let mut word_counts: Vec<WordCount> = words
+        .map(|(word, count, tags)| WordCount { word, count, rank: 0, tags })
+        .collect();
+    word_counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.word.cmp(&b.word)));
+    for (index, wc) in word_counts.iter_mut().enumerate() {
+        wc.rank = index + 1;
+    }
+
+    Dataset::from_words(name, word_counts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_difference_and_ratio() {
+        let a = Dataset::from_text("a", "the the the cat cat dog");
+        let b = Dataset::from_text("b", "the cat bird bird bird");
+
+        let merged = merge_datasets("ab", &[&a, &b], None);
+        let words: Vec<(&str, usize, usize)> =
+            merged.word_counts.iter().map(|wc| (wc.word.as_str(), wc.count, wc.rank)).collect();
+        assert_eq!(words, vec![("the", 4, 1), ("bird", 3, 2), ("cat", 3, 3), ("dog", 1, 4)]);
+        assert_eq!((merged.total_words, merged.unique_words), (11, 4));
+
+        // Merged words are tagged by the current matcher; the sources' tags do not carry over
+        let matcher = TagMatcher::from_toml_str("[tags.animals]\nname = \"Animals\"\nwords = [\"cat\", \"bird\"]").unwrap();
+        let animals = matcher.get_tag_by_name("Animals").unwrap();
+        let stale = Tag { name: "Old".to_string(), letter: 'O', color: None, description: None };
+        let mut tagged = a.clone();
+        for wc in &mut tagged.word_counts {
+            wc.tags.insert(stale.clone());
+        }
+        let merged = merge_datasets("ab", &[&tagged, &b], Some(&matcher));
+        for wc in &merged.word_counts {
+            assert_eq!(wc.tags.contains(animals), wc.word == "cat" || wc.word == "bird");
+            assert!(!wc.tags.contains(&stale));
+        }
+
+        let difference = difference_dataset(&a, &b);
+        assert_eq!(difference.name, "a - b");
+        let words: Vec<(&str, usize)> = difference.word_counts.iter().map(|wc| (wc.word.as_str(), wc.count)).collect();
+        assert_eq!(words, vec![("the", 2), ("cat", 1), ("dog", 1)]);
+
+        let ratio = ratio_dataset(&a, &b);
+        let words: Vec<(&str, usize)> = ratio.word_counts.iter().map(|wc| (wc.word.as_str(), wc.count)).collect();
+        assert_eq!(words, vec![("the", 250), ("cat", 167), ("dog", 167)]);
+        assert!(ratio.is_ratio() && !difference.is_ratio());
+        assert_eq!(ratio.percentage(250), None);
+        assert_eq!((ratio.total_words, ratio.unscored_words), (0, Some(1)));
+
+        // Words far rarer in `a` score 0 but are still listed
+        let common = Dataset::from_text("b", &"rare ".repeat(300));
+        let ratio = ratio_dataset(&Dataset::from_text("a", &format!("rare {}", "word ".repeat(299))), &common);
+        let words: Vec<(&str, usize)> = ratio.word_counts.iter().map(|wc| (wc.word.as_str(), wc.count)).collect();
+        assert_eq!(words, vec![("word", 59800), ("rare", 0)]);
+    }
+}
@@ -0,0 +1,551 @@
+use crate::analyzer::{Dataset, WordCount};
+use crate::bootstrap::Interval;
+use crate::filter::FilterSet;
+use crate::sample::{rarefaction, sample_sizes, RarefactionPoint};
+use crate::stats::{diversity, word_lengths, Diversity, LengthStats};
+use crate::view::{ChartScope, NormalizationMode, ZipfState};
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::io::Write;
+use std::path::Path;
+
+/// Identifies the export schema; bump `SCHEMA_VERSION` on incompatible changes.
+pub const SCHEMA_NAME: &str = "zipfr-export";
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    Csv,
+    Tsv,
+    Json,
+    Ndjson,
+    Latex,
+    Markdown,
+}
+
+impl ExportFormat {
+    /// Guess the format from a file extension (`.csv`, `.tsv`, `.json`, `.ndjson`/`.jsonl`,
+    /// `.tex`, `.md`).
+    pub fn from_path(path: &str) -> Option<Self> {
+        let extension = Path::new(path).extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "csv" => Some(Self::Csv),
+            "tsv" | "tab" => Some(Self::Tsv),
+            "json" => Some(Self::Json),
+            "ndjson" | "jsonl" => Some(Self::Ndjson),
+            "tex" | "latex" => Some(Self::Latex),
+            "md" | "markdown" => Some(Self::Markdown),
+            _ => None,
+        }
+    }
+}
+
+/// What to export: datasets, the (filtered, re-ranked) word lists to write
+/// for each of them, and the filter that produced those lists.
+pub struct ExportContext<'a> {
+    pub datasets: &'a [Dataset],
+    pub words: &'a [Vec<WordCount>],
+    pub filter_set: &'a FilterSet,
+    pub top: Option<usize>,
+    pub view: Option<ExportView<'a>>, // Set when exporting from the TUI
+    pub pgfplots: bool,               // LaTeX only: add a log-log pgfplots figure
+    pub rarefaction: bool,            // JSON/NDJSON: add each dataset's rarefaction curve (--rarefaction)
+}
+
+/// TUI display state at export time, so the file matches what was on screen.
+#[derive(Serialize)]
+pub struct ExportView<'a> {
+    pub normalization: &'a NormalizationMode,
+    pub chart_scope: &'a ChartScope,
+    pub log_scale: bool,
+    pub zipf: &'a ZipfState,
+    /// Zipf fit ratio per word, aligned with `ExportContext::words` (only when Zipf is enabled)
+    #[serde(skip)]
+    pub fit_ratios: Vec<Vec<Option<f64>>>,
+}
+
+impl ExportContext<'_> {
+    fn rows(&self, dataset_index: usize) -> &[WordCount] {
+        let words = &self.words[dataset_index];
+        &words[..self.top.unwrap_or(usize::MAX).min(words.len())]
+    }
+
+    fn fit_ratio(&self, dataset_index: usize, word_index: usize) -> Option<f64> {
+        let view = self.view.as_ref().filter(|view| view.zipf.enabled)?;
+        view.fit_ratios.get(dataset_index)?.get(word_index).copied().flatten()
+    }
+
+    fn has_fit_ratios(&self) -> bool {
+        self.view.as_ref().is_some_and(|view| view.zipf.enabled)
+    }
+}
+
+#[derive(Serialize)]
+struct ExportDocument<'a> {
+    schema: &'static str,
+    version: u32,
+    filter: &'a FilterSet,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    view: Option<&'a ExportView<'a>>,
+    datasets: Vec<ExportDataset<'a>>,
+}
+
+#[derive(Serialize)]
+struct ExportDataset<'a> {
+    #[serde(flatten)]
+    summary: DatasetSummary<'a>,
+    words: Vec<ExportWord<'a>>,
+}
+
+#[derive(Serialize)]
+struct DatasetSummary<'a> {
+    #[serde(flatten)]
+    dataset: &'a Dataset,
+    total_words: Option<usize>,          // None for ratio datasets, whose scores are not tokens
+    filtered_total_words: Option<usize>, // Likewise
+    filtered_unique_words: usize,
+    word_lengths: LengthStats, // Of the filtered words
+    #[serde(skip_serializing_if = "Option::is_none")]
+    diversity: Option<Diversity>, // Of all words, like the bootstrap intervals; None for ratio datasets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rarefaction: Option<Vec<RarefactionPoint>>, // Expected unique words at common sample sizes, with --rarefaction
+}
+
+#[derive(Serialize)]
+struct ExportWord<'a> {
+    #[serde(flatten)]
+    word: &'a WordCount,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    percentage: Option<f64>, // Share of the dataset's unfiltered total words; None for ratio datasets
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fit_ratio: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    percentage_interval: Option<Interval>, // With --bootstrap
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rank_interval: Option<Interval>, // Among all the dataset's words
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum NdjsonRecord<'a> {
+    Export {
+        schema: &'static str,
+        version: u32,
+        filter: &'a FilterSet,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        view: Option<&'a ExportView<'a>>,
+    },
+    Dataset(&'a DatasetSummary<'a>),
+    Word {
+        dataset: &'a str,
+        #[serde(flatten)]
+        word: &'a ExportWord<'a>,
+    },
+}
+
+pub fn write_export(out: &mut impl Write, format: ExportFormat, context: &ExportContext) -> Result<()> {
+    match format {
+        ExportFormat::Csv => write_delimited(out, b',', context),
+        ExportFormat::Tsv => write_delimited(out, b'\t', context),
+        ExportFormat::Json => write_json(out, context),
+        ExportFormat::Ndjson => write_ndjson(out, context),
+        ExportFormat::Latex => write_latex(out, context),
+        ExportFormat::Markdown => write_markdown(out, context),
+    }
+}
+
+fn export_datasets<'a>(context: &'a ExportContext) -> Vec<ExportDataset<'a>> {
+    context
+        .datasets
+        .iter()
+        .enumerate()
+        .map(|(i, dataset)| ExportDataset {
+            summary: DatasetSummary {
+                dataset,
+                total_words: (!dataset.is_ratio()).then_some(dataset.total_words),
+                filtered_total_words: (!dataset.is_ratio()).then(|| context.words[i].iter().map(|wc| wc.count).sum()),
+                filtered_unique_words: context.words[i].len(),
+                word_lengths: word_lengths(&context.words[i]),
+                diversity: (!dataset.is_ratio()).then(|| diversity(dataset.word_counts.iter().map(|wc| wc.count))),
+                rarefaction: (context.rarefaction && !dataset.is_ratio())
+                    .then(|| rarefaction(dataset.word_counts.iter().map(|wc| wc.count), &sample_sizes(dataset.total_words))),
+            },
+            words: context
+                .rows(i)
+                .iter()
+                .enumerate()
+                .map(|(j, word)| {
+                    let interval = dataset.bootstrap.as_ref().and_then(|intervals| intervals.word(&word.word));
+                    ExportWord {
+                        word,
+                        percentage: dataset.percentage(word.count),
+                        fit_ratio: context.fit_ratio(i, j),
+                        percentage_interval: interval.map(|interval| interval.percentage),
+                        rank_interval: interval.map(|interval| interval.rank),
+                    }
+                })
+                .collect(),
+        })
+        .collect()
+}
+
+fn write_json(out: &mut impl Write, context: &ExportContext) -> Result<()> {
+    let document = ExportDocument {
+        schema: SCHEMA_NAME,
+        version: SCHEMA_VERSION,
+        filter: context.filter_set,
+        view: context.view.as_ref(),
+        datasets: export_datasets(context),
+    };
+    serde_json::to_writer_pretty(&mut *out, &document)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+fn write_ndjson(out: &mut impl Write, context: &ExportContext) -> Result<()> {
+    let header = NdjsonRecord::Export {
+        schema: SCHEMA_NAME,
+        version: SCHEMA_VERSION,
+        filter: context.filter_set,
+        view: context.view.as_ref(),
+    };
+    serde_json::to_writer(&mut *out, &header)?;
+    writeln!(out)?;
+
+    for dataset in &export_datasets(context) {
+        // The dataset record carries metadata; its words follow one per line
+        serde_json::to_writer(&mut *out, &NdjsonRecord::Dataset(&dataset.summary))?;
+        writeln!(out)?;
+
+        for word in &dataset.words {
+            serde_json::to_writer(&mut *out, &NdjsonRecord::Word { dataset: &dataset.summary.dataset.name, word })?;
+            writeln!(out)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn write_delimited(out: &mut impl Write, delimiter: u8, context: &ExportContext) -> Result<()> {
+    let mut writer = csv::WriterBuilder::new().delimiter(delimiter).from_writer(out);
+    let mut header = vec!["dataset", "rank", "word", "count", "percentage", "tags"];
+    if context.has_fit_ratios() {
+        header.push("fit_ratio");
+    }
+    writer.write_record(&header)?;
+
+    for (i, dataset) in context.datasets.iter().enumerate() {
+        for (j, word) in context.rows(i).iter().enumerate() {
+            let mut tags: Vec<&str> = word.tags.iter().map(|tag| tag.name.as_str()).collect();
+            tags.sort_unstable();
+            let mut record = vec![
+                dataset.name.clone(),
+                word.rank.to_string(),
+                word.word.clone(),
+                word.count.to_string(),
+                dataset.percentage(word.count).map(|percentage| format!("{:.4}", percentage)).unwrap_or_default(),
+                tags.join(";"),
+            ];
+            if context.has_fit_ratios() {
+                record.push(context.fit_ratio(i, j).map(|ratio| format!("{:.4}", ratio)).unwrap_or_default());
+            }
+            writer.write_record(&record)?;
+        }
+    }
+
+    writer.flush()?;
+    Ok(())
+}
+
+/// Rows of the side-by-side tables: the longest dataset's row count.
+fn side_by_side_rows(context: &ExportContext) -> usize {
+    (0..context.datasets.len()).map(|i| context.rows(i).len()).max().unwrap_or(0)
+}
+
+/// Backslash-escape characters that Markdown reads as table cells, emphasis, code, links or
+/// HTML tags, so words like `__init__` and placeholders like `<NUM>` show as written.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn write_markdown(out: &mut impl Write, context: &ExportContext) -> Result<()> {
+    // Datasets side by side, like the TUI multi-dataset view
+    write!(out, "| Rank |")?;
+    for dataset in context.datasets {
+        write!(out, " {} | Count |", escape_markdown(&dataset.name))?;
+    }
+    writeln!(out)?;
+    write!(out, "| ---: |")?;
+    for _ in context.datasets {
+        write!(out, " --- | ---: |")?;
+    }
+    writeln!(out)?;
+
+    for row in 0..side_by_side_rows(context) {
+        write!(out, "| {} |", row + 1)?;
+        for i in 0..context.datasets.len() {
+            match context.rows(i).get(row) {
+                Some(word) => write!(out, " {} | {} |", escape_markdown(&word.word), word.count)?,
+                None => write!(out, "  |  |")?,
+            }
+        }
+        writeln!(out)?;
+    }
+
+    Ok(())
+}
+
+fn escape_latex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            '~' => escaped.push_str("\\textasciitilde{}"),
+            '^' => escaped.push_str("\\textasciicircum{}"),
+            '\\' => escaped.push_str("\\textbackslash{}"),
+            // OT1 fonts print these as ¡, ¿ and —
+            '<' => escaped.push_str("\\textless{}"),
+            '>' => escaped.push_str("\\textgreater{}"),
+            '|' => escaped.push_str("\\textbar{}"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn write_latex(out: &mut impl Write, context: &ExportContext) -> Result<()> {
+    let columns = context.datasets.len();
+
+    writeln!(out, "% Generated by zipfr; requires \\usepackage{{booktabs}}")?;
+    writeln!(out, "\\begin{{table}}[htbp]")?;
+    writeln!(out, "  \\centering")?;
+    writeln!(out, "  \\begin{{tabular}}{{r{}}}", " lr".repeat(columns))?;
+    writeln!(out, "    \\toprule")?;
+
+    // Dataset names span their word/count column pair
+    let mut names = String::from("   ");
+    let mut rules = String::from("   ");
+    for (i, dataset) in context.datasets.iter().enumerate() {
+        let first = 2 + i * 2;
+        let _ = write!(names, " & \\multicolumn{{2}}{{c}}{{{}}}", escape_latex(&dataset.name));
+        let _ = write!(rules, " \\cmidrule(lr){{{}-{}}}", first, first + 1);
+    }
+    writeln!(out, "{} \\\\", names)?;
+    writeln!(out, "{}", rules)?;
+    writeln!(out, "    Rank{} \\\\", " & Word & Count".repeat(columns))?;
+    writeln!(out, "    \\midrule")?;
+
+    for row in 0..side_by_side_rows(context) {
+        write!(out, "    {}", row + 1)?;
+        for i in 0..columns {
+            match context.rows(i).get(row) {
+                Some(word) => write!(out, " & {} & {}", escape_latex(&word.word), word.count)?,
+                None => write!(out, " & &")?,
+            }
+        }
+        writeln!(out, " \\\\")?;
+    }
+
+    writeln!(out, "    \\bottomrule")?;
+    writeln!(out, "  \\end{{tabular}}")?;
+    writeln!(out, "  \\caption{{Word frequencies by rank}}")?;
+    writeln!(out, "\\end{{table}}")?;
+
+    if context.pgfplots {
+        writeln!(out)?;
+        write_pgfplots_figure(out, context)?;
+    }
+
+    Ok(())
+}
+
+fn write_pgfplots_figure(out: &mut impl Write, context: &ExportContext) -> Result<()> {
+    writeln!(out, "% Requires \\usepackage{{pgfplots}}")?;
+    writeln!(out, "\\begin{{figure}}[htbp]")?;
+    writeln!(out, "  \\centering")?;
+    writeln!(out, "  \\begin{{tikzpicture}}")?;
+    writeln!(out, "    \\begin{{loglogaxis}}[xlabel={{Rank}}, ylabel={{Frequency}}, legend pos=north east]")?;
+
+    // The figure covers every filtered word, thinned to ~50 points per decade
+    for (i, dataset) in context.datasets.iter().enumerate() {
+        let mut coordinates = String::new();
+        let mut next_log_rank = 0.0;
+        let words = &context.words[i];
+        for (j, word) in words.iter().enumerate() {
+            let log_rank = (word.rank as f64).log10();
+            if (log_rank >= next_log_rank || j + 1 == words.len()) && word.count > 0 {
+                let _ = write!(coordinates, " ({},{})", word.rank, word.count);
+                next_log_rank = log_rank + 0.02;
+            }
+        }
+        writeln!(out, "      \\addplot+[mark=none] coordinates {{{} }};", coordinates)?;
+        writeln!(out, "      \\addlegendentry{{{}}}", escape_latex(&dataset.name))?;
+    }
+
+    writeln!(out, "    \\end{{loglogaxis}}")?;
+    writeln!(out, "  \\end{{tikzpicture}}")?;
+    writeln!(out, "  \\caption{{Rank/frequency distribution (log-log)}}")?;
+    writeln!(out, "\\end{{figure}}")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::WordAnalyzer;
+    use crate::filter::filter_datasets;
+
+    fn write(format: ExportFormat, context: &ExportContext) -> String {
+        let mut out = Vec::new();
+        write_export(&mut out, format, context).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    fn export(format: ExportFormat, datasets: &[Dataset]) -> String {
+        let words: Vec<Vec<WordCount>> = datasets.iter().map(|d| d.word_counts.clone()).collect();
+        let filter_set = FilterSet::new();
+        write(format, &ExportContext { datasets, words: &words, filter_set: &filter_set, top: None, view: None, pgfplots: false, rarefaction: true })
+    }
+
+    /// Export as from the TUI, with Zipf mode on and the given fit ratios.
+    fn export_view(format: ExportFormat, datasets: &[Dataset], fit_ratios: Vec<Vec<Option<f64>>>) -> String {
+        let words: Vec<Vec<WordCount>> = datasets.iter().map(|d| d.word_counts.clone()).collect();
+        let filter_set = FilterSet::new();
+        let zipf = ZipfState { enabled: true, ..ZipfState::new() };
+        let view = ExportView {
+            normalization: &NormalizationMode::Percentage,
+            chart_scope: &ChartScope::Relative,
+            log_scale: false,
+            zipf: &zipf,
+            fit_ratios,
+        };
+        write(format, &ExportContext { datasets, words: &words, filter_set: &filter_set, top: None, view: Some(view), pgfplots: false, rarefaction: false })
+    }
+
+    #[test]
+    fn test_csv_escaping() {
+        // Words with separators and quotes, which whitespace-split fixtures cannot hold
+        let words = WordAnalyzer::new().analyze(vec!["a,b".into(), "a,b".into(), "say \"hi\"".into()]);
+        let csv = export(ExportFormat::Csv, &[Dataset::from_words("Dickens, Charles", words)]);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "dataset,rank,word,count,percentage,tags");
+        assert_eq!(lines[1], "\"Dickens, Charles\",1,\"a,b\",2,66.6667,");
+        assert_eq!(lines[2], "\"Dickens, Charles\",2,\"say \"\"hi\"\"\",1,33.3333,");
+    }
+
+    #[test]
+    fn test_json_schema_fields() {
+        let json: serde_json::Value =
+            serde_json::from_str(&export(ExportFormat::Json, &[Dataset::from_text("alice", "the the cat")])).unwrap();
+        assert_eq!(json["schema"], SCHEMA_NAME);
+        assert_eq!(json["filter"]["cross_dataset"], "none");
+        assert_eq!(json["datasets"][0]["name"], "alice");
+        assert_eq!(json["datasets"][0]["total_words"], 3);
+        assert_eq!(json["datasets"][0]["word_lengths"]["by_type"]["3"], 2);
+        assert_eq!(json["datasets"][0]["words"][0]["word"], "the");
+        assert!(json["datasets"][0]["words"][0]["tags"].as_array().unwrap().is_empty());
+        assert_eq!(json["datasets"][0]["diversity"]["types"], 2);
+        assert_eq!(json["datasets"][0]["rarefaction"][0]["tokens"], 3);
+        assert_eq!(json["datasets"][0]["rarefaction"][0]["expected_types"], 2.0);
+        assert!(json["datasets"][0]["words"][0].get("percentage_interval").is_none());
+    }
+
+    #[test]
+    fn test_ndjson_records() {
+        let ndjson = export(ExportFormat::Ndjson, &[Dataset::from_text("alice", "the the cat")]);
+        let records: Vec<serde_json::Value> = ndjson.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+        assert_eq!(records.len(), 4);
+        assert_eq!(records[0]["type"], "export");
+        assert_eq!(records[1]["type"], "dataset");
+        assert_eq!(records[2]["type"], "word");
+        assert_eq!(records[2]["dataset"], "alice");
+        assert_eq!(records[2]["count"], 2);
+    }
+
+    #[test]
+    fn test_filtered_rows() {
+        let datasets = vec![Dataset::from_text("alice", "the the cat dog the")];
+        let mut filter_set = FilterSet::new();
+        filter_set.exclude_single = true;
+        let words = filter_datasets(&datasets, &filter_set);
+        let context = ExportContext { datasets: &datasets, words: &words, filter_set: &filter_set, top: None, view: None, pgfplots: false, rarefaction: false };
+
+        // Only the filtered words, re-ranked, with shares of the unfiltered total
+        let csv = write(ExportFormat::Csv, &context);
+        assert_eq!(csv.lines().skip(1).collect::<Vec<_>>(), ["alice,1,the,3,60.0000,"]);
+
+        let json: serde_json::Value = serde_json::from_str(&write(ExportFormat::Json, &context)).unwrap();
+        assert_eq!(json["filter"]["exclude_single"], true);
+        assert_eq!(json["datasets"][0]["total_words"], 5);
+        assert_eq!(json["datasets"][0]["words"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_view_adds_fit_ratio_column() {
+        let datasets = vec![Dataset::from_text("alice", "the the cat")];
+        let csv = export_view(ExportFormat::Csv, &datasets, vec![vec![Some(1.0), Some(1.0)]]);
+        assert!(csv.starts_with("dataset,rank,word,count,percentage,tags,fit_ratio\n"));
+        assert!(csv.contains("alice,1,the,2,66.6667,,1.0000"));
+    }
+
+    #[test]
+    fn test_view_in_json() {
+        let datasets = vec![Dataset::from_text("alice", "the the cat")];
+        let json = export_view(ExportFormat::Json, &datasets, vec![vec![Some(1.0), Some(1.0)]]);
+        let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(json["view"]["normalization"], "percentage");
+        assert_eq!(json["view"]["zipf"]["basis"], "unfiltered");
+        assert_eq!(json["datasets"][0]["words"][1]["fit_ratio"], 1.0);
+    }
+
+    #[test]
+    fn test_view_rows_name_their_dataset() {
+        // :wa writes every dataset into one CSV/TSV file; the dataset column keeps them apart
+        let datasets = vec![Dataset::from_text("alice", "the cat"), Dataset::from_text("dracula", "the")];
+        let tsv = export_view(ExportFormat::Tsv, &datasets, vec![vec![None, None], vec![None]]);
+        let names: Vec<&str> = tsv.lines().skip(1).map(|line| line.split('\t').next().unwrap()).collect();
+        assert_eq!(names, ["alice", "alice", "dracula"]);
+    }
+
+    #[test]
+    fn test_markdown_side_by_side() {
+        let datasets = vec![Dataset::from_text("a_b", "the the cat"), Dataset::from_text("c", "dog")];
+        let markdown = export(ExportFormat::Markdown, &datasets);
+        let lines: Vec<&str> = markdown.lines().collect();
+        assert_eq!(lines[0], "| Rank | a\\_b | Count | c | Count |");
+        assert_eq!(lines[2], "| 1 | the | 2 | dog | 1 |");
+        assert_eq!(lines[3], "| 2 | cat | 1 |  |  |");
+
+        let datasets = vec![Dataset::from_text("numbers", "<NUM> <NUM> __init__ a|b *")];
+        let markdown = export(ExportFormat::Markdown, &datasets);
+        let lines: Vec<&str> = markdown.lines().collect();
+        assert_eq!(lines[2], "| 1 | \\<NUM\\> | 2 |");
+        assert!(markdown.contains("| \\_\\_init\\_\\_ | 1 |"));
+        assert!(markdown.contains("| a\\|b | 1 |"));
+        assert!(markdown.contains("| \\* | 1 |"));
+    }
+
+    #[test]
+    fn test_latex_side_by_side() {
+        let datasets = vec![Dataset::from_text("a_b", "the the cat"), Dataset::from_text("c", "dog")];
+        let latex = export(ExportFormat::Latex, &datasets);
+        assert!(latex.contains("\\begin{tabular}{r lr lr}"));
+        assert!(latex.contains("\\multicolumn{2}{c}{a\\_b}"));
+        assert!(latex.contains("    2 & cat & 1 & & \\\\"));
+        assert!(!latex.contains("loglogaxis"));
+
+        assert_eq!(escape_latex("<NUM>|x"), "\\textless{}NUM\\textgreater{}\\textbar{}x");
+    }
+}
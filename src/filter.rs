@@ -0,0 +1,233 @@
+use crate::analyzer::{Dataset, Tag, WordCount};
+use clap::ValueEnum;
+use serde::{Serialize, Serializer};
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, PartialEq, Serialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum CrossDatasetFilter {
+    None,        // No cross-dataset filtering
+    #[value(name = "common")]
+    #[serde(rename = "common")]
+    CommonOnly,  // Show only words that appear in ALL datasets
+    #[value(name = "unique")]
+    #[serde(rename = "unique")]
+    UniqueOnly,  // Show only words unique to each dataset
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FilterSet {
+    #[serde(serialize_with = "serialize_tag_names")]
+    pub exclude_tags: Vec<Tag>,
+    #[serde(serialize_with = "serialize_tag_names")]
+    pub include_only_tags: Vec<Tag>,  // OR logic - match ANY of these
+    pub exclude_single: bool,
+    pub cross_dataset: CrossDatasetFilter,
+}
+
+impl FilterSet {
+    pub fn new() -> Self {
+        Self {
+            exclude_tags: Vec::new(),
+            include_only_tags: Vec::new(),
+            exclude_single: false,
+            cross_dataset: CrossDatasetFilter::None,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.exclude_tags.is_empty() &&
+        self.include_only_tags.is_empty() &&
+        !self.exclude_single &&
+        matches!(self.cross_dataset, CrossDatasetFilter::None)
+    }
+
+    pub fn matches(&self, word_count: &WordCount) -> bool {
+        // 1. Exclude singles check
+        if self.exclude_single && word_count.count == 1 {
+            return false;
+        }
+
+        // 2. Exclude tags check (exclude if word has ANY excluded tag)
+        if self.exclude_tags.iter().any(|tag| word_count.tags.contains(tag)) {
+            return false;
+        }
+
+        // 3. Include only tags check (OR logic - include if word has ANY include tag, or if no include filters)
+        if !self.include_only_tags.is_empty() {
+            return self.include_only_tags.iter().any(|tag| word_count.tags.contains(tag));
+        }
+
+        // Note: Cross-dataset filtering is handled by CrossDatasetWords
+        // because it requires knowledge of which dataset we're filtering
+
+        true
+    }
+
+    // Conflict prevention methods
+    pub fn add_exclude_tag(&mut self, tag: Tag) {
+        // Remove from include list if present (prevent conflicts)
+        self.include_only_tags.retain(|t| t != &tag);
+        // Add to exclude list if not already present
+        if !self.exclude_tags.contains(&tag) {
+            self.exclude_tags.push(tag);
+        }
+    }
+
+    pub fn add_include_tag(&mut self, tag: Tag) {
+        // Remove from exclude list if present (prevent conflicts)
+        self.exclude_tags.retain(|t| t != &tag);
+        // Add to include list if not already present
+        if !self.include_only_tags.contains(&tag) {
+            self.include_only_tags.push(tag);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.exclude_tags.clear();
+        self.include_only_tags.clear();
+        self.exclude_single = false;
+    }
+}
+
+impl Default for FilterSet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn serialize_tag_names<S: Serializer>(tags: &[Tag], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(tags.iter().map(|tag| &tag.name))
+}
+
+/// Word sets backing the cross-dataset filter.
+#[derive(Debug, Clone, Default)]
+pub struct CrossDatasetWords {
+    pub common: HashSet<String>,                  // Words that appear in ALL datasets
+    pub unique_per_dataset: Vec<HashSet<String>>, // Words unique to each dataset
+}
+
+impl CrossDatasetWords {
+    pub fn compute(datasets: &[Dataset]) -> Self {
+        // Only meaningful with multiple datasets
+        if datasets.len() <= 1 {
+            return Self::default();
+        }
+
+        // Collect word sets for each dataset
+        let dataset_word_sets: Vec<HashSet<String>> = datasets
+            .iter()
+            .map(|dataset| dataset.word_counts.iter().map(|wc| wc.word.clone()).collect())
+            .collect();
+
+        // Compute common words (intersection of all datasets)
+        let mut common = dataset_word_sets[0].clone();
+        for word_set in &dataset_word_sets[1..] {
+            common = common.intersection(word_set).cloned().collect();
+        }
+
+        // Compute unique words for each dataset
+        let unique_per_dataset = dataset_word_sets
+            .iter()
+            .enumerate()
+            .map(|(i, dataset_words)| {
+                // Remove words that appear in any other dataset
+                dataset_words
+                    .iter()
+                    .filter(|word| {
+                        dataset_word_sets
+                            .iter()
+                            .enumerate()
+                            .all(|(j, other_words)| i == j || !other_words.contains(*word))
+                    })
+                    .cloned()
+                    .collect()
+            })
+            .collect();
+
+        Self { common, unique_per_dataset }
+    }
+
+    pub fn includes(&self, filter: &CrossDatasetFilter, word: &str, dataset_index: usize) -> bool {
+        match filter {
+            CrossDatasetFilter::None => true,
+            CrossDatasetFilter::CommonOnly => self.common.contains(word),
+            CrossDatasetFilter::UniqueOnly => self
+                .unique_per_dataset
+                .get(dataset_index)
+                .is_some_and(|unique| unique.contains(word)),
+        }
+    }
+}
+
+/// Apply `filter_set` to one dataset's words and re-rank the survivors.
+pub fn filter_words(
+    words: &[WordCount],
+    filter_set: &FilterSet,
+    cross_dataset_words: &CrossDatasetWords,
+    dataset_index: usize,
+) -> Vec<WordCount> {
+    let mut filtered: Vec<WordCount> = if filter_set.is_empty() {
+        words.to_vec()
+    } else {
+        words
+            .iter()
+            .filter(|wc| {
+                filter_set.matches(wc)
+                    && cross_dataset_words.includes(&filter_set.cross_dataset, &wc.word, dataset_index)
+            })
+            .cloned()
+            .collect()
+    };
+
+    for (index, word_count) in filtered.iter_mut().enumerate() {
+        word_count.rank = index + 1;
+    }
+
+    filtered
+}
+
+/// Filter every dataset, returning re-ranked word lists in dataset order.
+pub fn filter_datasets(datasets: &[Dataset], filter_set: &FilterSet) -> Vec<Vec<WordCount>> {
+    let cross_dataset_words = if matches!(filter_set.cross_dataset, CrossDatasetFilter::None) {
+        CrossDatasetWords::default()
+    } else {
+        CrossDatasetWords::compute(datasets)
+    };
+
+    datasets
+        .iter()
+        .enumerate()
+        .map(|(i, dataset)| filter_words(&dataset.word_counts, filter_set, &cross_dataset_words, i))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn datasets() -> Vec<Dataset> {
+        vec![Dataset::from_text("a", "the the the cat cat dog"), Dataset::from_text("b", "the the cat bird")]
+    }
+
+    #[test]
+    fn test_unique_only_reranks() {
+        let mut filter_set = FilterSet::new();
+        filter_set.cross_dataset = CrossDatasetFilter::UniqueOnly;
+        let filtered = filter_datasets(&datasets(), &filter_set);
+        assert_eq!(filtered[0].len(), 1);
+        assert_eq!(filtered[0][0].word, "dog");
+        assert_eq!(filtered[0][0].rank, 1);
+        assert_eq!(filtered[1][0].word, "bird");
+    }
+
+    #[test]
+    fn test_common_only_excluding_single_words() {
+        let mut filter_set = FilterSet::new();
+        filter_set.cross_dataset = CrossDatasetFilter::CommonOnly;
+        filter_set.exclude_single = true;
+        let filtered = filter_datasets(&datasets(), &filter_set);
+        let words: Vec<&str> = filtered[1].iter().map(|wc| wc.word.as_str()).collect();
+        assert_eq!(words, vec!["the"]);
+    }
+}
@@ -0,0 +1,104 @@
+use anyhow::Result;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+
+/// Path that reads standard input instead of a file.
+pub const STDIN_PATH: &str = "-";
+
+/// Compressed formats recognized by their magic bytes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+    Bzip2,
+    Xz,
+}
+
+const MAGIC_LEN: usize = 6; // Longest magic number (xz)
+
+impl Compression {
+    pub fn detect(header: &[u8]) -> Self {
+        match header {
+            [0x1f, 0x8b, ..] => Compression::Gzip,
+            [0x28, 0xb5, 0x2f, 0xfd, ..] => Compression::Zstd,
+            [b'B', b'Z', b'h', ..] => Compression::Bzip2,
+            [0xfd, b'7', b'z', b'X', b'Z', 0x00, ..] => Compression::Xz,
+            _ => Compression::None,
+        }
+    }
+}
+
+/// Open a file, or stdin for `-`, as a buffered reader that decompresses on the fly.
+/// Detection uses the content, not the extension, so piped compressed data works too.
+pub fn open_input(path: &str) -> Result<Box<dyn BufRead>> {
+    let reader: Box<dyn Read> = if path == STDIN_PATH {
+        Box::new(io::stdin().lock())
+    } else {
+        Box::new(File::open(path)?)
+    };
+    Ok(decompress(reader)?)
+}
+
+/// Peek at the first bytes and wrap the stream in the matching decoder.
+/// Multi-member streams (e.g. concatenated `.gz` files) are read to the end.
+pub fn decompress(mut reader: Box<dyn Read>) -> io::Result<Box<dyn BufRead>> {
+    // A single read may return fewer bytes than asked for on pipes, so fill the header in a loop
+    let mut header = Vec::with_capacity(MAGIC_LEN);
+    (&mut reader).take(MAGIC_LEN as u64).read_to_end(&mut header)?;
+    let compression = Compression::detect(&header);
+    let stream = Cursor::new(header).chain(reader);
+
+    Ok(match compression {
+        Compression::None => Box::new(BufReader::new(stream)),
+        Compression::Gzip => Box::new(BufReader::new(flate2::read::MultiGzDecoder::new(stream))),
+        Compression::Zstd => Box::new(BufReader::new(zstd::stream::read::Decoder::new(stream)?)),
+        Compression::Bzip2 => Box::new(BufReader::new(bzip2::read::MultiBzDecoder::new(stream))),
+        Compression::Xz => Box::new(BufReader::new(xz2::read::XzDecoder::new_multi_decoder(stream))),
+    })
+}
+
+/// File name without a trailing compression extension (`corpus.txt.gz` → `corpus.txt`).
+pub fn strip_compression_extension(name: &str) -> &str {
+    [".gz", ".zst", ".bz2", ".xz"]
+        .iter()
+        .find_map(|extension| name.strip_suffix(extension))
+        .unwrap_or(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn test_decompress_by_magic_bytes() {
+        let text = "The quick brown fox\njumps over the lazy dog\n";
+
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip.write_all(text.as_bytes()).unwrap();
+        let mut bzip2 = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+        bzip2.write_all(text.as_bytes()).unwrap();
+        let mut xz = xz2::write::XzEncoder::new(Vec::new(), 6);
+        xz.write_all(text.as_bytes()).unwrap();
+
+        for (data, expected) in [
+            (text.as_bytes().to_vec(), Compression::None),
+            (gzip.finish().unwrap(), Compression::Gzip),
+            (zstd::encode_all(text.as_bytes(), 0).unwrap(), Compression::Zstd),
+            (bzip2.finish().unwrap(), Compression::Bzip2),
+            (xz.finish().unwrap(), Compression::Xz),
+        ] {
+            assert_eq!(Compression::detect(&data), expected);
+            let mut decoded = String::new();
+            decompress(Box::new(Cursor::new(data))).unwrap().read_to_string(&mut decoded).unwrap();
+            assert_eq!(decoded, text, "{:?}", expected);
+        }
+
+        // Inputs shorter than any magic number are plain text
+        let mut decoded = String::new();
+        decompress(Box::new(Cursor::new(b"a".to_vec()))).unwrap().read_to_string(&mut decoded).unwrap();
+        assert_eq!(decoded, "a");
+        assert_eq!(strip_compression_extension("corpus.txt.gz"), "corpus.txt");
+    }
+}
@@ -0,0 +1,250 @@
+use crate::input::strip_compression_extension;
+use crate::snapshot::is_snapshot_path;
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::collections::BTreeMap;
+use std::path::{Component, Path};
+use walkdir::WalkDir;
+
+/// How the files found under a directory input are grouped into datasets.
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum DirectoryLayout {
+    Tree,   // The whole directory is one merged dataset
+    Subdir, // One dataset per top-level subdirectory (files at the top level form their own)
+    File,   // One dataset per file
+}
+
+/// Directory walking and file selection for directory and glob inputs.
+#[derive(Debug, Clone)]
+pub struct CorpusOptions {
+    recursive: bool,
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+    layout: DirectoryLayout,
+}
+
+/// One dataset's worth of input, after directories and globs are expanded.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputSource {
+    File(String),                                // A file given directly (or matched by a glob), named after its stem
+    Group { name: String, files: Vec<String> }, // Files from a directory, read as one dataset
+}
+
+impl CorpusOptions {
+    pub fn new(recursive: bool, include: &[String], exclude: &[String], layout: DirectoryLayout) -> Result<Self> {
+        Ok(Self {
+            recursive,
+            include: build_glob_set(include)?,
+            exclude: build_glob_set(exclude)?,
+            layout,
+        })
+    }
+
+    /// Include patterns select files; exclude patterns skip files and whole directories.
+    /// Patterns are matched against the path relative to the directory input and the file name.
+    fn is_included(&self, relative: &str, file_name: &str) -> bool {
+        self.include
+            .as_ref()
+            .is_none_or(|set| set.is_match(relative) || set.is_match(file_name))
+            && !self.is_excluded(relative, file_name)
+    }
+
+    fn is_excluded(&self, relative: &str, file_name: &str) -> bool {
+        self.exclude
+            .as_ref()
+            .is_some_and(|set| set.is_match(relative) || set.is_match(file_name))
+    }
+}
+
+fn build_glob_set(patterns: &[String]) -> Result<Option<GlobSet>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern).with_context(|| format!("Invalid glob pattern '{}'", pattern))?);
+    }
+    Ok(Some(builder.build()?))
+}
+
+/// Expand directory and glob inputs into datasets, keeping the order of the inputs.
+/// Plain file paths pass through untouched.
+pub fn expand_inputs(inputs: &[String], options: &CorpusOptions) -> Result<Vec<InputSource>> {
+    let mut sources = Vec::new();
+
+    for input in inputs {
+        let path = Path::new(input);
+        if path.is_dir() {
+            sources.extend(expand_directory(path, options)?);
+        } else if !path.exists() && is_glob_pattern(input) {
+            // Like directory walks (and shells), wildcards do not match hidden files
+            let match_options = glob::MatchOptions { require_literal_leading_dot: true, ..Default::default() };
+            let mut files = Vec::new();
+            for entry in glob::glob_with(input, match_options).with_context(|| format!("Invalid glob pattern '{}'", input))? {
+                let file = entry?;
+                let name = file.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+                if file.is_file() && !options.is_excluded(&file.to_string_lossy(), name) {
+                    files.push(file.to_string_lossy().into_owned());
+                }
+            }
+            if files.is_empty() {
+                bail!("No files match '{}'", input);
+            }
+            sources.extend(files.into_iter().map(InputSource::File));
+        } else {
+            sources.push(InputSource::File(input.clone()));
+        }
+    }
+
+    Ok(sources)
+}
+
+fn is_glob_pattern(input: &str) -> bool {
+    input.contains(['*', '?', '['])
+}
+
+/// Walk a directory input and group its files according to the layout. Hidden files and
+/// snapshots are skipped; dataset names are the directory name followed by the subdirectory
+/// (`corpus/fiction`) or relative file path without extension (`corpus/fiction/emma`).
+fn expand_directory(root: &Path, options: &CorpusOptions) -> Result<Vec<InputSource>> {
+    let root_name = directory_name(root);
+
+    // Subdirectory datasets need one level below the root even without --recursive
+    let max_depth = match (options.recursive, options.layout) {
+        (true, _) => usize::MAX,
+        (false, DirectoryLayout::Subdir) => 2,
+        (false, _) => 1,
+    };
+
+    let walker = WalkDir::new(root)
+        .min_depth(1)
+        .max_depth(max_depth)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|entry| {
+            let name = entry.file_name().to_string_lossy();
+            let pruned = entry.file_type().is_dir() && options.is_excluded(&relative_path(root, entry.path()), &name);
+            !name.starts_with('.') && !pruned
+        });
+
+    let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for entry in walker {
+        let entry = entry.with_context(|| format!("Failed to read directory {}", root.display()))?;
+        let file_path = entry.path().to_string_lossy().into_owned();
+        let relative = relative_path(root, entry.path());
+        if !entry.file_type().is_file()
+            || is_snapshot_path(&file_path)
+            || !options.is_included(&relative, &entry.file_name().to_string_lossy())
+        {
+            continue;
+        }
+
+        let name = match options.layout {
+            DirectoryLayout::Tree => root_name.clone(),
+            DirectoryLayout::Subdir => match relative.split_once('/') {
+                Some((subdir, _)) => format!("{}/{}", root_name, subdir),
+                None => root_name.clone(),
+            },
+            DirectoryLayout::File => {
+                let relative = strip_compression_extension(&relative);
+                let without_extension = relative.rsplit_once('.').map_or(relative, |(stem, _)| stem);
+                format!("{}/{}", root_name, without_extension)
+            }
+        };
+        groups.entry(name).or_default().push(file_path);
+    }
+
+    if groups.is_empty() {
+        bail!("No input files found in {} (check --recursive, --include and --exclude)", root.display());
+    }
+
+    Ok(groups.into_iter().map(|(name, files)| InputSource::Group { name, files }).collect())
+}
+
+/// `/`-separated path of `path` below `root`.
+fn relative_path(root: &Path, path: &Path) -> String {
+    path.strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Name of a directory input, resolving `.` and `..` to the real directory name.
+fn directory_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .or_else(|| {
+            path.canonicalize()
+                .ok()
+                .and_then(|path| path.file_name().map(|name| name.to_string_lossy().into_owned()))
+        })
+        .unwrap_or_else(|| "corpus".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_directory_layouts_and_globs() {
+        let dir = std::env::temp_dir().join(format!("zipfr-corpus-test-{}", std::process::id()));
+        let root = dir.join("corpus");
+        for (file, text) in [
+            ("intro.txt", "a"),
+            ("fiction/emma.txt", "b"),
+            ("fiction/old/drafts.txt", "c"),
+            ("poetry/odes.md", "d"),
+            ("poetry/notes.txt", "e"),
+            (".hidden/secret.txt", "f"),
+        ] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, text).unwrap();
+        }
+        let inputs = [root.to_string_lossy().into_owned()];
+        let names_and_counts = |sources: Vec<InputSource>| -> Vec<(String, usize)> {
+            sources
+                .into_iter()
+                .map(|source| match source {
+                    InputSource::Group { name, files } => (name, files.len()),
+                    InputSource::File(path) => (path, 1),
+                })
+                .collect()
+        };
+
+        let options = CorpusOptions::new(false, &[], &[], DirectoryLayout::Tree).unwrap();
+        assert_eq!(names_and_counts(expand_inputs(&inputs, &options).unwrap()), vec![("corpus".to_string(), 1)]);
+
+        let options = CorpusOptions::new(true, &[], &[], DirectoryLayout::Tree).unwrap();
+        assert_eq!(names_and_counts(expand_inputs(&inputs, &options).unwrap()), vec![("corpus".to_string(), 5)]);
+
+        let options = CorpusOptions::new(false, &["*.txt".to_string()], &[], DirectoryLayout::Subdir).unwrap();
+        assert_eq!(
+            names_and_counts(expand_inputs(&inputs, &options).unwrap()),
+            vec![("corpus".to_string(), 1), ("corpus/fiction".to_string(), 1), ("corpus/poetry".to_string(), 1)]
+        );
+
+        let options = CorpusOptions::new(true, &[], &["old".to_string(), "*.md".to_string()], DirectoryLayout::File).unwrap();
+        assert_eq!(
+            names_and_counts(expand_inputs(&inputs, &options).unwrap()),
+            vec![
+                ("corpus/fiction/emma".to_string(), 1),
+                ("corpus/intro".to_string(), 1),
+                ("corpus/poetry/notes".to_string(), 1),
+            ]
+        );
+
+        let pattern = format!("{}/*/*.txt", root.display());
+        let options = CorpusOptions::new(false, &[], &[], DirectoryLayout::Tree).unwrap();
+        assert_eq!(expand_inputs(&[pattern], &options).unwrap().len(), 2);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,286 @@
+use crate::input::markup::html_body_text;
+use crate::input::InputFormat;
+use anyhow::{bail, Context, Result};
+use roxmltree::{Document, Node};
+use std::collections::HashMap;
+use std::io::{Cursor, Read};
+use zip::ZipArchive;
+
+/// A titled part of a document: an EPUB chapter, or the whole body of other formats.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub title: String,
+    pub text: String,
+}
+
+const WORD_NS: &str = "http://schemas.openxmlformats.org/wordprocessingml/2006/main";
+const ODF_TEXT_NS: &str = "urn:oasis:names:tc:opendocument:xmlns:text:1.0";
+const ODF_OFFICE_NS: &str = "urn:oasis:names:tc:opendocument:xmlns:office:1.0";
+
+/// Longest chapter title kept in dataset names.
+const MAX_TITLE_CHARS: usize = 40;
+
+/// Extract the body text of a zip-based document in reading order.
+pub fn read_document(bytes: Vec<u8>, format: InputFormat) -> Result<Vec<Section>> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).context("Not a valid zip container")?;
+    match format {
+        InputFormat::Epub => epub_sections(&mut archive),
+        InputFormat::Docx => {
+            let xml = read_entry(&mut archive, "word/document.xml")?;
+            Ok(vec![Section { title: String::new(), text: docx_text(&xml)? }])
+        }
+        InputFormat::Odt => {
+            let xml = read_entry(&mut archive, "content.xml")?;
+            Ok(vec![Section { title: String::new(), text: odt_text(&xml)? }])
+        }
+        _ => bail!("{:?} is not a document format", format),
+    }
+}
+
+fn read_entry(archive: &mut ZipArchive<Cursor<Vec<u8>>>, name: &str) -> Result<String> {
+    let mut entry = archive.by_name(name).with_context(|| format!("Missing {}", name))?;
+    let mut text = String::new();
+    entry.read_to_string(&mut text).with_context(|| format!("Failed to read {}", name))?;
+    Ok(text)
+}
+
+fn parse_xml<'a>(xml: &'a str, name: &str) -> Result<Document<'a>> {
+    let options = roxmltree::ParsingOptions { allow_dtd: true, ..Default::default() };
+    Document::parse_with_options(xml, options).with_context(|| format!("Invalid XML in {}", name))
+}
+
+/// Chapters in spine order. The navigation document and non-linear items (notes,
+/// covers) are skipped; metadata lives in the package file and is never read as text.
+fn epub_sections(archive: &mut ZipArchive<Cursor<Vec<u8>>>) -> Result<Vec<Section>> {
+    let container = read_entry(archive, "META-INF/container.xml")?;
+    let container = parse_xml(&container, "META-INF/container.xml")?;
+    let package_path = container
+        .descendants()
+        .find(|node| node.has_tag_name("rootfile"))
+        .and_then(|node| node.attribute("full-path"))
+        .context("container.xml names no package file")?
+        .to_string();
+
+    let package = read_entry(archive, &package_path)?;
+    let package = parse_xml(&package, &package_path)?;
+    let base = package_path.rsplit_once('/').map_or("", |(directory, _)| directory);
+
+    let manifest: HashMap<&str, (&str, &str)> = package
+        .descendants()
+        .filter(|node| node.has_tag_name("item"))
+        .filter_map(|item| {
+            Some((item.attribute("id")?, (item.attribute("href")?, item.attribute("properties").unwrap_or(""))))
+        })
+        .collect();
+
+    let mut sections = Vec::new();
+    for itemref in package.descendants().filter(|node| node.has_tag_name("itemref")) {
+        if itemref.attribute("linear") == Some("no") {
+            continue;
+        }
+        let Some(&(href, properties)) = itemref.attribute("idref").and_then(|id| manifest.get(id)) else {
+            continue;
+        };
+        if properties.split_whitespace().any(|property| property == "nav") {
+            continue;
+        }
+
+        let path = resolve_href(base, href);
+        let xhtml = read_entry(archive, &path)?;
+        let title = chapter_title(&xhtml).unwrap_or_else(|| {
+            let file = href.rsplit('/').next().unwrap_or(href);
+            file.rsplit_once('.').map_or(file, |(stem, _)| stem).to_string()
+        });
+        sections.push(Section { title, text: html_body_text(&xhtml) });
+    }
+
+    if sections.is_empty() {
+        bail!("EPUB spine lists no readable chapters");
+    }
+    Ok(sections)
+}
+
+/// Path of a manifest href relative to the package directory, with %-escapes decoded.
+fn resolve_href(base: &str, href: &str) -> String {
+    let href = href.split('#').next().unwrap_or(href);
+    let mut bytes = Vec::with_capacity(href.len());
+    let mut chars = href.bytes();
+    while let Some(byte) = chars.next() {
+        if byte == b'%' {
+            let hex: Vec<u8> = chars.by_ref().take(2).collect();
+            if let Some(decoded) = std::str::from_utf8(&hex).ok().and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                bytes.push(decoded);
+                continue;
+            }
+            bytes.push(byte);
+            bytes.extend(hex);
+        } else {
+            bytes.push(byte);
+        }
+    }
+    let href = String::from_utf8_lossy(&bytes);
+
+    let mut parts: Vec<&str> = base.split('/').filter(|part| !part.is_empty()).collect();
+    for part in href.split('/') {
+        match part {
+            ".." => {
+                parts.pop();
+            }
+            "." | "" => {}
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+/// First heading of a chapter, used to name chapter datasets.
+fn chapter_title(xhtml: &str) -> Option<String> {
+    let document = scraper::Html::parse_document(xhtml);
+    let selector = scraper::Selector::parse("h1, h2, h3").ok()?;
+    let heading = document.select(&selector).next()?;
+    let title = heading.text().collect::<Vec<_>>().join(" ");
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    (!title.is_empty()).then(|| title.chars().take(MAX_TITLE_CHARS).collect())
+}
+
+/// Paragraph text of a Word document. Only `w:t` runs are read, so deleted revisions
+/// and field codes are skipped; headers, footers and notes live in other parts.
+fn docx_text(xml: &str) -> Result<String> {
+    let document = parse_xml(xml, "word/document.xml")?;
+    let body = document
+        .descendants()
+        .find(|node| node.tag_name().namespace() == Some(WORD_NS) && node.tag_name().name() == "body")
+        .context("word/document.xml has no body")?;
+
+    let mut text = String::new();
+    for node in body.descendants().filter(|node| node.tag_name().namespace() == Some(WORD_NS)) {
+        match node.tag_name().name() {
+            "t" => text.push_str(node.text().unwrap_or("")),
+            "tab" => text.push(' '),
+            "br" | "cr" | "p" => text.push('\n'),
+            _ => {}
+        }
+    }
+    Ok(text)
+}
+
+/// Text of an OpenDocument text body, skipping annotations, tracked deletions and notes.
+fn odt_text(xml: &str) -> Result<String> {
+    let document = parse_xml(xml, "content.xml")?;
+    let body = document
+        .descendants()
+        .find(|node| node.tag_name().namespace() == Some(ODF_OFFICE_NS) && node.tag_name().name() == "text")
+        .context("content.xml has no text body")?;
+
+    let mut text = String::new();
+    odt_collect(body, &mut text);
+    Ok(text)
+}
+
+fn odt_collect(node: Node, text: &mut String) {
+    for child in node.children() {
+        if child.is_text() {
+            text.push_str(child.text().unwrap_or(""));
+            continue;
+        }
+
+        let name = child.tag_name();
+        if name.namespace() == Some(ODF_OFFICE_NS) && name.name() == "annotation" {
+            continue;
+        }
+        if name.namespace() == Some(ODF_TEXT_NS) {
+            match name.name() {
+                "tracked-changes" | "note" | "sequence-decls" => continue,
+                "s" => {
+                    let count = child.attribute((ODF_TEXT_NS, "c")).and_then(|c| c.parse().ok()).unwrap_or(1);
+                    text.extend(std::iter::repeat_n(' ', count));
+                    continue;
+                }
+                "tab" => {
+                    text.push(' ');
+                    continue;
+                }
+                "line-break" => {
+                    text.push('\n');
+                    continue;
+                }
+                _ => {}
+            }
+        }
+
+        odt_collect(child, text);
+        if name.namespace() == Some(ODF_TEXT_NS) && matches!(name.name(), "p" | "h") {
+            text.push('\n');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    fn zip(entries: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, content) in entries {
+            writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    fn words(text: &str) -> Vec<&str> {
+        text.split_whitespace().collect()
+    }
+
+    #[test]
+    fn test_epub_docx_and_odt() {
+        let epub = zip(&[
+            ("mimetype", "application/epub+zip"),
+            (
+                "META-INF/container.xml",
+                r#"<container><rootfiles><rootfile full-path="OEBPS/content.opf"/></rootfiles></container>"#,
+            ),
+            (
+                "OEBPS/content.opf",
+                r#"<package><metadata><title>Book Title</title></metadata><manifest>
+                <item id="nav" href="nav.xhtml" properties="nav"/><item id="c2" href="text/two.xhtml"/>
+                <item id="c1" href="text/chapter%201.xhtml"/><item id="notes" href="notes.xhtml"/></manifest>
+                <spine><itemref idref="nav"/><itemref idref="c1"/><itemref idref="c2"/><itemref idref="notes" linear="no"/></spine></package>"#,
+            ),
+            ("OEBPS/nav.xhtml", "<html><body><nav>Contents</nav></body></html>"),
+            ("OEBPS/text/chapter 1.xhtml", "<html><head><title>Book Title</title></head><body><h1>The Start</h1><p>Once upon</p></body></html>"),
+            ("OEBPS/text/two.xhtml", "<html><body><p>a time</p></body></html>"),
+            ("OEBPS/notes.xhtml", "<html><body><p>Footnote</p></body></html>"),
+        ]);
+        let sections = read_document(epub, InputFormat::Epub).unwrap();
+        let titles: Vec<&str> = sections.iter().map(|section| section.title.as_str()).collect();
+        assert_eq!(titles, vec!["The Start", "two"]);
+        assert_eq!(words(&sections[0].text), vec!["The", "Start", "Once", "upon"]);
+
+        let docx = zip(&[(
+            "word/document.xml",
+            &format!(
+                r#"<w:document xmlns:w="{}"><w:body><w:p><w:r><w:t>Hello</w:t></w:r><w:r><w:t xml:space="preserve"> wor</w:t></w:r><w:r><w:t>ld</w:t></w:r></w:p>
+                <w:p><w:del><w:r><w:delText>gone</w:delText></w:r></w:del><w:r><w:instrText>PAGE</w:instrText><w:t>again</w:t></w:r></w:p></w:body></w:document>"#,
+                WORD_NS
+            ),
+        )]);
+        let sections = read_document(docx, InputFormat::Docx).unwrap();
+        assert_eq!(words(&sections[0].text), vec!["Hello", "world", "again"]);
+
+        let odt = zip(&[(
+            "content.xml",
+            &format!(
+                r#"<office:document-content xmlns:office="{}" xmlns:text="{}"><office:body><office:text>
+                <text:sequence-decls><text:sequence-decl text:name="Figure"/></text:sequence-decls>
+                <text:h>Title</text:h><text:p>One<text:s/>two<text:note><text:note-body><text:p>note</text:p></text:note-body></text:note></text:p>
+                <office:annotation><text:p>comment</text:p></office:annotation></office:text></office:body></office:document-content>"#,
+                ODF_OFFICE_NS, ODF_TEXT_NS
+            ),
+        )]);
+        let sections = read_document(odt, InputFormat::Odt).unwrap();
+        assert_eq!(words(&sections[0].text), vec!["Title", "One", "two"]);
+    }
+}
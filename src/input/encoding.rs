@@ -0,0 +1,217 @@
+use anyhow::{bail, Result};
+use chardetng::EncodingDetector;
+use encoding_rs::{Decoder, DecoderResult, Encoding, UTF_8};
+use std::io::{self, Read};
+
+/// Bytes fed to the detector when the input is not valid UTF-8.
+const DETECTION_SAMPLE_BYTES: usize = 1 << 20;
+
+/// Bytes read at a time when decoding a stream.
+const READ_CHUNK_BYTES: usize = 64 * 1024;
+
+/// How input bytes are turned into text.
+#[derive(Debug, Clone, Default)]
+pub struct DecodeOptions {
+    pub encoding: Option<&'static Encoding>, // None: BOM, then UTF-8, then detection
+    pub lossy: bool,                         // Replace invalid sequences instead of failing
+}
+
+/// What decoding found, for reporting.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecodeReport {
+    pub encoding: &'static Encoding,
+    pub replacements: usize, // Invalid sequences replaced with U+FFFD (lossy mode only)
+}
+
+impl DecodeOptions {
+    /// `label` is `auto` or any WHATWG encoding label (`utf-8`, `latin1`, `windows-1252`, `utf-16le`, ...).
+    pub fn new(label: &str, lossy: bool) -> Result<Self> {
+        let encoding = match label {
+            "auto" => None,
+            label => match Encoding::for_label(label.as_bytes()) {
+                Some(encoding) => Some(encoding),
+                None => bail!("Unknown encoding '{}'", label),
+            },
+        };
+        Ok(Self { encoding, lossy })
+    }
+}
+
+/// Decode a whole input. A byte order mark always wins over detection; without one,
+/// valid UTF-8 is taken as is and anything else goes through charset detection.
+/// Strict decoding fails at the first invalid sequence with its byte offset.
+pub fn decode(bytes: Vec<u8>, options: &DecodeOptions) -> Result<(String, DecodeReport)> {
+    let (encoding, start) = pick_encoding(&bytes, options, true);
+
+    // Fast path: valid UTF-8 needs no copy
+    if encoding == UTF_8 && start == 0 {
+        match String::from_utf8(bytes) {
+            Ok(text) => return Ok((text, DecodeReport { encoding, replacements: 0 })),
+            Err(error) => return decode_with(encoding, error.as_bytes(), 0, options.lossy),
+        }
+    }
+
+    decode_with(encoding, &bytes, start, options.lossy)
+}
+
+/// Decode a stream chunk by chunk, so the raw bytes are never held in memory all at once.
+/// The encoding is picked from the first `DETECTION_SAMPLE_BYTES`: an input whose sample is
+/// valid UTF-8 is decoded as UTF-8 to the end, with invalid sequences further on reported
+/// (or replaced) like any other.
+pub fn decode_reader(mut reader: impl Read, options: &DecodeOptions) -> Result<(String, DecodeReport)> {
+    let mut sample = Vec::new();
+    (&mut reader).take(DETECTION_SAMPLE_BYTES as u64).read_to_end(&mut sample)?;
+    if sample.len() < DETECTION_SAMPLE_BYTES {
+        return decode(sample, options);
+    }
+
+    let (encoding, start) = pick_encoding(&sample, options, false);
+    let mut decoder = StreamDecoder::new(encoding, start, options.lossy);
+    decoder.feed(&sample[start..], false)?;
+    drop(sample);
+
+    let mut chunk = vec![0u8; READ_CHUNK_BYTES];
+    loop {
+        let read = match reader.read(&mut chunk) {
+            Ok(read) => read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error.into()),
+        };
+        decoder.feed(&chunk[..read], read == 0)?;
+        if read == 0 {
+            return Ok(decoder.finish());
+        }
+    }
+}
+
+/// The encoding to decode with and the length of the byte order mark to skip. `complete`
+/// says whether `bytes` is the whole input or only its start, which may end mid-character.
+fn pick_encoding(bytes: &[u8], options: &DecodeOptions, complete: bool) -> (&'static Encoding, usize) {
+    let bom = Encoding::for_bom(bytes);
+    let valid_utf8 = match std::str::from_utf8(bytes) {
+        Ok(_) => true,
+        Err(error) => !complete && error.error_len().is_none(),
+    };
+    match (options.encoding, bom) {
+        (None, Some((encoding, bom_length))) => (encoding, bom_length),
+        (Some(encoding), Some((bom_encoding, bom_length))) if encoding == bom_encoding => (encoding, bom_length),
+        (Some(encoding), _) => (encoding, 0),
+        (None, None) if valid_utf8 => (UTF_8, 0),
+        (None, None) => {
+            let mut detector = EncodingDetector::new();
+            detector.feed(&bytes[..bytes.len().min(DETECTION_SAMPLE_BYTES)], complete);
+            (detector.guess(None, true), 0)
+        }
+    }
+}
+
+fn decode_with(encoding: &'static Encoding, bytes: &[u8], start: usize, lossy: bool) -> Result<(String, DecodeReport)> {
+    let mut decoder = StreamDecoder::new(encoding, start, lossy);
+    decoder.feed(&bytes[start..], true)?;
+    Ok(decoder.finish())
+}
+
+/// Decodes input fed in pieces, keeping byte offsets for error messages across pieces.
+struct StreamDecoder {
+    encoding: &'static Encoding,
+    decoder: Decoder,
+    lossy: bool,
+    text: String,
+    position: usize, // Input bytes consumed so far
+    replacements: usize,
+}
+
+impl StreamDecoder {
+    fn new(encoding: &'static Encoding, start: usize, lossy: bool) -> Self {
+        Self {
+            encoding,
+            decoder: encoding.new_decoder_without_bom_handling(),
+            lossy,
+            text: String::new(),
+            position: start,
+            replacements: 0,
+        }
+    }
+
+    fn feed(&mut self, bytes: &[u8], last: bool) -> Result<()> {
+        let reserve = |decoder: &Decoder, remaining: usize| {
+            decoder
+                .max_utf8_buffer_length_without_replacement(remaining)
+                .unwrap_or(remaining)
+                .max(16)
+        };
+        self.text.reserve(reserve(&self.decoder, bytes.len()));
+        let mut consumed = 0;
+
+        loop {
+            let (result, read) =
+                self.decoder
+                    .decode_to_string_without_replacement(&bytes[consumed..], &mut self.text, last);
+            consumed += read;
+            match result {
+                DecoderResult::InputEmpty => break,
+                DecoderResult::OutputFull => self.text.reserve(reserve(&self.decoder, bytes.len() - consumed)),
+                DecoderResult::Malformed(bad, consumed_after) => {
+                    let offset = (self.position + consumed).saturating_sub(bad as usize + consumed_after as usize);
+                    if !self.lossy {
+                        bail!(
+                            "Invalid {} sequence at byte {} (pick the encoding with --encoding, or use --lossy to replace invalid bytes)",
+                            self.encoding.name(),
+                            offset
+                        );
+                    }
+                    self.text.push(char::REPLACEMENT_CHARACTER);
+                    self.replacements += 1;
+                }
+            }
+        }
+
+        self.position += consumed;
+        Ok(())
+    }
+
+    fn finish(self) -> (String, DecodeReport) {
+        (self.text, DecodeReport { encoding: self.encoding, replacements: self.replacements })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encoding_rs::{UTF_16LE, WINDOWS_1252};
+
+    #[test]
+    fn test_bom_detection_strict_and_lossy() {
+        // UTF-16 with a byte order mark
+        let mut utf16 = vec![0xff, 0xfe];
+        utf16.extend("héllo".encode_utf16().flat_map(|unit| unit.to_le_bytes()));
+        let (text, report) = decode(utf16, &DecodeOptions::default()).unwrap();
+        assert_eq!((text.as_str(), report.encoding), ("héllo", UTF_16LE));
+
+        // Latin-1 text is detected rather than rejected
+        let latin1 = WINDOWS_1252.encode("Le café était très animé, déjà plein à midi.").0.into_owned();
+        let (text, report) = decode(latin1.clone(), &DecodeOptions::default()).unwrap();
+        assert_eq!(text, "Le café était très animé, déjà plein à midi.");
+        assert_eq!(report.replacements, 0);
+
+        // Forcing UTF-8 fails at the first invalid byte, or replaces in lossy mode
+        let options = DecodeOptions::new("utf-8", false).unwrap();
+        let error = decode(latin1.clone(), &options).unwrap_err().to_string();
+        assert!(error.contains("at byte 6"), "{}", error);
+
+        let options = DecodeOptions::new("utf-8", true).unwrap();
+        let (text, report) = decode(latin1, &options).unwrap();
+        assert!(text.starts_with("Le caf\u{fffd} \u{fffd}tait"));
+        assert_eq!(report.replacements, 7);
+
+        assert!(DecodeOptions::new("klingon", false).is_err());
+
+        // Streams longer than the detection sample decode the same, with offsets past chunk ends
+        let mut long = "é".repeat(DETECTION_SAMPLE_BYTES).into_bytes();
+        let (text, _) = decode_reader(long.as_slice(), &DecodeOptions::default()).unwrap();
+        assert_eq!(text.chars().count(), DETECTION_SAMPLE_BYTES);
+        long.push(0xff);
+        let error = decode_reader(long.as_slice(), &DecodeOptions::default()).unwrap_err().to_string();
+        assert!(error.contains(&format!("at byte {}", 2 * DETECTION_SAMPLE_BYTES)), "{}", error);
+    }
+}
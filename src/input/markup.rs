@@ -0,0 +1,288 @@
+use crate::input::strip_compression_extension;
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use pulldown_cmark::{Event, Parser, Tag, TagEnd};
+use scraper::{Html, Node};
+use std::path::Path;
+
+/// How an input's text is extracted before words are split out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, ValueEnum)]
+pub enum InputFormat {
+    #[default]
+    Auto, // From the file extension, plain text otherwise
+    Text,
+    Html,
+    Markdown,
+    Xml,
+    Epub,
+    Docx,
+    Odt,
+    Pdf,
+    Csv,
+    Tsv,
+    Json,
+    Jsonl,
+}
+
+/// Elements whose content is never text to count.
+const HTML_SKIPPED: &[&str] = &["script", "style", "noscript", "template", "svg", "math"];
+
+/// Elements that break words apart; other (inline) elements join their text to their neighbours.
+const HTML_BLOCKS: &[&str] = &[
+    "address", "article", "aside", "blockquote", "br", "dd", "div", "dl", "dt", "figcaption", "figure", "footer",
+    "form", "h1", "h2", "h3", "h4", "h5", "h6", "header", "hr", "li", "main", "nav", "ol", "p", "pre", "section",
+    "table", "td", "th", "title", "tr", "ul",
+];
+
+/// Extraction settings for markup formats.
+#[derive(Debug, Clone, Default)]
+pub struct MarkupOptions {
+    pub skip_code: bool,           // Markdown: leave out code blocks and inline code
+    pub xml_elements: Vec<String>, // XML: only text inside these elements (`p`, `chapter/p`); all text if empty
+}
+
+impl InputFormat {
+    /// Pick the format for a file: an explicit format wins, `Auto` goes by extension
+    /// (ignoring a compression extension, so `page.html.gz` is HTML).
+    pub fn resolve(self, path: &str) -> InputFormat {
+        if self != InputFormat::Auto {
+            return self;
+        }
+
+        let extension = Path::new(strip_compression_extension(path))
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("html" | "htm" | "xhtml") => InputFormat::Html,
+            Some("md" | "markdown") => InputFormat::Markdown,
+            Some("xml") => InputFormat::Xml,
+            Some("epub") => InputFormat::Epub,
+            Some("docx") => InputFormat::Docx,
+            Some("odt") => InputFormat::Odt,
+            Some("pdf") => InputFormat::Pdf,
+            Some("csv") => InputFormat::Csv,
+            Some("tsv" | "tab") => InputFormat::Tsv,
+            Some("json") => InputFormat::Json,
+            Some("jsonl" | "ndjson") => InputFormat::Jsonl,
+            _ => InputFormat::Text,
+        }
+    }
+
+    /// Zip-based document formats, read from bytes rather than decoded text.
+    pub fn is_document(self) -> bool {
+        matches!(self, InputFormat::Epub | InputFormat::Docx | InputFormat::Odt)
+    }
+
+    /// Binary formats with their own extractors (documents and PDF).
+    pub fn is_binary(self) -> bool {
+        self.is_document() || self == InputFormat::Pdf
+    }
+
+    /// Record formats, whose text comes from one column or field of each record.
+    pub fn is_structured(self) -> bool {
+        matches!(self, InputFormat::Csv | InputFormat::Tsv | InputFormat::Json | InputFormat::Jsonl)
+    }
+}
+
+/// Strip markup and return only the text content, one block per line.
+pub fn extract_text(text: String, format: InputFormat, options: &MarkupOptions) -> Result<String> {
+    match format {
+        InputFormat::Auto | InputFormat::Text => Ok(text),
+        InputFormat::Html => Ok(html_text(&text)),
+        InputFormat::Markdown => Ok(markdown_text(&text, options.skip_code)),
+        InputFormat::Xml => xml_text(&text, &options.xml_elements),
+        InputFormat::Epub | InputFormat::Docx | InputFormat::Odt | InputFormat::Pdf => {
+            bail!("{:?} files are binary, not text", format)
+        }
+        InputFormat::Csv | InputFormat::Tsv | InputFormat::Json | InputFormat::Jsonl => {
+            bail!("{:?} files hold records; read them with read_records", format)
+        }
+    }
+}
+
+pub(crate) fn html_text(html: &str) -> String {
+    html_text_without(html, &[])
+}
+
+/// Text of the `<body>` only, for documents whose `<head>` repeats metadata (EPUB chapters).
+pub(crate) fn html_body_text(html: &str) -> String {
+    html_text_without(html, &["head"])
+}
+
+fn html_text_without(html: &str, skipped: &[&str]) -> String {
+    let document = Html::parse_document(html);
+    let mut text = String::new();
+
+    // Depth-first walk; a `None` marker closes a block element
+    let mut pending = vec![Some(document.tree.root())];
+    while let Some(entry) = pending.pop() {
+        let Some(node) = entry else {
+            text.push('\n');
+            continue;
+        };
+        match node.value() {
+            Node::Text(content) => text.push_str(content),
+            Node::Element(element) => {
+                let name = element.name();
+                if HTML_SKIPPED.contains(&name) || skipped.contains(&name) {
+                    continue;
+                }
+                if HTML_BLOCKS.contains(&name) {
+                    text.push('\n');
+                    pending.push(None);
+                }
+                pending.extend(node.children().rev().map(Some));
+            }
+            Node::Document | Node::Fragment => pending.extend(node.children().rev().map(Some)),
+            _ => {}
+        }
+    }
+
+    text
+}
+
+fn markdown_text(markdown: &str, skip_code: bool) -> String {
+    let mut text = String::new();
+    let mut in_code_block = false;
+
+    for event in Parser::new(markdown) {
+        match event {
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => {
+                in_code_block = false;
+                text.push('\n');
+            }
+            Event::Text(content) if !(in_code_block && skip_code) => text.push_str(&content),
+            Event::Code(content) if !skip_code => text.push_str(&content),
+            Event::Html(html) | Event::InlineHtml(html) => text.push_str(&html_fragment_text(&html)),
+            Event::SoftBreak | Event::HardBreak => text.push('\n'),
+            Event::End(TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::Item | TagEnd::TableCell) => text.push('\n'),
+            _ => {}
+        }
+    }
+
+    text
+}
+
+/// Text of raw HTML embedded in Markdown; lone tags yield nothing.
+fn html_fragment_text(html: &str) -> String {
+    if !html.contains('<') {
+        return html.to_string();
+    }
+    Html::parse_fragment(html)
+        .root_element()
+        .text()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Elements whose text stands apart from its neighbours (TEI, DocBook and XHTML names).
+/// Any other element, like `<hi>`, `<w>` or `<choice>`, is inline: its text runs on into the
+/// surrounding text, so markup inside a word does not split it.
+const XML_BLOCK_ELEMENTS: &[&str] = &[
+    "ab", "article", "bibl", "blockquote", "body", "book", "br", "caption", "cb", "cell", "chapter", "closer", "dd",
+    "div", "div1", "div2", "div3", "dt", "entry", "figure", "h1", "h2", "h3", "h4", "h5", "h6", "head", "item", "l",
+    "label", "lb", "lg", "li", "list", "note", "opener", "p", "para", "pb", "quote", "row", "section", "sp", "speaker",
+    "stage", "table", "td", "text", "th", "title", "tr",
+];
+
+fn xml_text(xml: &str, elements: &[String]) -> Result<String> {
+    let options = roxmltree::ParsingOptions { allow_dtd: true, ..Default::default() };
+    let document = roxmltree::Document::parse_with_options(xml, options).context("Invalid XML")?;
+    let selectors: Vec<Vec<&str>> = elements.iter().map(|element| element.split('/').collect()).collect();
+
+    let mut text = String::new();
+    let mut pending = vec![document.root_element()];
+    while let Some(node) = pending.pop() {
+        if selectors.is_empty() || selectors.iter().any(|selector| matches_path(node, selector)) {
+            // Matched: take all of its text, one element per line
+            push_xml_text(node, &mut text);
+            end_line(&mut text);
+        } else {
+            pending.extend(node.children().filter(|child| child.is_element()).rev());
+        }
+    }
+
+    Ok(text)
+}
+
+/// Append the text inside an element, with line breaks only around block elements.
+fn push_xml_text(node: roxmltree::Node, text: &mut String) {
+    for child in node.children() {
+        if child.is_text() {
+            text.push_str(child.text().unwrap_or_default());
+        } else if child.is_element() {
+            let block = XML_BLOCK_ELEMENTS.contains(&child.tag_name().name());
+            if block {
+                end_line(text);
+            }
+            push_xml_text(child, text);
+            if block {
+                end_line(text);
+            }
+        }
+    }
+}
+
+fn end_line(text: &mut String) {
+    if !text.is_empty() && !text.ends_with('\n') {
+        text.push('\n');
+    }
+}
+
+/// Whether an element's name and its ancestors' names end with the selector path (`chapter/p`).
+fn matches_path(node: roxmltree::Node, selector: &[&str]) -> bool {
+    let mut current = Some(node);
+    for name in selector.iter().rev() {
+        match current {
+            Some(element) if element.is_element() && element.tag_name().name() == *name => {
+                current = element.parent();
+            }
+            _ => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<&str> {
+        text.split_whitespace().collect()
+    }
+
+    #[test]
+    fn test_markup_extraction() {
+        assert_eq!(InputFormat::Auto.resolve("page.HTM.gz"), InputFormat::Html);
+        assert_eq!(InputFormat::Auto.resolve("notes.txt"), InputFormat::Text);
+        assert_eq!(InputFormat::Auto.resolve("posts.ndjson"), InputFormat::Jsonl);
+        assert_eq!(InputFormat::Xml.resolve("notes.txt"), InputFormat::Xml);
+
+        let html = r#"<html><head><title>Title</title><style>p { color: red }</style></head>
+            <body><p class="intro">Hello <b>wor</b>ld &amp; <a href="https://example.com/x">friends</a></p>
+            <script>var tracking = 1;</script><div>Bye</div></body></html>"#;
+        assert_eq!(words(&html_text(html)), vec!["Title", "Hello", "world", "&", "friends", "Bye"]);
+
+        let markdown = "# Heading\n\nSome *text* with `code` and a [link](http://x.org).\n\n```rust\nfn main() {}\n```\n";
+        let options = MarkupOptions { skip_code: true, ..Default::default() };
+        let text = extract_text(markdown.to_string(), InputFormat::Markdown, &options).unwrap();
+        assert_eq!(words(&text), vec!["Heading", "Some", "text", "with", "and", "a", "link."]);
+        let text = extract_text(markdown.to_string(), InputFormat::Markdown, &MarkupOptions::default()).unwrap();
+        assert!(text.contains("fn main()") && text.contains("code"));
+
+        let xml = r#"<?xml version="1.0"?><book id="b1"><meta>Ignored</meta>
+            <chapter><title>One</title><p>First <hi>para</hi>.</p></chapter><note><p>Aside</p></note></book>"#;
+        let options = MarkupOptions { xml_elements: vec!["chapter/p".to_string()], ..Default::default() };
+        assert_eq!(words(&extract_text(xml.to_string(), InputFormat::Xml, &options).unwrap()), vec!["First", "para."]);
+        let all = extract_text(xml.to_string(), InputFormat::Xml, &MarkupOptions::default()).unwrap();
+        assert_eq!(words(&all), vec!["Ignored", "One", "First", "para.", "Aside"]);
+        assert!(extract_text("<a>".to_string(), InputFormat::Xml, &MarkupOptions::default()).is_err());
+
+        // Inline elements inside a word keep it whole; block elements still separate
+        let tei = "<text><p><w>un<hi>believ</hi>able</w> <choice><sic>teh</sic></choice></p><p>end</p></text>";
+        let text = extract_text(tei.to_string(), InputFormat::Xml, &MarkupOptions::default()).unwrap();
+        assert_eq!(words(&text), vec!["unbelievable", "teh", "end"]);
+    }
+}
@@ -0,0 +1,85 @@
+pub mod compression;
+pub mod corpus;
+pub mod document;
+pub mod encoding;
+pub mod markup;
+pub mod pdf;
+pub mod series;
+pub mod structured;
+
+pub use compression::{open_input, strip_compression_extension, Compression, STDIN_PATH};
+pub use corpus::{expand_inputs, CorpusOptions, DirectoryLayout, InputSource};
+pub use document::{read_document, Section};
+pub use encoding::{decode, decode_reader, DecodeOptions, DecodeReport};
+pub use markup::{extract_text, InputFormat, MarkupOptions};
+pub use pdf::{read_pdf, PageRanges, PdfOptions};
+pub use series::{split_series, SeriesOptions};
+pub use structured::{read_records, DatePeriod, RecordOptions, MISSING_GROUP};
+
+use crate::code::CodeOptions;
+use crate::parser::Unit;
+use crate::tokens::TokenOptions;
+use anyhow::Result;
+use encoding_rs::{Encoding, UTF_8};
+use std::io::Read;
+
+/// Everything that controls how an input file becomes text and tokens.
+#[derive(Debug, Clone, Default)]
+pub struct ReadOptions {
+    pub decode: DecodeOptions,
+    pub format: InputFormat,
+    pub markup: MarkupOptions,
+    pub pdf: PdfOptions,
+    pub records: RecordOptions,
+    pub series: SeriesOptions,
+    pub code: Option<CodeOptions>, // Source-code identifiers instead of words
+    pub unit: Unit,
+    pub tokens: TokenOptions, // Numbers, URLs, mentions, ... in words
+}
+
+/// What reading an input found, for reporting.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReadReport {
+    pub encoding: &'static Encoding,
+    pub replacements: usize,  // Invalid sequences replaced (lossy decoding)
+    pub blank_pages: Vec<u32>, // PDF pages that gave no text
+}
+
+impl From<DecodeReport> for ReadReport {
+    fn from(report: DecodeReport) -> Self {
+        Self { encoding: report.encoding, replacements: report.replacements, blank_pages: Vec::new() }
+    }
+}
+
+/// Read an input as text: decompress, then unpack a document, extract PDF text, or decode
+/// and strip markup or pick record fields. EPUB inputs yield one section per chapter and
+/// grouped records one per group; everything else a single untitled section. With series
+/// options the text is then cut into equal segments or at heading lines instead.
+pub fn read_sections(file_path: &str, options: &ReadOptions) -> Result<(Vec<Section>, ReadReport)> {
+    let (sections, report) = read_format_sections(file_path, options)?;
+    Ok((split_series(sections, &options.series), report))
+}
+
+fn read_format_sections(file_path: &str, options: &ReadOptions) -> Result<(Vec<Section>, ReadReport)> {
+    let format = options.format.resolve(file_path);
+
+    // Binary formats carry their own text encoding and need the whole file, so they are
+    // buffered; everything else is decoded as it is decompressed
+    let binary_report = |blank_pages| ReadReport { encoding: UTF_8, replacements: 0, blank_pages };
+    if format.is_binary() {
+        let mut bytes = Vec::new();
+        open_input(file_path)?.read_to_end(&mut bytes)?;
+        if format == InputFormat::Pdf {
+            let (text, blank_pages) = read_pdf(&bytes, &options.pdf)?;
+            return Ok((vec![Section { title: String::new(), text }], binary_report(blank_pages)));
+        }
+        return Ok((read_document(bytes, format)?, binary_report(Vec::new())));
+    }
+
+    let (text, report) = decode_reader(open_input(file_path)?, &options.decode)?;
+    if format.is_structured() {
+        return Ok((read_records(&text, format, &options.records)?, report.into()));
+    }
+    let text = extract_text(text, format, &options.markup)?;
+    Ok((vec![Section { title: String::new(), text }], report.into()))
+}
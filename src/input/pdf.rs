@@ -0,0 +1,274 @@
+use anyhow::{bail, Context, Result};
+use pdf_extract::{output_doc_page, Document, PlainTextOutput};
+use std::collections::HashMap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::fmt;
+use std::str::FromStr;
+
+/// Lines at the top and bottom of each page that may be running headers or footers.
+const RUNNING_LINES: usize = 2;
+
+/// A line is a running header/footer when it appears on this share of pages (and on at least 2).
+const RUNNING_SHARE: f64 = 0.5;
+
+/// 1-based page selection such as `1-10,15,20-`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PageRanges(Vec<(u32, Option<u32>)>);
+
+impl PageRanges {
+    pub fn contains(&self, page: u32) -> bool {
+        self.0
+            .iter()
+            .any(|&(first, last)| page >= first && last.is_none_or(|last| page <= last))
+    }
+}
+
+impl FromStr for PageRanges {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        let parse = |page: &str| -> Result<u32, String> {
+            match page.trim().parse::<u32>() {
+                Ok(page) if page > 0 => Ok(page),
+                _ => Err(format!("invalid page number '{}' (pages start at 1)", page.trim())),
+            }
+        };
+
+        let mut ranges = Vec::new();
+        for part in spec.split(',').filter(|part| !part.trim().is_empty()) {
+            let range = match part.split_once('-') {
+                Some((first, "")) => (parse(first)?, None),
+                Some((first, last)) => (parse(first)?, Some(parse(last)?)),
+                None => (parse(part)?, Some(parse(part)?)),
+            };
+            if range.1.is_some_and(|last| last < range.0) {
+                return Err(format!("page range '{}' runs backwards", part.trim()));
+            }
+            ranges.push(range);
+        }
+
+        if ranges.is_empty() {
+            return Err("no pages given".to_string());
+        }
+        Ok(PageRanges(ranges))
+    }
+}
+
+impl fmt::Display for PageRanges {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let ranges: Vec<String> = self
+            .0
+            .iter()
+            .map(|&(first, last)| match last {
+                Some(last) if last == first => first.to_string(),
+                Some(last) => format!("{}-{}", first, last),
+                None => format!("{}-", first),
+            })
+            .collect();
+        write!(f, "{}", ranges.join(","))
+    }
+}
+
+/// PDF extraction settings.
+#[derive(Debug, Clone, Default)]
+pub struct PdfOptions {
+    pub pages: Option<PageRanges>, // All pages if None
+    pub strip_running_lines: bool, // Remove repeated running headers and footers
+}
+
+/// Extract the text of the selected pages, joining words hyphenated across line breaks.
+/// Returns the text and the pages that gave no text (scanned images, or extraction failures).
+pub fn read_pdf(bytes: &[u8], options: &PdfOptions) -> Result<(String, Vec<u32>)> {
+    let mut document = Document::load_mem(bytes).context("Not a readable PDF")?;
+    if document.is_encrypted() && document.decrypt("").is_err() {
+        bail!("PDF is encrypted with a password");
+    }
+
+    let mut pages = Vec::new();
+    let mut blank_pages = Vec::new();
+    for &page in document.get_pages().keys() {
+        if options.pages.as_ref().is_some_and(|ranges| !ranges.contains(page)) {
+            continue;
+        }
+
+        // Malformed fonts and content streams can make the extractor panic; treat those pages as blank
+        let text = catch_unwind(AssertUnwindSafe(|| {
+            let mut text = String::new();
+            output_doc_page(&document, &mut PlainTextOutput::new(&mut text), page).map(|_| text)
+        }));
+        match text {
+            Ok(Ok(text)) if !text.trim().is_empty() => pages.push(text),
+            _ => blank_pages.push(page),
+        }
+    }
+
+    if pages.is_empty() && blank_pages.is_empty() {
+        bail!("No pages selected (the PDF has {} pages)", document.get_pages().len());
+    }
+
+    if options.strip_running_lines {
+        strip_running_lines(&mut pages);
+    }
+
+    Ok((dehyphenate(&pages.join("\n")), blank_pages))
+}
+
+/// Drop lines that repeat near the top or bottom of many pages. Digits are ignored when
+/// comparing, so page numbers ("Page 3", "- 12 -") count as the same line.
+fn strip_running_lines(pages: &mut [String]) {
+    let normalize = |line: &str| -> String {
+        line.trim()
+            .chars()
+            .map(|c| if c.is_ascii_digit() { '#' } else { c.to_ascii_lowercase() })
+            .collect()
+    };
+    let edge_lines = |page: &str| -> Vec<String> {
+        let lines: Vec<&str> = page.lines().filter(|line| !line.trim().is_empty()).collect();
+        let mut edges: Vec<String> = lines.iter().take(RUNNING_LINES).map(|line| normalize(line)).collect();
+        edges.extend(lines.iter().rev().take(RUNNING_LINES).map(|line| normalize(line)));
+        edges.sort();
+        edges.dedup();
+        edges
+    };
+
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for page in pages.iter() {
+        for line in edge_lines(page) {
+            *counts.entry(line).or_default() += 1;
+        }
+    }
+    let threshold = ((pages.len() as f64 * RUNNING_SHARE).ceil() as usize).max(2);
+
+    for page in pages.iter_mut() {
+        let running: Vec<String> = edge_lines(page)
+            .into_iter()
+            .filter(|line| counts.get(line).is_some_and(|&count| count >= threshold))
+            .collect();
+        if running.is_empty() {
+            continue;
+        }
+        *page = page
+            .lines()
+            .filter(|line| !running.contains(&normalize(line)))
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+}
+
+/// Join words split by a hyphen at the end of a line ("exam-\nple" → "example").
+/// Only lowercase continuations are joined, so "Anglo-\nSaxon" keeps its hyphen.
+fn dehyphenate(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let lines: Vec<&str> = text.lines().collect();
+    let mut i = 0;
+
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim_end();
+        let before_hyphen = trimmed.strip_suffix('-').and_then(|rest| rest.chars().last());
+
+        // The other half may follow blank lines (page breaks); they are only dropped on a join
+        let next_index = (i + 1..lines.len()).find(|&j| !lines[j].trim().is_empty());
+        let continues = next_index
+            .and_then(|j| lines[j].trim_start().chars().next())
+            .is_some_and(|first| first.is_lowercase());
+
+        let join = next_index.filter(|_| before_hyphen.is_some_and(char::is_alphabetic) && continues);
+        if let Some(j) = join {
+            result.push_str(&trimmed[..trimmed.len() - 1]);
+            // Glue the first word of the next line onto this one
+            let next = lines[j].trim_start();
+            let (first_word, rest) = next.split_once(char::is_whitespace).unwrap_or((next, ""));
+            result.push_str(first_word);
+            result.push('\n');
+            result.push_str(rest);
+            result.push('\n');
+            i = j + 1;
+        } else {
+            result.push_str(line);
+            result.push('\n');
+            i += 1;
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lopdf::content::{Content, Operation};
+    use lopdf::{dictionary, Object, Stream};
+
+    /// A PDF with one text line per entry, top to bottom, on each page.
+    fn pdf(pages: &[&[&str]]) -> Vec<u8> {
+        let mut document = lopdf::Document::with_version("1.5");
+        let pages_id = document.new_object_id();
+        let font_id = document.add_object(dictionary! {
+            "Type" => "Font", "Subtype" => "Type1", "BaseFont" => "Helvetica", "Encoding" => "WinAnsiEncoding",
+        });
+        let resources_id = document.add_object(dictionary! { "Font" => dictionary! { "F1" => font_id } });
+
+        let mut kids = Vec::new();
+        for lines in pages {
+            let mut operations = vec![Operation::new("BT", vec![]), Operation::new("Tf", vec!["F1".into(), 12.into()])];
+            for (i, line) in lines.iter().enumerate() {
+                let y = 800 - 20 * i as i64;
+                operations.push(Operation::new("Tm", vec![1.into(), 0.into(), 0.into(), 1.into(), 50.into(), y.into()]));
+                operations.push(Operation::new("Tj", vec![Object::string_literal(*line)]));
+            }
+            operations.push(Operation::new("ET", vec![]));
+            let content = Content { operations }.encode().unwrap();
+            let content_id = document.add_object(Stream::new(dictionary! {}, content));
+            kids.push(Object::from(document.add_object(dictionary! {
+                "Type" => "Page", "Parent" => pages_id, "Contents" => content_id,
+            })));
+        }
+
+        let count = kids.len() as i64;
+        document.objects.insert(pages_id, Object::Dictionary(dictionary! {
+            "Type" => "Pages", "Kids" => kids, "Count" => count, "Resources" => resources_id,
+            "MediaBox" => vec![0.into(), 0.into(), 595.into(), 842.into()],
+        }));
+        let catalog_id = document.add_object(dictionary! { "Type" => "Catalog", "Pages" => pages_id });
+        document.trailer.set("Root", catalog_id);
+
+        let mut bytes = Vec::new();
+        document.save_to(&mut bytes).unwrap();
+        bytes
+    }
+
+    fn words(text: &str) -> Vec<&str> {
+        text.split_whitespace().collect()
+    }
+
+    #[test]
+    fn test_pdf_pages_headers_and_hyphenation() {
+        assert_eq!("2-3,5,9-".parse::<PageRanges>().unwrap().0, vec![(2, Some(3)), (5, Some(5)), (9, None)]);
+        assert_eq!("2-3, 5-5,9-".parse::<PageRanges>().unwrap().to_string(), "2-3,5,9-");
+        assert!("3-1".parse::<PageRanges>().is_err());
+        assert!("0".parse::<PageRanges>().is_err());
+
+        assert_eq!(words(&dehyphenate("an exam-\nple here\nAnglo-\nSaxon")), vec!["an", "example", "here", "Anglo-", "Saxon"]);
+        assert_eq!(dehyphenate("exam-\n\n\nple"), "example\n\n");
+        // Lines that are not joined keep the paragraph breaks after them
+        assert_eq!(dehyphenate("in 2020-\n\nnext paragraph\nx -\n\nmore"), "in 2020-\n\nnext paragraph\nx -\n\nmore\n");
+
+        let bytes = pdf(&[
+            &["Journal of Tests", "The first exam-", "ple of text", "Page 1"],
+            &["Journal of Tests", "Second page words", "Page 2"],
+            &[],
+            &["Journal of Tests", "Last page", "Page 4"],
+        ]);
+
+        let (text, blank_pages) = read_pdf(&bytes, &PdfOptions::default()).unwrap();
+        assert!(words(&text).contains(&"example"), "{}", text);
+        assert_eq!(words(&text).iter().filter(|word| **word == "Journal").count(), 3);
+        assert_eq!(blank_pages, vec![3]);
+
+        let options = PdfOptions { pages: Some("2-4".parse().unwrap()), strip_running_lines: true };
+        let (text, blank_pages) = read_pdf(&bytes, &options).unwrap();
+        assert_eq!(words(&text), vec!["Second", "page", "words", "Last", "page"]);
+        assert_eq!(blank_pages, vec![3]);
+    }
+}
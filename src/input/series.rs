@@ -0,0 +1,100 @@
+use crate::input::Section;
+use regex::Regex;
+
+/// How one input is cut into a series of sections, in text order.
+#[derive(Debug, Clone, Default)]
+pub struct SeriesOptions {
+    pub segments: Option<usize>, // N parts with (nearly) equal word counts
+    pub split_on: Option<Regex>, // Lines matching this start a new part, e.g. chapter headings
+}
+
+impl SeriesOptions {
+    pub fn is_active(&self) -> bool {
+        self.segments.is_some() || self.split_on.is_some()
+    }
+}
+
+/// Join the sections of an input and cut the text into a series. Equal segments are
+/// untitled; with `split_on` each part is titled by its heading line, and text before the
+/// first heading becomes an untitled part when it has any words.
+pub fn split_series(sections: Vec<Section>, options: &SeriesOptions) -> Vec<Section> {
+    if !options.is_active() {
+        return sections;
+    }
+    let text = sections.into_iter().map(|section| section.text).collect::<Vec<_>>().join("\n");
+
+    match (&options.split_on, options.segments) {
+        (Some(pattern), _) => split_on_headings(&text, pattern),
+        (None, Some(segments)) => split_equal(&text, segments),
+        (None, None) => unreachable!(),
+    }
+}
+
+fn split_on_headings(text: &str, pattern: &Regex) -> Vec<Section> {
+    let mut parts = vec![Section { title: String::new(), text: String::new() }];
+    for line in text.lines() {
+        if pattern.is_match(line) {
+            parts.push(Section { title: line.trim().to_string(), text: String::new() });
+        } else {
+            let part = parts.last_mut().expect("always one part");
+            part.text.push_str(line);
+            part.text.push('\n');
+        }
+    }
+    if parts[0].text.split_whitespace().next().is_none() {
+        parts.remove(0);
+    }
+    parts
+}
+
+/// Cut before the words that start each of `segments` parts; a part never splits a word.
+fn split_equal(text: &str, segments: usize) -> Vec<Section> {
+    // Byte offset where each whitespace-separated word starts
+    let starts: Vec<usize> = text
+        .char_indices()
+        .scan(true, |after_space, (offset, c)| {
+            let start = *after_space && !c.is_whitespace();
+            *after_space = c.is_whitespace();
+            Some((offset, start))
+        })
+        .filter_map(|(offset, start)| start.then_some(offset))
+        .collect();
+
+    let segments = segments.clamp(1, starts.len().max(1));
+    let cuts: Vec<usize> = (1..segments).map(|part| starts[part * starts.len() / segments]).collect();
+    let bounds = std::iter::once(0).chain(cuts).chain(std::iter::once(text.len())).collect::<Vec<_>>();
+    bounds
+        .windows(2)
+        .map(|window| Section { title: String::new(), text: text[window[0]..window[1]].to_string() })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(text: &str, options: &SeriesOptions) -> Vec<(String, String)> {
+        let sections = vec![Section { title: String::new(), text: text.to_string() }];
+        split_series(sections, options).into_iter().map(|section| (section.title, section.text)).collect()
+    }
+
+    #[test]
+    fn test_segments_and_headings() {
+        let equal = SeriesOptions { segments: Some(3), ..Default::default() };
+        let parts = split("one two three\nfour  five six seven", &equal);
+        assert_eq!(parts.iter().map(|(_, text)| text.as_str()).collect::<Vec<_>>(), vec!["one two ", "three\nfour  ", "five six seven"]);
+        assert_eq!(split("one two", &SeriesOptions { segments: Some(5), ..Default::default() }).len(), 2);
+
+        let chapters = SeriesOptions { split_on: Some(Regex::new(r"^CHAPTER \w+").unwrap()), ..Default::default() };
+        let text = "Title page\nCHAPTER I\nIt was a dark night.\nCHAPTER II \nThe end.\n";
+        assert_eq!(
+            split(text, &chapters),
+            vec![
+                (String::new(), "Title page\n".to_string()),
+                ("CHAPTER I".to_string(), "It was a dark night.\n".to_string()),
+                ("CHAPTER II".to_string(), "The end.\n".to_string()),
+            ]
+        );
+        assert_eq!(split("\nCHAPTER I\nText\n", &chapters).len(), 1);
+    }
+}
@@ -0,0 +1,308 @@
+use crate::input::{InputFormat, Section};
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Group name for records without a value in the `--group-by` column or field.
+pub const MISSING_GROUP: &str = "(none)";
+
+/// Which part of each record holds the text, and how records are grouped.
+#[derive(Debug, Clone, Default)]
+pub struct RecordOptions {
+    pub column: Option<String>,     // CSV/TSV header name or 1-based column number
+    pub field: Option<String>,      // JSON field path (`.body`, `user.bio`, `items[0]`) or pointer (`/body`)
+    pub group_by: Option<String>,   // Column or field whose value splits records into groups
+    pub date_field: Option<String>, // Column or field with a date; groups records by `period`, oldest first
+    pub period: DatePeriod,
+}
+
+/// How finely `--date-field` groups records.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum DatePeriod {
+    Year,
+    #[default]
+    Month,
+    Day,
+}
+
+impl DatePeriod {
+    /// The `YYYY`, `YYYY-MM` or `YYYY-MM-DD` key of a date: ISO 8601 (`2024-05-01`, `2024/05/01T10:00`),
+    /// compact `YYYYMM` or `YYYYMMDD`, Unix seconds (9 to 11 digits) or milliseconds (13 digits).
+    /// Other all-digit values, like ids, are not dates. Missing parts of a shorter date (`2024`)
+    /// make the key coarser.
+    pub fn key(self, date: &str) -> Option<String> {
+        let date = date.trim();
+        let digits = date.bytes().all(|b| b.is_ascii_digit());
+        let part = |range: std::ops::Range<usize>, max: u32| date[range].parse::<u32>().ok().filter(|n| (1..=max).contains(n));
+        let (year, month, day) = match date.len() {
+            6 | 8 if digits => {
+                let day = if date.len() == 8 { Some(part(6..8, 31)?) } else { None };
+                (date[..4].parse().ok()?, Some(part(4..6, 12)?), day)
+            }
+            9..=11 if digits => civil_date(date.parse::<i64>().ok()?.div_euclid(86_400)),
+            13 if digits => civil_date(date.parse::<i64>().ok()?.div_euclid(86_400_000)),
+            length if digits && length != 4 => return None,
+            _ => {
+                let mut parts = date.split(['-', '/', 'T', ' ']);
+                let year: i64 = parts.next().filter(|year| year.len() == 4)?.parse().ok()?;
+                let mut number = |max: u32| parts.next().and_then(|part| part.parse::<u32>().ok()).filter(|n| (1..=max).contains(n));
+                let month = number(12);
+                let day = month.and_then(|_| number(31));
+                (year, month, day)
+            }
+        };
+        Some(match (self, month, day) {
+            (DatePeriod::Day, Some(month), Some(day)) => format!("{:04}-{:02}-{:02}", year, month, day),
+            (DatePeriod::Day | DatePeriod::Month, Some(month), _) => format!("{:04}-{:02}", year, month),
+            _ => format!("{:04}", year),
+        })
+    }
+}
+
+/// Year, month and day of a day count since 1970-01-01 (proleptic Gregorian calendar).
+fn civil_date(days: i64) -> (i64, Option<u32>, Option<u32>) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, Some(month), Some(day))
+}
+
+/// Text of each record, as one section, or one section per group (in order of first appearance;
+/// date groups in date order, with undated records last).
+pub fn read_records(text: &str, format: InputFormat, options: &RecordOptions) -> Result<Vec<Section>> {
+    if options.group_by.is_some() && options.date_field.is_some() {
+        bail!("Records can be grouped by --group-by or --date-field, not both");
+    }
+    let records = match format {
+        InputFormat::Csv => csv_records(text, b',', options)?,
+        InputFormat::Tsv => csv_records(text, b'\t', options)?,
+        InputFormat::Json => {
+            let value: Value = serde_json::from_str(text).context("Invalid JSON")?;
+            let values = match value {
+                Value::Array(values) => values,
+                value => vec![value],
+            };
+            json_records(values.iter(), options)?
+        }
+        InputFormat::Jsonl => {
+            let values = text
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(i, line)| serde_json::from_str(line).with_context(|| format!("Invalid JSON on line {}", i + 1)))
+                .collect::<Result<Vec<Value>>>()?;
+            json_records(values.iter(), options)?
+        }
+        _ => bail!("{:?} is not a record format", format),
+    };
+
+    // Grouping nothing would yield no datasets at all
+    let grouped = options.group_by.is_some() || options.date_field.is_some();
+    if grouped && records.is_empty() {
+        bail!("No records to group: the file has no data rows");
+    }
+
+    if options.date_field.is_none() {
+        return Ok(group_records(records, options.group_by.is_some()));
+    }
+    let dated = records.into_iter().map(|(date, text)| (date.and_then(|date| options.period.key(&date)), text)).collect();
+    let mut sections = group_records(dated, true);
+    // Keys sort chronologically as text; MISSING_GROUP sorts last
+    sections.sort_by_key(|section| (section.title == MISSING_GROUP, section.title.clone()));
+    Ok(sections)
+}
+
+/// (group, text) pairs in input order.
+type Records = Vec<(Option<String>, String)>;
+
+fn group_records(records: Records, grouped: bool) -> Vec<Section> {
+    if !grouped {
+        let text = records.into_iter().map(|(_, text)| text).collect::<Vec<_>>().join("\n");
+        return vec![Section { title: String::new(), text }];
+    }
+
+    // Sections stay in first-seen order; the index finds a group's section without a scan
+    let mut sections: Vec<Section> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    for (group, text) in records {
+        let title = group.unwrap_or_else(|| MISSING_GROUP.to_string());
+        match index.get(&title) {
+            Some(&i) => {
+                sections[i].text.push('\n');
+                sections[i].text.push_str(&text);
+            }
+            None => {
+                index.insert(title.clone(), sections.len());
+                sections.push(Section { title, text });
+            }
+        }
+    }
+    sections
+}
+
+fn csv_records(text: &str, delimiter: u8, options: &RecordOptions) -> Result<Records> {
+    let mut reader = csv::ReaderBuilder::new().delimiter(delimiter).flexible(true).from_reader(text.as_bytes());
+    let headers: Vec<String> = reader.headers()?.iter().map(str::to_string).collect();
+
+    let column = match options.column.as_deref() {
+        Some(column) => column_index(&headers, column)?,
+        None if headers.len() == 1 => 0,
+        None => bail!("Choose the text column with --column (columns: {})", headers.join(", ")),
+    };
+    let group = options.group_by.as_deref().or(options.date_field.as_deref()).map(|group| column_index(&headers, group)).transpose()?;
+
+    let mut records = Vec::new();
+    for (i, record) in reader.records().enumerate() {
+        // Header is line 1
+        let record = record.with_context(|| format!("Invalid CSV record on line {}", i + 2))?;
+        let group = group.map(|index| record.get(index).unwrap_or("").trim()).filter(|value| !value.is_empty());
+        records.push((group.map(str::to_string), record.get(column).unwrap_or("").to_string()));
+    }
+    Ok(records)
+}
+
+/// Find a column by header name (exact, then ignoring case) or 1-based number.
+fn column_index(headers: &[String], column: &str) -> Result<usize> {
+    if let Some(index) = headers.iter().position(|header| header == column) {
+        return Ok(index);
+    }
+    if let Some(index) = headers.iter().position(|header| header.eq_ignore_ascii_case(column)) {
+        return Ok(index);
+    }
+    match column.parse::<usize>() {
+        Ok(number) if (1..=headers.len()).contains(&number) => Ok(number - 1),
+        _ => bail!("No column '{}' (columns: {})", column, headers.join(", ")),
+    }
+}
+
+fn json_records<'a>(values: impl Iterator<Item = &'a Value>, options: &RecordOptions) -> Result<Records> {
+    let field = options.field.as_deref().map(json_pointer);
+    let group = options.group_by.as_deref().or(options.date_field.as_deref()).map(json_pointer);
+
+    let mut records = Vec::new();
+    for value in values {
+        let text = match &field {
+            Some(pointer) => value.pointer(pointer).map(json_text).unwrap_or_default(),
+            None => match value {
+                Value::String(text) => text.clone(),
+                _ => bail!("Records are not strings; choose the text field with --field (e.g. --field .body)"),
+            },
+        };
+        let group = group.as_ref().and_then(|pointer| value.pointer(pointer)).map(json_text).filter(|group| !group.is_empty());
+        records.push((group, text));
+    }
+    Ok(records)
+}
+
+/// Turn a field path (`.user.bio`, `items[0]`) into a JSON pointer; pointers pass through.
+fn json_pointer(path: &str) -> String {
+    if path.starts_with('/') {
+        return path.to_string();
+    }
+    path.replace('[', ".")
+        .replace(']', "")
+        .split('.')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| format!("/{}", segment.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+/// Text of a JSON value: strings as is, arrays joined line by line, other scalars printed.
+fn json_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Array(values) => values.iter().map(json_text).collect::<Vec<_>>().join("\n"),
+        Value::Null | Value::Object(_) => String::new(),
+        scalar => scalar.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sections(text: &str, format: InputFormat, options: &RecordOptions) -> Vec<(String, String)> {
+        read_records(text, format, options)
+            .unwrap()
+            .into_iter()
+            .map(|section| (section.title, section.text))
+            .collect()
+    }
+
+    #[test]
+    fn test_grouping_no_records_is_an_error() {
+        let options = RecordOptions { column: Some("text".to_string()), group_by: Some("author".to_string()), ..Default::default() };
+        let error = read_records("author,text\n", InputFormat::Csv, &options).unwrap_err();
+        assert!(error.to_string().starts_with("No records to group"));
+
+        // Without grouping, an empty file is one empty text
+        let options = RecordOptions { column: Some("text".to_string()), ..Default::default() };
+        assert_eq!(sections("author,text\n", InputFormat::Csv, &options), vec![(String::new(), String::new())]);
+    }
+
+    #[test]
+    fn test_columns_fields_and_groups() {
+        let csv = "id,author,text\n1,ann,\"Hello, world\"\n2,bob,Second\n3,ann,Third\n4,,Fourth\n";
+        let options = RecordOptions { column: Some("Text".to_string()), ..Default::default() };
+        assert_eq!(sections(csv, InputFormat::Csv, &options), vec![(String::new(), "Hello, world\nSecond\nThird\nFourth".to_string())]);
+
+        let options = RecordOptions { column: Some("3".to_string()), group_by: Some("author".to_string()), ..Default::default() };
+        assert_eq!(
+            sections(csv, InputFormat::Csv, &options),
+            vec![
+                ("ann".to_string(), "Hello, world\nThird".to_string()),
+                ("bob".to_string(), "Second".to_string()),
+                (MISSING_GROUP.to_string(), "Fourth".to_string()),
+            ]
+        );
+        assert!(read_records(csv, InputFormat::Csv, &RecordOptions::default()).is_err());
+        assert!(read_records("a\tb\n1\t2\n", InputFormat::Tsv, &RecordOptions { column: Some("c".to_string()), ..Default::default() }).is_err());
+
+        let jsonl = "{\"body\": \"first post\", \"user\": {\"name\": \"ann\"}, \"tags\": [\"x\", \"y\"]}\n\n{\"body\": \"second\", \"user\": {\"name\": \"bob\"}}\n";
+        let options = RecordOptions { field: Some(".body".to_string()), group_by: Some("user.name".to_string()), ..Default::default() };
+        assert_eq!(
+            sections(jsonl, InputFormat::Jsonl, &options),
+            vec![("ann".to_string(), "first post".to_string()), ("bob".to_string(), "second".to_string())]
+        );
+        let options = RecordOptions { field: Some("/tags".to_string()), ..Default::default() };
+        assert_eq!(sections(jsonl, InputFormat::Jsonl, &options)[0].1, "x\ny\n");
+
+        let json = r#"["one", "two"]"#;
+        assert_eq!(sections(json, InputFormat::Json, &RecordOptions::default())[0].1, "one\ntwo");
+        assert!(read_records("{\"a\": 1}\nnot json", InputFormat::Jsonl, &RecordOptions::default())
+            .unwrap_err()
+            .to_string()
+            .contains("line 2"));
+
+        let dated = "date,text\n2024-05-20,late\n2023-12-01T08:00,early\n,undated\n2024/05/02,may\n1714521600,unix\n";
+        let options = RecordOptions { column: Some("text".to_string()), date_field: Some("date".to_string()), ..Default::default() };
+        assert_eq!(
+            sections(dated, InputFormat::Csv, &options),
+            vec![
+                ("2023-12".to_string(), "early".to_string()),
+                ("2024-05".to_string(), "late\nmay\nunix".to_string()),
+                (MISSING_GROUP.to_string(), "undated".to_string()),
+            ]
+        );
+        assert_eq!(DatePeriod::Day.key("1714521600"), Some("2024-05-01".to_string()));
+        assert_eq!(DatePeriod::Day.key("1714521600000"), Some("2024-05-01".to_string()));
+        assert_eq!(DatePeriod::Day.key("20240315"), Some("2024-03-15".to_string()));
+        assert_eq!(DatePeriod::Month.key("20241315"), None);
+        assert_eq!(DatePeriod::Day.key("202405"), Some("2024-05".to_string()));
+        assert_eq!(DatePeriod::Month.key("202413"), None);
+        // Ids and other digit runs that are neither compact dates nor timestamps
+        assert_eq!(DatePeriod::Month.key("123456"), None);
+        assert_eq!(DatePeriod::Month.key("12345"), None);
+        assert_eq!(DatePeriod::Month.key("123456789012"), None);
+        assert_eq!(DatePeriod::Day.key("999999999"), Some("2001-09-09".to_string()));
+        assert_eq!(DatePeriod::Day.key("2024"), Some("2024".to_string()));
+        assert_eq!(DatePeriod::Year.key("May 2024"), None);
+    }
+}
@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use whatlang::{Detector, Lang};
+
+/// Number of leading words sampled for detection; more adds cost but little accuracy.
+const DETECTION_SAMPLE_WORDS: usize = 5000;
+
+/// Stop-word lists bundled into the binary, keyed by ISO 639-3 code.
+/// English is absent on purpose: the English list comes from `tags.toml`.
+const BUNDLED_STOPWORDS: &[(&str, &str)] = &[
+    ("fra", include_str!("stopwords/fra.txt")),
+    ("deu", include_str!("stopwords/deu.txt")),
+    ("spa", include_str!("stopwords/spa.txt")),
+    ("ita", include_str!("stopwords/ita.txt")),
+    ("por", include_str!("stopwords/por.txt")),
+    ("nld", include_str!("stopwords/nld.txt")),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Language {
+    pub code: String, // ISO 639-3, e.g. "fra"
+    pub name: String, // English name, e.g. "French"
+    pub confidence: f64,
+}
+
+impl Language {
+    fn from_lang(lang: Lang, confidence: f64) -> Self {
+        Self {
+            code: lang.code().to_string(),
+            name: lang.eng_name().to_string(),
+            confidence,
+        }
+    }
+
+    /// Parse a user-supplied language code (ISO 639-3 like "fra", or 639-1 like "fr").
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code = code.to_lowercase();
+        let lang = Lang::from_code(code.as_str()).or_else(|| {
+            Lang::all()
+                .iter()
+                .copied()
+                .find(|lang| iso_639_1(*lang) == Some(code.as_str()))
+        })?;
+        Some(Self::from_lang(lang, 1.0))
+    }
+
+    /// Bundled stop words for this language, if any.
+    pub fn stopwords(&self) -> Option<Vec<&'static str>> {
+        BUNDLED_STOPWORDS
+            .iter()
+            .find(|(code, _)| *code == self.code)
+            .map(|(_, list)| {
+                list.lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .collect()
+            })
+    }
+}
+
+/// Identify the language of a parsed word stream using bundled trigram profiles.
+/// Returns `None` when the sample is too small or detection is unreliable.
+pub fn detect_language<'a>(words: impl IntoIterator<Item = &'a String>) -> Option<Language> {
+    let sample = words
+        .into_iter()
+        .take(DETECTION_SAMPLE_WORDS)
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let info = Detector::new().detect(&sample)?;
+    if !info.is_reliable() {
+        return None;
+    }
+
+    Some(Language::from_lang(info.lang(), info.confidence()))
+}
+
+fn iso_639_1(lang: Lang) -> Option<&'static str> {
+    match lang {
+        Lang::Eng => Some("en"),
+        Lang::Fra => Some("fr"),
+        Lang::Deu => Some("de"),
+        Lang::Spa => Some("es"),
+        Lang::Ita => Some("it"),
+        Lang::Por => Some("pt"),
+        Lang::Nld => Some("nl"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(text: &str) -> Vec<String> {
+        text.split_whitespace().map(|w| w.to_lowercase()).collect()
+    }
+
+    #[test]
+    fn test_detect_language() {
+        let french = words(
+            "le chat est sur la table et il regarde les oiseaux dans le jardin \
+             pendant que nous préparons le dîner avec nos amis de la ville",
+        );
+        let german = words(
+            "der hund läuft schnell über die straße und die kinder spielen \
+             im garten während wir das abendessen für unsere freunde kochen",
+        );
+
+        assert_eq!(detect_language(&french).unwrap().code, "fra");
+        assert_eq!(detect_language(&german).unwrap().code, "deu");
+    }
+
+    #[test]
+    fn test_language_codes_and_stopwords() {
+        let french = Language::from_code("fr").unwrap();
+        assert_eq!(french.code, "fra");
+        assert!(french.stopwords().unwrap().contains(&"les"));
+
+        assert_eq!(Language::from_code("deu").unwrap().name, "German");
+        assert!(Language::from_code("eng").unwrap().stopwords().is_none());
+        assert!(Language::from_code("xx").is_none());
+    }
+}
@@ -1,7 +1,23 @@
 pub mod parser;
 pub mod analyzer;
+pub mod bootstrap;
 pub mod cli;
+pub mod code;
+pub mod combine;
+pub mod export;
+pub mod filter;
+pub mod input;
+pub mod language;
+pub mod plot;
+pub mod report;
+pub mod sample;
+pub mod segment;
+pub mod snapshot;
+pub mod stats;
+pub mod text_report;
+pub mod tokens;
 pub mod tui;
+pub mod view;
 
 pub use analyzer::{WordCount, WordAnalyzer};
 pub use parser::TextParser;
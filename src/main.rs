@@ -1,79 +1,798 @@
+use anyhow::Context;
 use clap::Parser;
 use crossterm::{
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
-use std::io;
-use std::time::Instant;
-use zipfr::{analyzer::{WordAnalyzer, TagMatcher, Dataset}, cli::Args, parser::TextParser, tui::App};
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+use zipfr::{
+    analyzer::{language_tag_matcher, WordAnalyzer, Tag, TagMatcher, Dataset},
+    bootstrap::{bootstrap, Resample},
+    code::{CodeLanguage, Tokenizer},
+    cli::{AnalyzeArgs, Args, ChartArgs, Command, CompareArgs, ExportArgs, FilterArgs, FitArgs, InputArgs, NgramArgs, TagsArgs},
+    combine::{difference_dataset, merge_datasets, ratio_dataset},
+    export::{write_export, ExportContext, ExportFormat},
+    filter::{filter_datasets, FilterSet},
+    input::{expand_inputs, strip_compression_extension, InputFormat, InputSource, ReadOptions, STDIN_PATH},
+    language::{detect_language, Language},
+    parser::{TextParser, Unit},
+    plot::{write_chart, ChartFormat, ChartOptions, ChartSeries},
+    report::{write_report, ReportContext},
+    sample::{sample_tokens, SampleMode},
+    segment::SegmentStats,
+    snapshot::{is_snapshot_path, snapshot_path_for, Snapshot, SourceStatus},
+    stats::fit_zipf,
+    text_report::{format_interval, write_comparison, write_multi_results, write_rarefaction},
+    tui::App,
+};
+
+const DEFAULT_TOP: usize = 20;
 
 fn main() -> anyhow::Result<()> {
     let args = Args::parse();
 
-    let start_time = Instant::now();
-    
-    // Try to load tags configuration once for all datasets
-    let tag_matcher = TagMatcher::from_config("tags.toml").ok();
-    
-    // Process each file into a dataset
-    let mut datasets = Vec::new();
-    
-    for (i, file_path) in args.files.iter().enumerate() {
-        let parse_start = Instant::now();
-        let words = TextParser::parse_file(file_path)?;
-        let parse_duration = parse_start.elapsed();
-        
-        let analyze_start = Instant::now();
-        
-        let mut analyzer = if let Some(ref tag_matcher) = tag_matcher {
-            WordAnalyzer::with_tags(tag_matcher.clone())
-        } else {
-            WordAnalyzer::new()
-        };
-        
-        let word_counts = analyzer.analyze(words);
-        let analyze_duration = analyze_start.elapsed();
-        
-        // Determine dataset name (custom name or filename)
-        let dataset_name = if i < args.names.len() {
-            args.names[i].clone()
+    // Load tags configuration once for all datasets (optional, but must be valid if present)
+    let tag_matcher = if std::path::Path::new(&args.tags_config).exists() {
+        Some(TagMatcher::from_config(&args.tags_config)
+            .with_context(|| format!("Invalid {}", args.tags_config))?)
+    } else {
+        None
+    };
+
+    match args.command {
+        Some(Command::Analyze(analyze)) => run_analyze(&analyze, tag_matcher.as_ref()),
+        Some(Command::Compare(compare)) => run_compare(&compare, tag_matcher.as_ref()),
+        Some(Command::Fit(fit)) => run_fit(&fit, tag_matcher.as_ref()),
+        Some(Command::Ngrams(ngrams)) => run_ngrams(&ngrams, tag_matcher.as_ref()),
+        Some(Command::Tags(tags)) => run_tags(&tags, &args.tags_config, tag_matcher.as_ref()),
+        Some(Command::Export(export)) => run_export(&export, tag_matcher.as_ref()),
+        Some(Command::ExportChart(chart)) => run_export_chart(&chart, tag_matcher.as_ref()),
+        None => run_analyze(&args.analyze, tag_matcher.as_ref()),
+    }
+}
+
+/// Parse and analyze every input into a dataset. `ngram_size` > 1 counts word
+/// n-grams instead of single words. Directories and glob patterns are expanded
+/// first; snapshot inputs (`.zipfr`) are loaded instead of parsed unless their
+/// source file has changed.
+fn load_datasets(
+    input: &InputArgs,
+    tag_matcher: Option<&TagMatcher>,
+    ngram_size: usize,
+) -> anyhow::Result<Vec<Dataset>> {
+    // A fixed language applies to every file; "auto" detects per file
+    let forced_language = match input.language.as_str() {
+        "auto" | "none" => None,
+        code => Some(Language::from_code(code)
+            .with_context(|| format!("Unknown language code '{}'", code))?),
+    };
+
+    // Notes about unsaved snapshots are given once, however many files they apply to
+    let noted = RefCell::new(HashSet::new());
+    let note = |message: &'static str| {
+        if noted.borrow_mut().insert(message) {
+            eprintln!("Note: {}", message);
+        }
+    };
+
+    // Most files give one dataset; EPUBs give one per chapter with --epub-chapters, record
+    // files one per group with --group-by or --date-field, and any file one per segment
+    // with --segments or --split-on
+    let series = input.segments.is_some() || input.split_on.is_some();
+    let splits = |file_path: &str| {
+        let format = input.input_format.resolve(file_path);
+        let chapters = input.epub_chapters && format == InputFormat::Epub;
+        let groups = (input.group_by.is_some() || input.date_field.is_some()) && format.is_structured();
+        series || chapters || groups
+    };
+    let split = |file_path: &str| {
+        if input.save_snapshot {
+            note("snapshots are not saved for segments, EPUB chapters or record groups");
+        }
+        let numbered = series || input.input_format.resolve(file_path) == InputFormat::Epub;
+        analyze_sections(file_path, numbered, input, forced_language.as_ref(), tag_matcher, ngram_size)
+    };
+    let load = |file_path: &str| -> anyhow::Result<Vec<Dataset>> {
+        if is_snapshot_path(file_path) {
+            Ok(vec![load_snapshot(file_path, input, forced_language.as_ref(), tag_matcher, ngram_size)?])
+        } else if splits(file_path) {
+            split(file_path)
         } else {
-            std::path::Path::new(file_path)
-                .file_stem()
-                .and_then(|s| s.to_str())
-                .unwrap_or("Unknown")
-                .to_string()
+            let dataset = analyze_file(file_path, input, forced_language.as_ref(), tag_matcher, ngram_size)?;
+            if input.save_snapshot && file_path == STDIN_PATH {
+                eprintln!("Note: snapshots are not saved for standard input");
+            } else if input.save_snapshot {
+                save_snapshot(&dataset, file_path, &snapshot_path_for(file_path), input, ngram_size)?;
+            }
+            Ok(vec![dataset])
+        }
+    };
+
+    let mut datasets = Vec::new();
+
+    for source in expand_inputs(&input.files, &input.corpus_options()?)? {
+        match source {
+            InputSource::File(file_path) => datasets.extend(load(&file_path)?),
+            InputSource::Group { name, files } => {
+                if input.save_snapshot {
+                    note("snapshots are saved for single files only, not for directory inputs");
+                }
+                // Files that split are cut on their own, as if given directly; the rest of the
+                // group is still read as one dataset
+                let (split_files, rest): (Vec<String>, Vec<String>) = files.into_iter().partition(|file| splits(file));
+                for file_path in &split_files {
+                    datasets.extend(split(file_path)?);
+                }
+                if !rest.is_empty() {
+                    datasets.push(analyze_files(&name, &rest, input, forced_language.as_ref(), tag_matcher, ngram_size)?);
+                }
+            }
+        }
+    }
+
+    // Custom names override file, directory, chapter and snapshot names
+    for (dataset, name) in datasets.iter_mut().zip(&input.names) {
+        dataset.name = name.clone();
+    }
+
+    // --merge NAME=FILE,FILE: one dataset summing the counts of every file in the group
+    for group in &input.merges {
+        let (name, files) = match group.split_once('=') {
+            Some((name, files)) => (Some(name.trim()), files),
+            None => (None, group.as_str()),
         };
-        
-        datasets.push(Dataset {
-            name: dataset_name,
-            word_counts,
-            total_words: analyzer.total_words(),
-            unique_words: analyzer.unique_words(),
-            parse_duration,
-            analyze_duration,
+        let files: Vec<String> = files.split(',').map(str::trim).filter(|file| !file.is_empty()).map(String::from).collect();
+        let parts = files
+            .iter()
+            .map(|file| load(file))
+            .collect::<anyhow::Result<Vec<Vec<Dataset>>>>()?
+            .concat();
+        if parts.is_empty() {
+            anyhow::bail!("--merge '{}' lists no files", group);
+        }
+
+        let default_name = parts.iter().map(|d| d.name.as_str()).collect::<Vec<_>>().join("+");
+        let refs: Vec<&Dataset> = parts.iter().collect();
+        let builtin_matcher = builtin_tag_matcher(tag_matcher, &input.read_options()?, &files);
+        datasets.push(merge_datasets(name.unwrap_or(&default_name), &refs, builtin_matcher.as_ref().or(tag_matcher)));
+    }
+
+    // --difference/--ratio A,B refer to datasets loaded above by name
+    for (specs, combine) in [
+        (&input.differences, difference_dataset as fn(&Dataset, &Dataset) -> Dataset),
+        (&input.ratios, ratio_dataset),
+    ] {
+        for spec in specs {
+            let (a, b) = spec
+                .split_once(',')
+                .with_context(|| format!("Expected two dataset names as A,B, got '{}'", spec))?;
+            let find = |name: &str| -> anyhow::Result<&Dataset> {
+                let dataset = datasets
+                    .iter()
+                    .find(|d| d.name.eq_ignore_ascii_case(name.trim()))
+                    .with_context(|| format!("Unknown dataset '{}' in '{}'", name.trim(), spec))?;
+                if dataset.is_ratio() {
+                    anyhow::bail!("'{}' is a ratio dataset: its scores cannot be combined as counts", dataset.name);
+                }
+                Ok(dataset)
+            };
+            let derived = combine(find(a)?, find(b)?);
+            datasets.push(derived);
+        }
+    }
+
+    // Splitting can leave nothing, e.g. an empty file cut with --split-on
+    if datasets.is_empty() {
+        anyhow::bail!("No datasets to analyze: the inputs gave no text, segments or record groups");
+    }
+    Ok(datasets)
+}
+
+fn analyze_file(
+    file_path: &str,
+    input: &InputArgs,
+    forced_language: Option<&Language>,
+    tag_matcher: Option<&TagMatcher>,
+    ngram_size: usize,
+) -> anyhow::Result<Dataset> {
+    analyze_files(file_dataset_name(file_path), &[file_path.to_string()], input, forced_language, tag_matcher, ngram_size)
+}
+
+/// Name after the file stem, ignoring a compression extension (corpus.txt.gz → corpus).
+fn file_dataset_name(file_path: &str) -> &str {
+    if file_path == STDIN_PATH {
+        return "stdin";
+    }
+    std::path::Path::new(strip_compression_extension(file_path))
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("Unknown")
+}
+
+/// One dataset per section: EPUB chapters and segments are named "BOOK/NN Title"
+/// (just "BOOK/NN" for untitled equal segments), record groups "FILE/GROUP".
+fn analyze_sections(
+    file_path: &str,
+    numbered: bool,
+    input: &InputArgs,
+    forced_language: Option<&Language>,
+    tag_matcher: Option<&TagMatcher>,
+    ngram_size: usize,
+) -> anyhow::Result<Vec<Dataset>> {
+    let read_options = input.read_options()?;
+    let builtin_matcher = builtin_tag_matcher(tag_matcher, &read_options, &[file_path.to_string()]);
+    let tag_matcher = builtin_matcher.as_ref().or(tag_matcher);
+
+    let parse_start = Instant::now();
+    let (sections, _) = TextParser::parse_sections(file_path, &read_options)
+        .with_context(|| format!("Failed to read {}", file_path))?;
+    let parse_duration = parse_start.elapsed();
+
+    // The file is parsed in one go; share its parse time out by section length
+    let file = file_dataset_name(file_path);
+    let file_words = sections.iter().map(|section| section.words.len()).sum::<usize>().max(1);
+
+    sections
+        .into_iter()
+        .enumerate()
+        .map(|(i, section)| {
+            let share = parse_duration.mul_f64(section.words.len() as f64 / file_words as f64);
+            let name = match numbered {
+                true => format!("{}/{:02} {}", file, i + 1, section.title).trim_end().to_string(),
+                false => format!("{}/{}", file, section.title),
+            };
+            let mut dataset =
+                analyze_words(&name, vec![section.words], section.segments, share, input, forced_language, tag_matcher, ngram_size);
+            dataset.series = Some(file_path.to_string());
+            Ok(dataset)
+        })
+        .collect()
+}
+
+/// Analyze several files as one dataset. N-grams do not span file boundaries.
+fn analyze_files(
+    dataset_name: &str,
+    file_paths: &[String],
+    input: &InputArgs,
+    forced_language: Option<&Language>,
+    tag_matcher: Option<&TagMatcher>,
+    ngram_size: usize,
+) -> anyhow::Result<Dataset> {
+    let read_options = input.read_options()?;
+    let builtin_matcher = builtin_tag_matcher(tag_matcher, &read_options, file_paths);
+    let tag_matcher = builtin_matcher.as_ref().or(tag_matcher);
+
+    let parse_start = Instant::now();
+    let mut files_words = Vec::with_capacity(file_paths.len());
+    let mut segments: Option<SegmentStats> = None;
+    for file_path in file_paths {
+        let (parsed, report) = TextParser::parse_file_with(file_path, &read_options)
+            .with_context(|| format!("Failed to read {}", file_path))?;
+        if report.replacements > 0 {
+            eprintln!(
+                "Note: replaced {} invalid {} sequence(s) in {}",
+                report.replacements, report.encoding.name(), file_path
+            );
+        }
+        if !report.blank_pages.is_empty() {
+            eprintln!(
+                "Note: no extractable text on page(s) {} of {} (scanned images need OCR first)",
+                format_page_list(&report.blank_pages), file_path
+            );
+        }
+        files_words.push(parsed.words);
+        if let Some(file_segments) = parsed.segments {
+            segments.get_or_insert_with(SegmentStats::default).merge(&file_segments);
+        }
+    }
+    let parse_duration = parse_start.elapsed();
+
+    Ok(analyze_words(dataset_name, files_words, segments, parse_duration, input, forced_language, tag_matcher, ngram_size))
+}
+
+/// Compact page list: [1, 2, 3, 7] → "1-3, 7".
+fn format_page_list(pages: &[u32]) -> String {
+    let mut ranges: Vec<(u32, u32)> = Vec::new();
+    for &page in pages {
+        match ranges.last_mut() {
+            Some((_, last)) if *last + 1 == page => *last = page,
+            _ => ranges.push((page, page)),
+        }
+    }
+    ranges
+        .iter()
+        .map(|&(first, last)| if first == last { first.to_string() } else { format!("{}-{}", first, last) })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Count parsed words (one list per file) into a dataset. Sentence statistics describe the
+/// whole text, so a --sample-tokens sample drops them.
+#[allow(clippy::too_many_arguments)]
+fn analyze_words(
+    dataset_name: &str,
+    files_words: Vec<Vec<String>>,
+    segments: Option<SegmentStats>,
+    parse_duration: Duration,
+    input: &InputArgs,
+    forced_language: Option<&Language>,
+    tag_matcher: Option<&TagMatcher>,
+    ngram_size: usize,
+) -> Dataset {
+    let analyze_start = Instant::now();
+
+    // Identifiers and letters are not prose, so there is nothing to detect in them
+    let language = match input.language.as_str() {
+        "auto" if input.tokenizer == Tokenizer::Code || input.unit != Unit::Word => None,
+        "auto" => detect_language(files_words.iter().flatten()),
+        "none" => None,
+        _ => forced_language.cloned(),
+    };
+
+    let mut analyzer = word_analyzer(language.as_ref(), tag_matcher);
+    let tokens: Vec<String> = files_words
+        .iter()
+        .flat_map(|words| TextParser::ngrams(words, ngram_size))
+        .collect();
+    let (tokens, sampled_from) = sample_dataset(dataset_name, tokens, input);
+    // Chunk resampling needs the tokens in text order; token resampling works from the counts
+    let bootstrap_options = input.bootstrap_options();
+    let ordered_tokens = bootstrap_options.filter(|options| options.resample == Resample::Chunk).map(|_| tokens.clone());
+    let word_counts = analyzer.analyze(tokens);
+    let analyze_duration = analyze_start.elapsed();
+    let bootstrap = bootstrap_options.and_then(|options| bootstrap(&word_counts, ordered_tokens.as_deref(), &options));
+
+    let segments = segments.filter(|_| sampled_from.is_none());
+    Dataset { parse_duration, analyze_duration, language, segments, bootstrap, sampled_from, ..Dataset::from_words(dataset_name, word_counts) }
+}
+
+/// Cut the tokens down to `--sample-tokens`, returning how many there were when they were cut.
+fn sample_dataset(dataset_name: &str, tokens: Vec<String>, input: &InputArgs) -> (Vec<String>, Option<usize>) {
+    let Some(options) = input.sample_options() else {
+        return (tokens, None);
+    };
+    let available = tokens.len();
+    if available <= options.tokens {
+        eprintln!(
+            "Note: {} has {} tokens, not more than --sample-tokens {}; using all of them",
+            dataset_name, available, options.tokens
+        );
+        return (tokens, None);
+    }
+    (sample_tokens(tokens, &options), Some(available))
+}
+
+fn load_snapshot(
+    snapshot_path: &str,
+    input: &InputArgs,
+    forced_language: Option<&Language>,
+    tag_matcher: Option<&TagMatcher>,
+    ngram_size: usize,
+) -> anyhow::Result<Dataset> {
+    let load_start = Instant::now();
+    let snapshot = Snapshot::load(snapshot_path)?;
+
+    if let Some((option, saved, current)) = snapshot.settings.mismatch(&input.parse_settings(ngram_size)) {
+        anyhow::bail!(
+            "Snapshot {} was saved with {} '{}', but '{}' is needed here; re-create it with these options and --save-snapshot",
+            snapshot_path, option, saved, current
+        );
+    }
+
+    match snapshot.source_status(snapshot_path)? {
+        SourceStatus::Fresh => {}
+        SourceStatus::Missing => {
+            eprintln!("Note: source of {} ({}) not found; using the snapshot as is", snapshot_path, snapshot.source.path);
+        }
+        SourceStatus::Changed(source) => {
+            // Stale: re-analyze the source and refresh the snapshot
+            eprintln!("Snapshot {} is stale ({} changed); re-analyzing", snapshot_path, source.display());
+            let source = source.to_string_lossy();
+            let dataset = analyze_file(&source, input, forced_language, tag_matcher, ngram_size)?;
+            save_snapshot(&dataset, &source, snapshot_path, input, ngram_size)?;
+            return Ok(dataset);
+        }
+    }
+
+    let parse_duration = load_start.elapsed();
+
+    // Tags are re-applied so snapshots follow the current tags.toml
+    let builtin_matcher = builtin_tag_matcher(tag_matcher, &input.read_options()?, std::slice::from_ref(&snapshot.source.path));
+    let tag_matcher = builtin_matcher.as_ref().or(tag_matcher);
+    let analyze_start = Instant::now();
+    let language = match input.language.as_str() {
+        "auto" => snapshot.language,
+        "none" => None,
+        _ => forced_language.cloned(),
+    };
+    let mut analyzer = word_analyzer(language.as_ref(), tag_matcher);
+    let (word_counts, sampled_from) = match input.sample_options() {
+        None => (analyzer.analyze_counts(snapshot.words), None),
+        Some(options) if options.mode == SampleMode::Contiguous => anyhow::bail!(
+            "Snapshot {} keeps no word order; --sample contiguous needs the source text", snapshot_path
+        ),
+        // A random sample does not depend on order, so the counts can stand in for the text;
+        // laid out by word, so that a seed picks the same tokens whatever the saved rank order
+        Some(_) => {
+            let mut words = snapshot.words;
+            words.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+            let tokens = words.into_iter().flat_map(|(word, count)| std::iter::repeat_n(word, count)).collect();
+            let (tokens, sampled_from) = sample_dataset(&snapshot.name, tokens, input);
+            (analyzer.analyze(tokens), sampled_from)
+        }
+    };
+    let bootstrap = input.bootstrap_options().and_then(|options| {
+        let intervals = bootstrap(&word_counts, None, &options);
+        if intervals.is_none() && options.resample == Resample::Chunk {
+            eprintln!("Note: snapshot {} keeps no word order; --resample chunk needs the source text", snapshot_path);
+        }
+        intervals
+    });
+
+    Ok(Dataset {
+        parse_duration,
+        analyze_duration: analyze_start.elapsed(),
+        language,
+        segments: snapshot.segments.filter(|_| sampled_from.is_none()),
+        bootstrap,
+        sampled_from,
+        ..Dataset::from_words(&snapshot.name, word_counts)
+    })
+}
+
+fn save_snapshot(dataset: &Dataset, source_path: &str, snapshot_path: &str, input: &InputArgs, ngram_size: usize) -> anyhow::Result<()> {
+    if dataset.sampled_from.is_some() {
+        eprintln!("Note: snapshots are not saved for --sample-tokens samples");
+        return Ok(());
+    }
+    Snapshot::from_dataset(dataset, source_path, input.parse_settings(ngram_size))?.save(snapshot_path)?;
+    eprintln!("Snapshot saved to {}", snapshot_path);
+    Ok(())
+}
+
+/// Word analyzer for one dataset, with bundled stop words swapped in when the language has its own list.
+fn word_analyzer(language: Option<&Language>, tag_matcher: Option<&TagMatcher>) -> WordAnalyzer {
+    match language_tag_matcher(tag_matcher, language) {
+        Some(tag_matcher) => WordAnalyzer::with_tags(tag_matcher),
+        None => WordAnalyzer::new(),
+    }
+}
+
+/// Every file the inputs name, with directories and globs expanded as `load_datasets` reads them.
+fn input_files(input: &InputArgs) -> anyhow::Result<Vec<String>> {
+    let sources = expand_inputs(&input.files, &input.corpus_options()?)?;
+    Ok(sources
+        .into_iter()
+        .flat_map(|source| match source {
+            InputSource::File(file_path) => vec![file_path],
+            InputSource::Group { files, .. } => files,
+        })
+        .collect())
+}
+
+/// Built-in tags for what the input options count: with the code tokenizer, the keywords of the
+/// files' languages; with the word tokenizer, each kept or normalized token class (numbers, URLs, ...).
+fn builtin_tag_matcher(tag_matcher: Option<&TagMatcher>, read_options: &ReadOptions, file_paths: &[String]) -> Option<TagMatcher> {
+    if let Some(code) = &read_options.code {
+        let mut languages: Vec<&CodeLanguage> = file_paths.iter().map(|path| code.language_for(path)).collect();
+        languages.sort_unstable_by_key(|language| language.name);
+        languages.dedup();
+        let matcher = languages.iter().fold(tag_matcher.cloned().unwrap_or_default(), |matcher, language| {
+            matcher.with_keywords(&language.keywords(), language.keywords_ignore_case())
         });
+        return Some(matcher);
+    }
+
+    let classes = read_options.tokens.counted_classes();
+    if classes.is_empty() || read_options.unit != Unit::Word {
+        return None;
+    }
+    Some(tag_matcher.cloned().unwrap_or_default().with_token_classes(&classes))
+}
+
+/// Report destination: the `--output` file if given, stdout otherwise.
+fn open_output(output: Option<&str>) -> anyhow::Result<Box<dyn Write>> {
+    match output {
+        Some(path) => {
+            let file = std::fs::File::create(path)
+                .with_context(|| format!("Failed to create {}", path))?;
+            Ok(Box::new(io::BufWriter::new(file)))
+        }
+        None => Ok(Box::new(io::stdout().lock())),
+    }
+}
+
+fn finish_output(mut out: Box<dyn Write>, output: Option<&str>) -> anyhow::Result<()> {
+    out.flush()?;
+    if let Some(path) = output {
+        eprintln!("Results written to {}", path);
     }
-    
+    Ok(())
+}
+
+fn run_analyze(args: &AnalyzeArgs, tag_matcher: Option<&TagMatcher>) -> anyhow::Result<()> {
+    let start_time = Instant::now();
+    let datasets = load_datasets(&args.input, tag_matcher, 1)?;
     let total_duration = start_time.elapsed();
+    let top = args.output.top_or(DEFAULT_TOP);
+    let filter_set = build_filter_set(&args.filter, tag_matcher, &datasets)?;
+    let words = filter_datasets(&datasets, &filter_set);
 
-    if args.no_interactive {
-        print_multi_results(&datasets, args.top, total_duration);
-        
-        if let Some(output_file) = args.output {
-            write_multi_results_to_file(&datasets, &output_file)?;
+    // The report goes to --output whenever given; stdout only when the TUI is off
+    if args.output.output.is_some() || args.no_interactive {
+        let output = args.output.output.as_deref();
+        let mut out = open_output(output)?;
+        write_multi_results(&mut out, &datasets, &words, top, total_duration)?;
+        if args.input.rarefaction {
+            write_rarefaction(&mut out, &datasets)?;
         }
-    } else {
-        run_multi_tui(datasets, total_duration)?;
+        finish_output(out, output)?;
+    }
+
+    if let Some(report) = args.report.as_deref() {
+        // Report tables hold every word unless --top is given
+        let context = ReportContext {
+            datasets: &datasets,
+            words: &words,
+            filter_set: &filter_set,
+            top: args.output.top,
+        };
+        let mut out = open_output(Some(report))?;
+        write_report(&mut out, &context)?;
+        finish_output(out, Some(report))?;
+    }
+
+    if !args.no_interactive {
+        // Datasets merged in the TUI are tagged like the inputs
+        let files = input_files(&args.input)?;
+        let tag_matcher = builtin_tag_matcher(tag_matcher, &args.input.read_options()?, &files).or(tag_matcher.cloned());
+        run_multi_tui(datasets, filter_set, tag_matcher, total_duration)?;
     }
 
     Ok(())
 }
 
+fn run_compare(args: &CompareArgs, tag_matcher: Option<&TagMatcher>) -> anyhow::Result<()> {
+    let datasets = load_datasets(&args.input, tag_matcher, 1)?;
+    let top = args.output.top_or(DEFAULT_TOP);
+    let output = args.output.output.as_deref();
+
+    let mut out = open_output(output)?;
+    write_comparison(&mut out, &datasets, top)?;
+    if args.input.rarefaction {
+        write_rarefaction(&mut out, &datasets)?;
+    }
+    finish_output(out, output)
+}
+
+fn run_fit(args: &FitArgs, tag_matcher: Option<&TagMatcher>) -> anyhow::Result<()> {
+    let datasets = load_datasets(&args.input, tag_matcher, 1)?;
+    let output = args.output.output.as_deref();
+
+    let mut out = open_output(output)?;
+    writeln!(out, "Zipf Fit Results")?;
+    writeln!(out, "================")?;
+    match args.output.top {
+        Some(top) => writeln!(out, "Fitted ranks: 1-{}", top)?,
+        None => writeln!(out, "Fitted ranks: all")?,
+    }
+    // Bootstrap intervals are for fits over all ranks
+    let intervals = args.output.top.is_none().then(|| datasets.iter().find_map(|d| d.bootstrap.as_ref())).flatten();
+    if args.input.bootstrap.is_some() && intervals.is_none() {
+        eprintln!("Note: bootstrap intervals are only shown when fitting all ranks (without --top)");
+    }
+    writeln!(out)?;
+    write!(out, "{:20} | {:>8} | {:>12} | {:>6} | {:>7}", "Dataset", "Exponent", "Constant", "R²", "Points")?;
+    match intervals {
+        Some(intervals) => writeln!(out, " | {:>16}", format!("{}% interval", intervals.confidence * 100.0))?,
+        None => writeln!(out)?,
+    }
+    write!(out, "{:->20}-+-{:->8}-+-{:->12}-+-{:->6}-+-{:->7}", "", "", "", "", "")?;
+    match intervals {
+        Some(_) => writeln!(out, "-+-{:->16}", "")?,
+        None => writeln!(out)?,
+    }
+
+    for dataset in &datasets {
+        let words = &dataset.word_counts[..args.output.top_or(usize::MAX).min(dataset.word_counts.len())];
+        let interval = dataset.bootstrap.as_ref().and_then(|b| b.exponent.as_ref()).filter(|_| intervals.is_some());
+        if dataset.is_ratio() {
+            writeln!(out, "{:20} | ratio dataset; its scores are not frequencies to fit", dataset.name)?;
+            continue;
+        }
+        match fit_zipf(words) {
+            Some(fit) => writeln!(
+                out,
+                "{:20} | {:>8.3} | {:>12.1} | {:>6.3} | {:>7}{}",
+                dataset.name,
+                fit.exponent,
+                fit.constant,
+                fit.r_squared,
+                fit.points,
+                interval.map(|interval| format!(" | {:>16}", format_interval(interval, 3))).unwrap_or_default()
+            )?,
+            None => writeln!(out, "{:20} | not enough distinct ranks to fit", dataset.name)?,
+        }
+    }
+
+    finish_output(out, output)
+}
+
+fn run_ngrams(args: &NgramArgs, tag_matcher: Option<&TagMatcher>) -> anyhow::Result<()> {
+    let start_time = Instant::now();
+    let datasets = load_datasets(&args.input, tag_matcher, args.size as usize)?;
+    let total_duration = start_time.elapsed();
+    let output = args.output.output.as_deref();
+
+    let words = filter_datasets(&datasets, &FilterSet::new());
+
+    let mut out = open_output(output)?;
+    writeln!(out, "N-gram size: {}", args.size)?;
+    write_multi_results(&mut out, &datasets, &words, args.output.top_or(DEFAULT_TOP), total_duration)?;
+    if args.input.rarefaction {
+        write_rarefaction(&mut out, &datasets)?;
+    }
+    finish_output(out, output)
+}
+
+fn run_tags(args: &TagsArgs, config_path: &str, tag_matcher: Option<&TagMatcher>) -> anyhow::Result<()> {
+    let Some(tag_matcher) = tag_matcher else {
+        anyhow::bail!("No tag configuration found at {}", config_path);
+    };
+
+    let datasets = if args.input.files.is_empty() && args.input.merges.is_empty() {
+        Vec::new()
+    } else {
+        load_datasets(&args.input, Some(tag_matcher), 1)?
+    };
+
+    let mut tags: Vec<_> = tag_matcher.available_tags().to_vec();
+    tags.sort_by_key(|tag| tag.letter);
+
+    let output = args.output.as_deref();
+    let mut out = open_output(output)?;
+    let title = format!("Configured Tags ({})", config_path);
+    writeln!(out, "{}", title)?;
+    writeln!(out, "{}", "=".repeat(title.chars().count()))?;
+    for tag in &tags {
+        writeln!(
+            out,
+            "[{}] {:15} {:10} {:>5} words  {}",
+            tag.letter,
+            tag.name,
+            tag.color.as_deref().unwrap_or("-"),
+            tag_matcher.words_with_tag(tag),
+            tag.description.as_deref().unwrap_or("")
+        )?;
+    }
+
+    // Ratio scores are not tokens, so they have no coverage to report
+    for dataset in datasets.iter().filter(|dataset| !dataset.is_ratio()) {
+        writeln!(out)?;
+        writeln!(out, "Tag coverage: {}", dataset.name)?;
+        writeln!(out, "  {:18} | {:>10} | {:>7} | {:>12}", "Tag", "Tokens", "Tokens%", "Unique words")?;
+        writeln!(out, "  {:->18}-+-{:->10}-+-{:->7}-+-{:->12}", "", "", "", "")?;
+        for tag in &tags {
+            let tagged: Vec<_> = dataset.word_counts.iter().filter(|wc| wc.tags.contains(tag)).collect();
+            let tokens: usize = tagged.iter().map(|wc| wc.count).sum();
+            writeln!(
+                out,
+                "  {:18} | {:>10} | {:>6.1}% | {:>12}",
+                format!("[{}] {}", tag.letter, tag.name), tokens, dataset.percentage(tokens).unwrap_or(0.0), tagged.len()
+            )?;
+        }
+    }
+
+    finish_output(out, output)
+}
+
+fn run_export(args: &ExportArgs, tag_matcher: Option<&TagMatcher>) -> anyhow::Result<()> {
+    let datasets = load_datasets(&args.input, tag_matcher, 1)?;
+    let filter_set = build_filter_set(&args.filter, tag_matcher, &datasets)?;
+    let words = filter_datasets(&datasets, &filter_set);
+    let output = args.output.output.as_deref();
+    let format = args.format
+        .or_else(|| output.and_then(ExportFormat::from_path))
+        .unwrap_or(ExportFormat::Csv);
+
+    // Data exports are complete tables unless --top is given; tables for papers and wikis show the top words
+    let top = match format {
+        ExportFormat::Latex | ExportFormat::Markdown => Some(args.output.top_or(DEFAULT_TOP)),
+        _ => args.output.top,
+    };
+    let context = ExportContext {
+        datasets: &datasets,
+        words: &words,
+        filter_set: &filter_set,
+        top,
+        view: None,
+        pgfplots: args.pgfplots,
+        rarefaction: args.input.rarefaction,
+    };
+
+    let mut out = open_output(output)?;
+    write_export(&mut out, format, &context)?;
+    finish_output(out, output)
+}
+
+fn run_export_chart(args: &ChartArgs, tag_matcher: Option<&TagMatcher>) -> anyhow::Result<()> {
+    if args.whiskers && args.input.bootstrap.is_none() {
+        anyhow::bail!("--whiskers draws bootstrap intervals; add --bootstrap N");
+    }
+    let datasets = load_datasets(&args.input, tag_matcher, 1)?;
+    let filter_set = build_filter_set(&args.filter, tag_matcher, &datasets)?;
+    let words = filter_datasets(&datasets, &filter_set);
+    let output = args.output.output.as_deref();
+    let format = args.format
+        .or_else(|| output.and_then(ChartFormat::from_path))
+        .unwrap_or(ChartFormat::Svg);
+
+    // Charts plot every word unless --top is given
+    let series: Vec<ChartSeries> = datasets
+        .iter()
+        .zip(&words)
+        .map(|(dataset, words)| {
+            let words = &words[..args.output.top.unwrap_or(usize::MAX).min(words.len())];
+            ChartSeries {
+                name: &dataset.name,
+                words,
+                total_words: dataset.total_words,
+                zipf_constant: words.first().filter(|_| args.zipf && !dataset.is_ratio()).map(|wc| wc.count as f64),
+                whiskers: dataset.bootstrap.as_ref().filter(|_| args.whiskers),
+            }
+        })
+        .collect();
+    let options = ChartOptions {
+        width: args.width,
+        height: args.height,
+        log_scale: args.log,
+        percentage: args.percentage,
+        title: args.title.clone(),
+    };
+
+    let mut out = open_output(output)?;
+    write_chart(&mut out, format, &series, &options)?;
+    finish_output(out, output)
+}
+
+/// Resolve `--exclude-tag`/`--include-tag` values (tag names or letters) against the configured tags
+/// and the built-in tags (keywords, token classes) found on the datasets' words.
+fn build_filter_set(args: &FilterArgs, tag_matcher: Option<&TagMatcher>, datasets: &[Dataset]) -> anyhow::Result<FilterSet> {
+    let find_tag = |query: &str| -> anyhow::Result<Tag> {
+        tag_matcher
+            .into_iter()
+            .flat_map(|matcher| matcher.available_tags())
+            .chain(datasets.iter().flat_map(|dataset| dataset.word_counts.iter().flat_map(|wc| &wc.tags)))
+            .find(|tag| {
+                tag.name.eq_ignore_ascii_case(query)
+                    || query.to_lowercase() == tag.letter.to_lowercase().to_string()
+            })
+            .cloned()
+            .with_context(|| format!("Unknown tag '{}'", query))
+    };
+
+    let mut filter_set = FilterSet::new();
+    for query in &args.exclude_tags {
+        filter_set.add_exclude_tag(find_tag(query)?);
+    }
+    for query in &args.include_tags {
+        filter_set.add_include_tag(find_tag(query)?);
+    }
+    filter_set.exclude_single = args.exclude_single;
+    filter_set.cross_dataset = args.cross_dataset.clone();
+
+    Ok(filter_set)
+}
+
 fn run_multi_tui(
     datasets: Vec<Dataset>,
-    total_duration: std::time::Duration,
+    filter_set: FilterSet,
+    tag_matcher: Option<TagMatcher>,
+    total_duration: Duration,
 ) -> anyhow::Result<()> {
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -81,7 +800,7 @@ fn run_multi_tui(
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new(datasets, total_duration);
+    let mut app = App::new(datasets, total_duration).with_filter_set(filter_set).with_tag_matcher(tag_matcher);
     let res = app.run(&mut terminal);
 
     disable_raw_mode()?;
@@ -92,64 +811,81 @@ fn run_multi_tui(
     Ok(())
 }
 
-fn print_multi_results(
-    datasets: &[Dataset],
-    top: usize,
-    total_duration: std::time::Duration,
-) {
-    println!("Zipfian Multi-Dataset Analysis Results");
-    println!("=====================================");
-    println!("Datasets analyzed: {}", datasets.len());
-    println!("Total processing time: {:.2?}", total_duration);
-    println!();
-
-    for (i, dataset) in datasets.iter().enumerate() {
-        println!("Dataset {}: {}", i + 1, dataset.name);
-        println!("  Total words: {}", dataset.total_words);
-        println!("  Unique words: {}", dataset.unique_words);
-        println!("  Parse time: {:.2?}", dataset.parse_duration);
-        println!("  Analysis time: {:.2?}", dataset.analyze_duration);
-        println!("  Words per second: {:.0}", dataset.total_words as f64 / (dataset.parse_duration + dataset.analyze_duration).as_secs_f64());
-        println!();
-        println!("  {:>4} | {:20} | {:>8}", "Rank", "Word", "Count");
-        println!("  {:->4}-+-{:->20}-+-{:->8}", "", "", "");
-
-        for word_count in dataset.word_counts.iter().take(top) {
-            println!(
-                "  {:>4} | {:20} | {:>8}",
-                word_count.rank, word_count.word, word_count.count
-            );
-        }
-        println!();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_split_is_an_error() {
+        let path = std::env::temp_dir().join(format!("zipfr-empty-split-{}.txt", std::process::id()));
+        std::fs::write(&path, "").unwrap();
+
+        let args = Args::try_parse_from(["zipfr", "--split-on", "^CHAPTER", path.to_str().unwrap()]).unwrap();
+        let error = load_datasets(&args.analyze.input, None, 1).unwrap_err().to_string();
+        assert!(error.starts_with("No datasets to analyze"), "{}", error);
+
+        std::fs::remove_file(&path).unwrap();
     }
-}
 
-fn write_multi_results_to_file(
-    datasets: &[Dataset],
-    output_file: &str,
-) -> anyhow::Result<()> {
-    use std::fs::File;
-    use std::io::Write;
+    #[test]
+    fn test_directory_files_are_segmented() {
+        let dir = std::env::temp_dir().join(format!("zipfr-segmented-corpus-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("emma.txt"), "one two three four").unwrap();
+        std::fs::write(dir.join("persuasion.txt"), "five six seven eight").unwrap();
+
+        // Each file of the directory is cut on its own, not merged into one "corpus" dataset
+        let args = Args::try_parse_from(["zipfr", "--segments", "2", dir.to_str().unwrap()]).unwrap();
+        let datasets = load_datasets(&args.analyze.input, None, 1).unwrap();
+        let names: Vec<&str> = datasets.iter().map(|dataset| dataset.name.as_str()).collect();
+        assert_eq!(names, ["emma/01", "emma/02", "persuasion/01", "persuasion/02"]);
+        assert!(datasets.iter().all(|dataset| dataset.total_words == 2));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_directory_records_are_grouped() {
+        let dir = std::env::temp_dir().join(format!("zipfr-grouped-corpus-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("posts.csv"), "author,text\nann,hello there\nbob,hi\nann,bye\n").unwrap();
+        std::fs::write(dir.join("notes.txt"), "plain text notes").unwrap();
 
-    let mut file = File::create(output_file)?;
-    writeln!(file, "Zipfian Multi-Dataset Analysis Results")?;
-    writeln!(file, "=====================================")?;
-    writeln!(file, "Datasets analyzed: {}", datasets.len())?;
-    writeln!(file)?;
+        let args = Args::try_parse_from(["zipfr", "--column", "text", "--group-by", "author", dir.to_str().unwrap()]).unwrap();
+        let datasets = load_datasets(&args.analyze.input, None, 1).unwrap();
+        let names: Vec<&str> = datasets.iter().map(|dataset| dataset.name.as_str()).collect();
+        // The record file splits by author; the text file still forms the directory's dataset
+        let corpus = dir.file_name().unwrap().to_str().unwrap();
+        assert_eq!(names, ["posts/ann", "posts/bob", corpus]);
+        assert_eq!(datasets[0].total_words, 3);
 
-    for (i, dataset) in datasets.iter().enumerate() {
-        writeln!(file, "Dataset {}: {}", i + 1, dataset.name)?;
-        writeln!(file, "Total words: {}", dataset.total_words)?;
-        writeln!(file, "Unique words: {}", dataset.unique_words)?;
-        writeln!(file)?;
-        writeln!(file, "Rank,Word,Count")?;
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 
-        for word_count in &dataset.word_counts {
-            writeln!(file, "{},{},{}", word_count.rank, word_count.word, word_count.count)?;
+    #[test]
+    fn test_keywords_follow_language_case() {
+        let args = Args::try_parse_from(["zipfr", "--tokenizer", "code", "lib.rs", "schema.sql"]).unwrap();
+        let read_options = args.analyze.input.read_options().unwrap();
+        let matcher = builtin_tag_matcher(None, &read_options, &args.analyze.input.files).unwrap();
+        let keywords = matcher.get_tag_by_name("Keywords").unwrap();
+        for (word, tagged) in [("type", true), ("Type", false), ("Self", true), ("SELECT", true), ("select", true)] {
+            assert_eq!(matcher.get_tags(word).contains(keywords), tagged, "{}", word);
         }
-        writeln!(file)?;
     }
 
-    println!("Results written to {}", output_file);
-    Ok(())
+    #[test]
+    fn test_directory_keywords_follow_its_files() {
+        let dir = std::env::temp_dir().join(format!("zipfr-code-corpus-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("lib.rs"), "fn main() {}").unwrap();
+
+        let args = Args::try_parse_from(["zipfr", "--tokenizer", "code", dir.to_str().unwrap()]).unwrap();
+        let files = input_files(&args.analyze.input).unwrap();
+        assert_eq!(files, [dir.join("lib.rs").to_str().unwrap()]);
+        let matcher = builtin_tag_matcher(None, &args.analyze.input.read_options().unwrap(), &files).unwrap();
+        // Rust keywords, not the generic language's empty list
+        assert!(matcher.get_tags("fn").contains(matcher.get_tag_by_name("Keywords").unwrap()));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
@@ -1,34 +1,146 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
+use crate::code::tokenize;
+use crate::input::{read_sections, ReadReport, ReadOptions};
+use crate::segment::{segment, SegmentStats};
+use crate::tokens::{extract_tokens, TokenOptions};
 use anyhow::Result;
+use std::fmt;
+use std::str::FromStr;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A parsed section (EPUB chapter, record group, or a whole file): its words and,
+/// for prose, its sentence and paragraph statistics.
+#[derive(Debug, Clone, Default)]
+pub struct ParsedSection {
+    pub title: String,
+    pub words: Vec<String>,
+    pub segments: Option<SegmentStats>, // None for source code
+}
+
+/// What is counted: words, or the letters inside them.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Unit {
+    #[default]
+    Word,
+    Char,             // Single letters (Unicode scalar values)
+    CharNgram(usize), // Runs of N letters within a word
+    Grapheme,         // User-perceived letters, with their combining marks
+}
+
+impl FromStr for Unit {
+    type Err = String;
+
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        match spec.to_ascii_lowercase().as_str() {
+            "word" => Ok(Unit::Word),
+            "char" => Ok(Unit::Char),
+            "grapheme" => Ok(Unit::Grapheme),
+            spec => match spec.strip_prefix("charngram:").map(str::parse::<usize>) {
+                Some(Ok(n)) if n > 0 => Ok(Unit::CharNgram(n)),
+                Some(_) => Err("expected a positive size, e.g. 'charngram:2'".to_string()),
+                None => Err(format!("unknown unit '{}' (expected word, char, charngram:N or grapheme)", spec)),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Unit::Word => write!(f, "word"),
+            Unit::Char => write!(f, "char"),
+            Unit::CharNgram(n) => write!(f, "charngram:{}", n),
+            Unit::Grapheme => write!(f, "grapheme"),
+        }
+    }
+}
 
 pub struct TextParser;
 
 impl TextParser {
+    /// Read words from a file (or stdin for `-`), decompressing gzip, zstd, bzip2 and xz input.
     pub fn parse_file(file_path: &str) -> Result<Vec<String>> {
-        let file = File::open(file_path)?;
-        let reader = BufReader::new(file);
-        let mut words = Vec::new();
-
-        for line in reader.lines() {
-            let line = line?;
-            let line_words = Self::extract_words(&line);
-            words.extend(line_words);
-        }
+        Ok(Self::parse_file_with(file_path, &ReadOptions::default())?.0.words)
+    }
 
-        Ok(words)
+    /// Like `parse_file`, with control over the text encoding and input format
+    /// (markup is stripped and documents unpacked so only text content is counted).
+    /// Sections are joined into one, with their sentence statistics merged.
+    pub fn parse_file_with(file_path: &str, options: &ReadOptions) -> Result<(ParsedSection, ReadReport)> {
+        let (sections, report) = Self::parse_sections(file_path, options)?;
+        let mut parsed = ParsedSection::default();
+        for section in sections {
+            parsed.words.extend(section.words);
+            if let Some(segments) = section.segments {
+                parsed.segments.get_or_insert_with(SegmentStats::default).merge(&segments);
+            }
+        }
+        Ok((parsed, report))
     }
 
-    fn extract_words(text: &str) -> Vec<String> {
-        text.split_whitespace()
-            .map(|word| {
-                word.chars()
-                    .filter(|c| c.is_alphabetic())
-                    .collect::<String>()
-                    .to_lowercase()
+    /// Words per titled section (EPUB chapters; a single untitled section for other inputs).
+    /// With the code tokenizer, identifiers replace words; other units split words into letters.
+    /// Sentence lengths are always counted in word tokens, numbers included unless dropped.
+    pub fn parse_sections(file_path: &str, options: &ReadOptions) -> Result<(Vec<ParsedSection>, ReadReport)> {
+        let (sections, report) = read_sections(file_path, options)?;
+        let sections = sections
+            .into_iter()
+            .map(|section| {
+                if let Some(code) = &options.code {
+                    let words = tokenize(&section.text, code.language_for(file_path), code);
+                    return ParsedSection { title: section.title, words, segments: None };
+                }
+
+                let segments = Some(segment(&section.text, &options.tokens));
+                let words = match options.unit {
+                    Unit::Word => section.text.lines().flat_map(|line| extract_tokens(line, &options.tokens)).collect(),
+                    unit => Self::extract_units(&section.text, unit),
+                };
+                ParsedSection { title: section.title, words, segments }
             })
-            .filter(|word| !word.is_empty())
-            .collect()
+            .collect();
+
+        Ok((sections, report))
+    }
+
+    /// Lowercased words of a line, without numbers, URLs or any other token class; digits are
+    /// stripped from words (`test123` → `test`). See `extract_tokens` for the token classes.
+    pub fn extract_words(text: &str) -> Vec<String> {
+        extract_tokens(text, &TokenOptions::WORDS_ONLY)
+    }
+
+    /// Join each run of `n` consecutive words into a single space-separated token.
+    pub fn ngrams(words: &[String], n: usize) -> Vec<String> {
+        if n <= 1 {
+            return words.to_vec();
+        }
+
+        words.windows(n).map(|window| window.join(" ")).collect()
+    }
+
+    /// Lowercased letters, letter n-grams or graphemes. Only letters count: n-grams never span
+    /// a space or punctuation, and graphemes must start with a letter.
+    fn extract_units(text: &str, unit: Unit) -> Vec<String> {
+        match unit {
+            Unit::Word => Self::extract_words(text),
+            Unit::Char => text
+                .chars()
+                .filter(|c| c.is_alphabetic())
+                .flat_map(char::to_lowercase)
+                .map(String::from)
+                .collect(),
+            Unit::CharNgram(n) => text
+                .split(|c: char| !c.is_alphabetic())
+                .flat_map(|word| {
+                    let letters: Vec<char> = word.chars().flat_map(char::to_lowercase).collect();
+                    letters.windows(n).map(|window| window.iter().collect::<String>()).collect::<Vec<_>>()
+                })
+                .collect(),
+            Unit::Grapheme => text
+                .graphemes(true)
+                .filter(|grapheme| grapheme.chars().next().is_some_and(char::is_alphabetic))
+                .map(str::to_lowercase)
+                .collect(),
+        }
     }
 }
 
@@ -49,4 +161,23 @@ mod tests {
         let words = TextParser::extract_words(text);
         assert_eq!(words, vec!["test", "with", "numbers", "and", "symbols"]);
     }
+
+    #[test]
+    fn test_extract_units() {
+        let text = "Ab-c e\u{301}t\u{e9}!";
+        assert_eq!(TextParser::extract_units(text, Unit::Char), vec!["a", "b", "c", "e", "t", "\u{e9}"]);
+        assert_eq!(TextParser::extract_units(text, Unit::Grapheme), vec!["a", "b", "c", "e\u{301}", "t", "\u{e9}"]);
+        assert_eq!(TextParser::extract_units("The other, ox", Unit::CharNgram(2)), vec!["th", "he", "ot", "th", "he", "er", "ox"]);
+        assert_eq!("charngram:3".parse::<Unit>(), Ok(Unit::CharNgram(3)));
+        assert!("charngram:0".parse::<Unit>().is_err());
+        assert!("letters".parse::<Unit>().is_err());
+    }
+
+    #[test]
+    fn test_ngrams() {
+        let words: Vec<String> = ["the", "cat", "sat", "down"].iter().map(|w| w.to_string()).collect();
+        assert_eq!(TextParser::ngrams(&words, 2), vec!["the cat", "cat sat", "sat down"]);
+        assert_eq!(TextParser::ngrams(&words, 1), words);
+        assert!(TextParser::ngrams(&words, 5).is_empty());
+    }
 }
\ No newline at end of file
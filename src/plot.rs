@@ -0,0 +1,516 @@
+use crate::analyzer::WordCount;
+use crate::bootstrap::Bootstrap;
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use std::fmt::Write as _;
+use std::io::Write;
+use std::path::Path;
+
+/// Line colors for overlaid datasets (cycled when there are more datasets).
+const PALETTE: [&str; 8] = [
+    "#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#17becf",
+];
+
+const FONT_FAMILY: &str = "DejaVu Sans, Helvetica, Arial, sans-serif";
+
+/// PNG text font, bundled with its license in `src/fonts`.
+const BUNDLED_FONT: &[u8] = include_bytes!("fonts/DejaVuSans.ttf");
+const BUNDLED_FONT_FAMILY: &str = "DejaVu Sans";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ChartFormat {
+    Svg,
+    Png,
+}
+
+impl ChartFormat {
+    /// Guess the format from a file extension (`.svg`, `.png`).
+    pub fn from_path(path: &str) -> Option<Self> {
+        let extension = Path::new(path).extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "svg" => Some(Self::Svg),
+            "png" => Some(Self::Png),
+            _ => None,
+        }
+    }
+}
+
+/// One dataset's line on the chart.
+pub struct ChartSeries<'a> {
+    pub name: &'a str,
+    pub words: &'a [WordCount], // Ranked words to plot
+    pub total_words: usize,     // Denominator when plotting percentages
+    pub zipf_constant: Option<f64>, // Draw the ideal `C / rank` line when set
+    pub whiskers: Option<&'a Bootstrap>, // Draw each word's frequency interval when set
+}
+
+#[derive(Debug, Clone)]
+pub struct ChartOptions {
+    pub width: u32,
+    pub height: u32,
+    pub log_scale: bool,
+    pub percentage: bool, // Plot share of total words instead of raw counts
+    pub title: Option<String>,
+}
+
+impl Default for ChartOptions {
+    fn default() -> Self {
+        Self {
+            width: 800,
+            height: 500,
+            log_scale: false,
+            percentage: false,
+            title: None,
+        }
+    }
+}
+
+/// Write the chart as SVG or PNG.
+pub fn write_chart(out: &mut impl Write, format: ChartFormat, series: &[ChartSeries], options: &ChartOptions) -> Result<()> {
+    let svg = render_svg(series, options);
+    match format {
+        ChartFormat::Svg => out.write_all(svg.as_bytes())?,
+        ChartFormat::Png => out.write_all(&rasterize(&svg)?)?,
+    }
+    Ok(())
+}
+
+/// Rasterize an SVG document with resvg. Text is set in the bundled DejaVu Sans, so PNGs look
+/// the same on every machine, including headless ones without fonts installed.
+pub fn rasterize(svg: &str) -> Result<Vec<u8>> {
+    let mut options = resvg::usvg::Options::default();
+    let fonts = options.fontdb_mut();
+    fonts.load_font_data(BUNDLED_FONT.to_vec());
+    fonts.load_system_fonts(); // Only for glyphs DejaVu Sans lacks, like CJK dataset names
+    fonts.set_sans_serif_family(BUNDLED_FONT_FAMILY);
+    options.font_family = BUNDLED_FONT_FAMILY.to_string();
+
+    let tree = resvg::usvg::Tree::from_str(svg, &options).context("Failed to parse chart SVG")?;
+    let size = tree.size().to_int_size();
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(size.width(), size.height())
+        .context("Chart size must be non-zero")?;
+    resvg::render(&tree, resvg::tiny_skia::Transform::default(), &mut pixmap.as_mut());
+
+    pixmap.encode_png().context("Failed to encode PNG")
+}
+
+/// Maps data values to one pixel axis, linearly or on a log10 scale.
+struct Axis {
+    min: f64,
+    max: f64,
+    log: bool,
+    start: f64, // Pixel position of `min`
+    end: f64,   // Pixel position of `max`
+}
+
+impl Axis {
+    fn new(values: impl Iterator<Item = f64>, log: bool, start: f64, end: f64) -> Self {
+        let (mut min, mut max) = values
+            .filter(|v| v.is_finite() && (!log || *v > 0.0))
+            .fold((f64::MAX, f64::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)));
+        if min > max {
+            (min, max) = (1.0, 10.0);
+        }
+
+        if log {
+            // Snap to whole decades so the tick labels line up with the frame
+            min = 10f64.powf(min.log10().floor());
+            max = 10f64.powf(max.log10().ceil());
+            if min == max {
+                max = min * 10.0;
+            }
+        } else {
+            let step = nice_step(max - min.min(0.0));
+            min = if min >= 0.0 { 0.0 } else { (min / step).floor() * step };
+            max = ((max / step).ceil() * step).max(min + step);
+        }
+
+        Self { min, max, log, start, end }
+    }
+
+    fn position(&self, value: f64) -> f64 {
+        let (value, min, max) = if self.log {
+            (value.max(self.min).log10(), self.min.log10(), self.max.log10())
+        } else {
+            (value, self.min, self.max)
+        };
+        self.start + (value - min) / (max - min) * (self.end - self.start)
+    }
+
+    fn ticks(&self) -> Vec<f64> {
+        if self.log {
+            let (first, last) = (self.min.log10().round() as i32, self.max.log10().round() as i32);
+            (first..=last).map(|exponent| 10f64.powi(exponent)).collect()
+        } else {
+            let step = nice_step(self.max - self.min);
+            let count = ((self.max - self.min) / step).round() as usize;
+            (0..=count).map(|i| self.min + i as f64 * step).collect()
+        }
+    }
+}
+
+/// A 1/2/5 × 10^k step giving roughly five ticks over `range`.
+fn nice_step(range: f64) -> f64 {
+    if range <= 0.0 {
+        return 1.0;
+    }
+    let rough = range / 5.0;
+    let magnitude = 10f64.powf(rough.log10().floor());
+    let step = match rough / magnitude {
+        r if r <= 1.0 => 1.0,
+        r if r <= 2.0 => 2.0,
+        r if r <= 5.0 => 5.0,
+        _ => 10.0,
+    };
+    step * magnitude
+}
+
+fn format_tick(value: f64) -> String {
+    if value.abs() >= 1_000_000.0 {
+        format!("{}M", value / 1_000_000.0)
+    } else if value.abs() >= 10_000.0 {
+        format!("{}k", value / 1_000.0)
+    } else if value.fract() == 0.0 {
+        format!("{}", value as i64)
+    } else {
+        let formatted = format!("{:.4}", value);
+        formatted.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+pub(crate) fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn series_value(series: &ChartSeries, count: f64, percentage: bool) -> f64 {
+    if percentage && series.total_words > 0 {
+        count / series.total_words as f64 * 100.0
+    } else {
+        count
+    }
+}
+
+/// Low and high ends of each word's bootstrap interval, in the chart's units, at the word's rank.
+fn whisker_values(series: &ChartSeries, percentage: bool) -> Vec<(f64, f64, f64)> {
+    let Some(intervals) = series.whiskers else {
+        return Vec::new();
+    };
+    let scale = if percentage { 1.0 } else { series.total_words as f64 / 100.0 };
+    series
+        .words
+        .iter()
+        .filter_map(|wc| {
+            let interval = intervals.word(&wc.word)?.percentage;
+            Some((wc.rank as f64, interval.low * scale, interval.high * scale))
+        })
+        .collect()
+}
+
+/// Render a rank/frequency chart of every series as a standalone SVG document.
+pub fn render_svg(series: &[ChartSeries], options: &ChartOptions) -> String {
+    let (width, height) = (options.width as f64, options.height as f64);
+    let top = if options.title.is_some() { 44.0 } else { 20.0 };
+    let (left, right, bottom) = (72.0, width - 20.0, height - 52.0);
+
+    let x_axis = Axis::new(
+        series.iter().flat_map(|s| s.words.iter().map(|wc| wc.rank as f64)),
+        options.log_scale,
+        left,
+        right,
+    );
+    let y_axis = Axis::new(
+        series.iter().flat_map(|s| {
+            let zipf_top = s.zipf_constant.into_iter().map(|c| series_value(s, c, options.percentage));
+            let whisker_tops = whisker_values(s, options.percentage).into_iter().map(|(_, _, high)| high);
+            s.words
+                .iter()
+                .map(|wc| series_value(s, wc.count as f64, options.percentage))
+                .chain(zipf_top)
+                .chain(whisker_tops)
+        }),
+        options.log_scale,
+        bottom,
+        top,
+    );
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="{FONT_FAMILY}" font-size="12">"#,
+        w = options.width,
+        h = options.height,
+    );
+    let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+
+    if let Some(title) = &options.title {
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.1}" y="26" text-anchor="middle" font-size="16" font-weight="bold">{}</text>"#,
+            width / 2.0,
+            escape_xml(title)
+        );
+    }
+
+    // Grid lines and tick labels
+    for tick in x_axis.ticks() {
+        let x = x_axis.position(tick);
+        let _ = writeln!(svg, r##"<line x1="{x:.1}" y1="{top:.1}" x2="{x:.1}" y2="{bottom:.1}" stroke="#e0e0e0"/>"##);
+        let _ = writeln!(
+            svg,
+            r#"<text x="{x:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+            bottom + 16.0,
+            format_tick(tick)
+        );
+    }
+    for tick in y_axis.ticks() {
+        let y = y_axis.position(tick);
+        let _ = writeln!(svg, r##"<line x1="{left:.1}" y1="{y:.1}" x2="{right:.1}" y2="{y:.1}" stroke="#e0e0e0"/>"##);
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="end">{}</text>"#,
+            left - 6.0,
+            y + 4.0,
+            format_tick(tick)
+        );
+    }
+    let _ = writeln!(
+        svg,
+        r##"<rect x="{left:.1}" y="{top:.1}" width="{:.1}" height="{:.1}" fill="none" stroke="#404040"/>"##,
+        right - left,
+        bottom - top
+    );
+
+    // Axis labels
+    let scale = if options.log_scale { " (log)" } else { "" };
+    let y_label = if options.percentage { "Frequency (%)" } else { "Frequency" };
+    let _ = writeln!(
+        svg,
+        r#"<text x="{:.1}" y="{:.1}" text-anchor="middle" font-size="13">Rank{scale}</text>"#,
+        (left + right) / 2.0,
+        height - 14.0
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text transform="translate(18 {:.1}) rotate(-90)" text-anchor="middle" font-size="13">{y_label}{scale}</text>"#,
+        (top + bottom) / 2.0
+    );
+
+    // Data and Zipf reference lines, clipped to the plot area
+    let _ = writeln!(
+        svg,
+        r#"<clipPath id="plot"><rect x="{left:.1}" y="{top:.1}" width="{:.1}" height="{:.1}"/></clipPath>"#,
+        right - left,
+        bottom - top
+    );
+    let _ = writeln!(svg, r#"<g clip-path="url(#plot)" fill="none" stroke-width="1.5">"#);
+    for (i, s) in series.iter().enumerate() {
+        let color = PALETTE[i % PALETTE.len()];
+
+        if let Some(constant) = s.zipf_constant {
+            let points = polyline_points(s.words.iter().map(|wc| {
+                let ideal = series_value(s, constant / wc.rank as f64, options.percentage);
+                (x_axis.position(wc.rank as f64), y_axis.position(ideal))
+            }));
+            let _ = writeln!(svg, r#"<polyline points="{points}" stroke="{color}" stroke-dasharray="6 4" opacity="0.6"/>"#);
+        }
+
+        let points = polyline_points(s.words.iter().map(|wc| {
+            let value = series_value(s, wc.count as f64, options.percentage);
+            (x_axis.position(wc.rank as f64), y_axis.position(value))
+        }));
+        let _ = writeln!(svg, r#"<polyline points="{points}" stroke="{color}"/>"#);
+
+        // Interval whiskers with short caps
+        for (rank, low, high) in whisker_values(s, options.percentage) {
+            let (x, y_low, y_high) = (x_axis.position(rank), y_axis.position(low), y_axis.position(high));
+            let _ = writeln!(
+                svg,
+                r#"<path d="M{x:.1} {y_low:.1}V{y_high:.1}M{:.1} {y_low:.1}h6M{:.1} {y_high:.1}h6" stroke="{color}" stroke-width="1" opacity="0.5"/>"#,
+                x - 3.0,
+                x - 3.0
+            );
+        }
+    }
+    let _ = writeln!(svg, "</g>");
+
+    // Legend in the top-right corner of the plot area
+    let has_zipf = series.iter().any(|s| s.zipf_constant.is_some());
+    let entries = series.len() + usize::from(has_zipf);
+    if entries > 0 {
+        let longest = series.iter().map(|s| s.name.chars().count()).max().unwrap_or(0).max(if has_zipf { 14 } else { 0 });
+        let box_width = 40.0 + longest as f64 * 7.0;
+        let box_x = right - box_width - 8.0;
+        let box_y = top + 8.0;
+        let _ = writeln!(
+            svg,
+            r##"<rect x="{box_x:.1}" y="{box_y:.1}" width="{box_width:.1}" height="{:.1}" fill="white" fill-opacity="0.85" stroke="#c0c0c0"/>"##,
+            entries as f64 * 18.0 + 8.0
+        );
+        for (i, s) in series.iter().enumerate() {
+            let y = box_y + 16.0 + i as f64 * 18.0;
+            let _ = writeln!(
+                svg,
+                r#"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="{}" stroke-width="2"/>"#,
+                box_x + 8.0,
+                y - 4.0,
+                box_x + 28.0,
+                y - 4.0,
+                PALETTE[i % PALETTE.len()]
+            );
+            let _ = writeln!(svg, r#"<text x="{:.1}" y="{y:.1}">{}</text>"#, box_x + 34.0, escape_xml(s.name));
+        }
+        if has_zipf {
+            let y = box_y + 16.0 + series.len() as f64 * 18.0;
+            let _ = writeln!(
+                svg,
+                r##"<line x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}" stroke="#606060" stroke-width="1.5" stroke-dasharray="6 4"/>"##,
+                box_x + 8.0,
+                y - 4.0,
+                box_x + 28.0,
+                y - 4.0
+            );
+            let _ = writeln!(svg, r#"<text x="{:.1}" y="{y:.1}">Zipf (C / rank)</text>"#, box_x + 34.0);
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// A bar chart of binned counts (e.g. sentence lengths) as SVG, in the `index`-th palette color.
+/// `bars` are (label under the bar, count); labels are thinned out when they would overlap.
+pub fn render_histogram_svg(bars: &[(String, usize)], x_label: &str, y_label: &str, index: usize) -> String {
+    let (width, height) = (560.0, 260.0);
+    let (left, right, top, bottom) = (64.0, width - 16.0, 16.0, height - 48.0);
+    let y_axis = Axis::new(bars.iter().map(|(_, count)| *count as f64), false, bottom, top);
+    let color = PALETTE[index % PALETTE.len()];
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="{FONT_FAMILY}" font-size="12">"#
+    );
+    let _ = writeln!(svg, r#"<rect width="100%" height="100%" fill="white"/>"#);
+    for tick in y_axis.ticks() {
+        let y = y_axis.position(tick);
+        let _ = writeln!(svg, r##"<line x1="{left:.1}" y1="{y:.1}" x2="{right:.1}" y2="{y:.1}" stroke="#e0e0e0"/>"##);
+        let _ = writeln!(
+            svg,
+            r#"<text x="{:.1}" y="{:.1}" text-anchor="end">{}</text>"#,
+            left - 6.0,
+            y + 4.0,
+            format_tick(tick)
+        );
+    }
+
+    let slot = (right - left) / bars.len().max(1) as f64;
+    let label_every = bars.len().div_ceil(12).max(1);
+    for (i, (label, count)) in bars.iter().enumerate() {
+        let x = left + i as f64 * slot;
+        let y = y_axis.position(*count as f64);
+        let _ = writeln!(
+            svg,
+            r#"<rect x="{:.1}" y="{y:.1}" width="{:.1}" height="{:.1}" fill="{color}"><title>{}: {count}</title></rect>"#,
+            x + slot * 0.1,
+            slot * 0.8,
+            bottom - y,
+            escape_xml(label)
+        );
+        if i % label_every == 0 {
+            let _ = writeln!(
+                svg,
+                r#"<text x="{:.1}" y="{:.1}" text-anchor="middle">{}</text>"#,
+                x + slot / 2.0,
+                bottom + 16.0,
+                escape_xml(label)
+            );
+        }
+    }
+    let _ = writeln!(
+        svg,
+        r##"<line x1="{left:.1}" y1="{bottom:.1}" x2="{right:.1}" y2="{bottom:.1}" stroke="#404040"/>"##
+    );
+
+    let _ = writeln!(
+        svg,
+        r#"<text x="{:.1}" y="{:.1}" text-anchor="middle" font-size="13">{}</text>"#,
+        (left + right) / 2.0,
+        height - 12.0,
+        escape_xml(x_label)
+    );
+    let _ = writeln!(
+        svg,
+        r#"<text transform="translate(18 {:.1}) rotate(-90)" text-anchor="middle" font-size="13">{}</text>"#,
+        (top + bottom) / 2.0,
+        escape_xml(y_label)
+    );
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn polyline_points(points: impl Iterator<Item = (f64, f64)>) -> String {
+    let mut out = String::new();
+    for (x, y) in points {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        let _ = write!(out, "{:.1},{:.1}", x, y);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::ranked;
+    use crate::bootstrap::{bootstrap, BootstrapOptions, Resample};
+
+    fn render(a: &[WordCount], b: &[WordCount], whiskers: Option<&Bootstrap>) -> String {
+        let series = vec![
+            ChartSeries { name: "alice & co", words: a, total_words: 208, zipf_constant: Some(100.0), whiskers: None },
+            ChartSeries { name: "dracula", words: b, total_words: 70, zipf_constant: None, whiskers },
+        ];
+        let options = ChartOptions { log_scale: true, title: Some("Test".to_string()), ..ChartOptions::default() };
+        render_svg(&series, &options)
+    }
+
+    #[test]
+    fn test_log_axis_snaps_to_decades() {
+        let axis = Axis::new([3.0, 450.0].into_iter(), true, 0.0, 300.0);
+        assert_eq!(axis.ticks(), vec![1.0, 10.0, 100.0, 1000.0]);
+        assert_eq!(axis.position(1.0), 0.0);
+        assert_eq!(axis.position(1000.0), 300.0);
+
+        let axis = Axis::new([0.0, 87.0].into_iter(), false, 0.0, 100.0);
+        assert_eq!(axis.ticks(), vec![0.0, 20.0, 40.0, 60.0, 80.0, 100.0]);
+    }
+
+    #[test]
+    fn test_svg_overlay() {
+        let svg = render(&ranked(&[100, 50, 33, 25]), &ranked(&[40, 20, 10]), None);
+        // One line per dataset plus alice's Zipf reference
+        assert_eq!(svg.matches("<polyline").count(), 3);
+        assert!(svg.contains("alice &amp; co"));
+        assert!(svg.contains("Rank (log)"));
+    }
+
+    #[test]
+    fn test_svg_whiskers() {
+        let b = ranked(&[40, 20, 10]);
+        let options = BootstrapOptions { samples: 20, resample: Resample::Token, chunk_size: 1, confidence: 0.9, seed: Some(1) };
+        let intervals = bootstrap(&b, None, &options).unwrap();
+
+        let svg = render(&ranked(&[100, 50, 33, 25]), &b, Some(&intervals));
+        assert_eq!(svg.matches("<path").count(), 3); // One whisker per dracula word
+    }
+
+    #[test]
+    fn test_png() {
+        let svg = render(&ranked(&[100, 50, 33, 25]), &ranked(&[40, 20, 10]), None);
+        let png = rasterize(&svg).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+    }
+}
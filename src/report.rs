@@ -0,0 +1,452 @@
+use crate::analyzer::{Dataset, Tag, WordCount};
+use crate::filter::{CrossDatasetFilter, FilterSet};
+use crate::plot::{escape_xml, render_histogram_svg, render_svg, ChartOptions, ChartSeries};
+use crate::stats::{fit_zipf, word_lengths};
+use anyhow::Result;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::Write;
+
+/// Rows in the side-by-side comparison table.
+const COMPARISON_ROWS: usize = 20;
+
+/// Most bars in a sentence-length histogram.
+const SENTENCE_BINS: usize = 30;
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; margin: 2em auto; max-width: 1100px; color: #222; padding: 0 1em; }
+h1 { margin-bottom: 0.2em; }
+h2 { border-bottom: 1px solid #ddd; padding-bottom: 0.2em; margin-top: 1.6em; }
+table { border-collapse: collapse; margin: 0.8em 0; font-size: 0.9em; }
+th, td { border: 1px solid #ddd; padding: 0.25em 0.6em; text-align: left; }
+th { background: #f4f4f4; }
+td.num, th.num { text-align: right; }
+table.sortable th { cursor: pointer; user-select: none; }
+table.sortable th[data-order="asc"]::after { content: " \25B2"; }
+table.sortable th[data-order="desc"]::after { content: " \25BC"; }
+.scroll { max-height: 32em; overflow-y: auto; display: inline-block; }
+.scroll th { position: sticky; top: 0; }
+.tag { display: inline-block; color: white; border-radius: 3px; padding: 0 0.35em; margin-right: 0.2em; font-size: 0.85em; font-weight: bold; }
+.muted { color: #777; }
+"#;
+
+/// Click a header to sort its table; numeric columns sort by `data-value`.
+const SCRIPT: &str = r#"
+document.querySelectorAll("table.sortable").forEach(function (table) {
+  table.querySelectorAll("th").forEach(function (th, column) {
+    th.addEventListener("click", function () {
+      var order = th.dataset.order === "asc" ? "desc" : "asc";
+      table.querySelectorAll("th").forEach(function (other) { delete other.dataset.order; });
+      th.dataset.order = order;
+      var body = table.tBodies[0];
+      var rows = Array.from(body.rows);
+      var key = function (row) {
+        var cell = row.cells[column];
+        return cell.dataset.value !== undefined ? parseFloat(cell.dataset.value) : cell.textContent.toLowerCase();
+      };
+      rows.sort(function (a, b) {
+        var x = key(a), y = key(b);
+        var result = x < y ? -1 : x > y ? 1 : 0;
+        return order === "asc" ? result : -result;
+      });
+      rows.forEach(function (row) { body.appendChild(row); });
+    });
+  });
+});
+"#;
+
+/// What goes into the report: datasets, their filtered word lists and the filter used.
+pub struct ReportContext<'a> {
+    pub datasets: &'a [Dataset],
+    pub words: &'a [Vec<WordCount>],
+    pub filter_set: &'a FilterSet,
+    pub top: Option<usize>, // Rows per frequency table; all words when unset
+}
+
+/// Write a single self-contained HTML report (inline CSS, JS and SVG).
+pub fn write_report(out: &mut impl Write, context: &ReportContext) -> Result<()> {
+    let names: Vec<&str> = context.datasets.iter().map(|d| d.name.as_str()).collect();
+
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, "<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">")?;
+    writeln!(out, "<title>Zipfr report: {}</title>", escape_xml(&names.join(", ")))?;
+    writeln!(out, "<style>{}</style>\n</head>\n<body>", STYLE)?;
+    writeln!(out, "<h1>Zipfian analysis report</h1>")?;
+    writeln!(
+        out,
+        "<p class=\"muted\">Generated by zipfr {} from {} dataset(s): {}</p>",
+        env!("CARGO_PKG_VERSION"),
+        names.len(),
+        escape_xml(&names.join(", "))
+    )?;
+
+    write_filter_section(out, context.filter_set)?;
+    write_summary_section(out, context)?;
+    write_chart_section(out, context)?;
+    write_sentence_section(out, context)?;
+    write_word_length_section(out, context)?;
+    if context.datasets.len() > 1 {
+        write_comparison_section(out, context)?;
+    }
+    write_frequency_sections(out, context)?;
+
+    writeln!(out, "<script>{}</script>\n</body>\n</html>", SCRIPT)?;
+    Ok(())
+}
+
+fn tag_names(tags: &[Tag]) -> String {
+    tags.iter().map(|tag| escape_xml(&tag.name)).collect::<Vec<_>>().join(", ")
+}
+
+fn write_filter_section(out: &mut impl Write, filter_set: &FilterSet) -> Result<()> {
+    writeln!(out, "<h2>Filters</h2>")?;
+    if filter_set.is_empty() {
+        writeln!(out, "<p>No filters: all words are included.</p>")?;
+        return Ok(());
+    }
+
+    writeln!(out, "<ul>")?;
+    if !filter_set.exclude_tags.is_empty() {
+        writeln!(out, "<li>Excluding tags: {}</li>", tag_names(&filter_set.exclude_tags))?;
+    }
+    if !filter_set.include_only_tags.is_empty() {
+        writeln!(out, "<li>Only words tagged: {}</li>", tag_names(&filter_set.include_only_tags))?;
+    }
+    if filter_set.exclude_single {
+        writeln!(out, "<li>Excluding words that occur only once</li>")?;
+    }
+    match filter_set.cross_dataset {
+        CrossDatasetFilter::None => {}
+        CrossDatasetFilter::CommonOnly => writeln!(out, "<li>Only words common to all datasets</li>")?,
+        CrossDatasetFilter::UniqueOnly => writeln!(out, "<li>Only words unique to each dataset</li>")?,
+    }
+    writeln!(out, "</ul>")?;
+    Ok(())
+}
+
+fn write_summary_section(out: &mut impl Write, context: &ReportContext) -> Result<()> {
+    writeln!(out, "<h2>Summary</h2>")?;
+    writeln!(out, "<table class=\"sortable\">\n<thead><tr>")?;
+    writeln!(
+        out,
+        "<th>Dataset</th><th>Language</th><th class=\"num\">Total words</th><th class=\"num\">Unique words</th>\
+         <th class=\"num\">Filtered total</th><th class=\"num\">Filtered unique</th>\
+         <th class=\"num\">Zipf exponent</th><th class=\"num\">R²</th>"
+    )?;
+    writeln!(out, "</tr></thead>\n<tbody>")?;
+
+    for (dataset, words) in context.datasets.iter().zip(context.words) {
+        // Ratio scores do not add up to a token count
+        let total_cell = |total: usize| if dataset.is_ratio() { "<td class=\"num\">–</td>".to_string() } else { number_cell(total) };
+        let filtered_total: usize = words.iter().map(|wc| wc.count).sum();
+        let language = dataset.language.as_ref().map(|l| l.name.as_str()).unwrap_or("Unknown");
+        let (exponent, r_squared) = match fit_zipf(words).filter(|_| !dataset.is_ratio()) {
+            Some(fit) => (format!("{:.3}", fit.exponent), format!("{:.3}", fit.r_squared)),
+            None => ("–".to_string(), "–".to_string()),
+        };
+
+        writeln!(
+            out,
+            "<tr><td>{}</td><td>{}</td>{}{}{}{}<td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+            escape_xml(&dataset.name),
+            escape_xml(language),
+            total_cell(dataset.total_words),
+            number_cell(dataset.unique_words),
+            total_cell(filtered_total),
+            number_cell(words.len()),
+            exponent,
+            r_squared
+        )?;
+    }
+
+    writeln!(out, "</tbody>\n</table>")?;
+    Ok(())
+}
+
+fn number_cell(value: usize) -> String {
+    format!("<td class=\"num\" data-value=\"{0}\">{0}</td>", value)
+}
+
+fn write_chart_section(out: &mut impl Write, context: &ReportContext) -> Result<()> {
+    // Percentages keep datasets of different sizes comparable in the overlay
+    let percentage = context.datasets.len() > 1;
+    let series: Vec<ChartSeries> = context
+        .datasets
+        .iter()
+        .zip(context.words)
+        .map(|(dataset, words)| ChartSeries {
+            name: &dataset.name,
+            words,
+            total_words: dataset.total_words,
+            zipf_constant: words.first().filter(|_| !dataset.is_ratio()).map(|wc| wc.count as f64),
+            whiskers: dataset.bootstrap.as_ref(),
+        })
+        .collect();
+    let options = ChartOptions { log_scale: true, percentage, ..ChartOptions::default() };
+
+    writeln!(out, "<h2>Rank / frequency (log-log)</h2>")?;
+    write!(out, "{}", render_svg(&series, &options))?;
+    writeln!(out, "<p class=\"muted\">Dashed lines show the ideal Zipf distribution C / rank for each dataset.</p>")?;
+    if let Some(intervals) = context.datasets.iter().find_map(|dataset| dataset.bootstrap.as_ref()) {
+        writeln!(
+            out,
+            "<p class=\"muted\">Whiskers show {}% bootstrap intervals of each word's frequency ({} resamples).</p>",
+            intervals.confidence * 100.0,
+            intervals.samples
+        )?;
+    }
+    Ok(())
+}
+
+/// Sentence counts and length histograms for datasets read as prose (skipped when there are none).
+fn write_sentence_section(out: &mut impl Write, context: &ReportContext) -> Result<()> {
+    if context.datasets.iter().all(|dataset| dataset.segments.is_none()) {
+        return Ok(());
+    }
+
+    writeln!(out, "<h2>Sentences</h2>")?;
+    writeln!(out, "<table class=\"sortable\">\n<thead><tr>")?;
+    writeln!(
+        out,
+        "<th>Dataset</th><th class=\"num\">Paragraphs</th><th class=\"num\">Sentences</th>\
+         <th class=\"num\">Mean length</th><th class=\"num\">Median length</th><th class=\"num\">Longest</th>"
+    )?;
+    writeln!(out, "</tr></thead>\n<tbody>")?;
+    for dataset in context.datasets {
+        let Some(segments) = &dataset.segments else { continue };
+        let length = |value: Option<f64>| value.map_or("–".to_string(), |value| format!("{:.1}", value));
+        writeln!(
+            out,
+            "<tr><td>{}</td>{}{}<td class=\"num\">{}</td><td class=\"num\">{}</td>{}</tr>",
+            escape_xml(&dataset.name),
+            number_cell(segments.paragraphs),
+            number_cell(segments.sentences()),
+            length(segments.mean_length()),
+            length(segments.median_length()),
+            number_cell(segments.max_length())
+        )?;
+    }
+    writeln!(out, "</tbody>\n</table>")?;
+
+    for (index, dataset) in context.datasets.iter().enumerate() {
+        let Some(segments) = dataset.segments.as_ref().filter(|segments| segments.sentences() > 0) else { continue };
+        let bars: Vec<(String, usize)> = segments
+            .histogram(SENTENCE_BINS)
+            .into_iter()
+            .map(|(first, last, count)| {
+                let label = if first == last { first.to_string() } else { format!("{}–{}", first, last) };
+                (label, count)
+            })
+            .collect();
+        writeln!(out, "<h3>Sentence lengths: {}</h3>", escape_xml(&dataset.name))?;
+        write!(out, "{}", render_histogram_svg(&bars, "Sentence length (words)", "Sentences", index))?;
+    }
+    Ok(())
+}
+
+/// Word-length distributions of the filtered words by type and token, and the rank–length correlation.
+fn write_word_length_section(out: &mut impl Write, context: &ReportContext) -> Result<()> {
+    let lengths: Vec<_> = context.words.iter().map(|words| word_lengths(words)).collect();
+    let format = |value: Option<f64>, precision: usize| value.map_or("–".to_string(), |value| format!("{:.*}", precision, value));
+
+    writeln!(out, "<h2>Word lengths</h2>")?;
+    writeln!(
+        out,
+        "<p class=\"muted\">Lengths in characters. A positive rank–length correlation means frequent words are shorter (Zipf's law of abbreviation).</p>"
+    )?;
+    writeln!(out, "<table class=\"sortable\">\n<thead><tr>")?;
+    writeln!(
+        out,
+        "<th>Dataset</th><th class=\"num\">Mean length (types)</th><th class=\"num\">Mean length (tokens)</th>\
+         <th class=\"num\">Rank–length ρ (Spearman)</th>"
+    )?;
+    writeln!(out, "</tr></thead>\n<tbody>")?;
+    for (dataset, stats) in context.datasets.iter().zip(&lengths) {
+        writeln!(
+            out,
+            "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+            escape_xml(&dataset.name),
+            format(stats.mean_type_length, 2),
+            format(stats.mean_token_length, 2),
+            format(stats.rank_correlation, 3)
+        )?;
+    }
+    writeln!(out, "</tbody>\n</table>")?;
+
+    // Distribution: one row per length, types and tokens per dataset
+    let all_lengths: BTreeSet<usize> = lengths.iter().flat_map(|stats| stats.by_type.keys().copied()).collect();
+    writeln!(out, "<div class=\"scroll\"><table class=\"sortable\">\n<thead><tr><th class=\"num\">Length</th>")?;
+    for dataset in context.datasets {
+        let name = escape_xml(&dataset.name);
+        write!(out, "<th class=\"num\">{0}: types</th><th class=\"num\">{0}: tokens</th>", name)?;
+    }
+    writeln!(out, "</tr></thead>\n<tbody>")?;
+    for length in all_lengths {
+        write!(out, "<tr>{}", number_cell(length))?;
+        for stats in &lengths {
+            let types = stats.by_type.get(&length).copied().unwrap_or(0);
+            let tokens = stats.by_token.get(&length).copied().unwrap_or(0);
+            write!(out, "{}{}", number_cell(types), number_cell(tokens))?;
+        }
+        writeln!(out, "</tr>")?;
+    }
+    writeln!(out, "</tbody>\n</table></div>")?;
+    Ok(())
+}
+
+fn write_comparison_section(out: &mut impl Write, context: &ReportContext) -> Result<()> {
+    let counts: Vec<HashMap<&str, usize>> = context
+        .words
+        .iter()
+        .map(|words| words.iter().map(|wc| (wc.word.as_str(), wc.count)).collect())
+        .collect();
+    let shared: HashSet<&str> = counts[0]
+        .keys()
+        .filter(|word| counts[1..].iter().all(|other| other.contains_key(*word)))
+        .copied()
+        .collect();
+
+    writeln!(out, "<h2>Comparison</h2>")?;
+    writeln!(out, "<p>{} words appear in every dataset.</p>", shared.len())?;
+
+    // Vocabulary overlap per dataset
+    writeln!(out, "<table class=\"sortable\">\n<thead><tr><th>Dataset</th><th class=\"num\">Words</th><th class=\"num\">Shared with all</th><th class=\"num\">Only in this dataset</th></tr></thead>\n<tbody>")?;
+    for (dataset, dataset_counts) in context.datasets.iter().zip(&counts) {
+        let only_here = dataset_counts
+            .keys()
+            .filter(|word| counts.iter().filter(|other| other.contains_key(*word)).count() == 1)
+            .count();
+        writeln!(
+            out,
+            "<tr><td>{}</td>{}{}{}</tr>",
+            escape_xml(&dataset.name),
+            number_cell(dataset_counts.len()),
+            number_cell(shared.len()),
+            number_cell(only_here)
+        )?;
+    }
+    writeln!(out, "</tbody>\n</table>")?;
+
+    // Top words side by side, like the TUI multi-dataset view
+    writeln!(out, "<h3>Top {} words side by side</h3>", COMPARISON_ROWS)?;
+    writeln!(out, "<table>\n<thead><tr><th class=\"num\">Rank</th>")?;
+    for dataset in context.datasets {
+        writeln!(out, "<th>{}</th>", escape_xml(&dataset.name))?;
+    }
+    writeln!(out, "</tr></thead>\n<tbody>")?;
+    let rows = context.words.iter().map(|words| words.len().min(COMPARISON_ROWS)).max().unwrap_or(0);
+    for row in 0..rows {
+        write!(out, "<tr><td class=\"num\">{}</td>", row + 1)?;
+        for words in context.words {
+            match words.get(row) {
+                Some(wc) => write!(out, "<td>{} <span class=\"muted\">({})</span></td>", escape_xml(&wc.word), wc.count)?,
+                None => write!(out, "<td></td>")?,
+            }
+        }
+        writeln!(out, "</tr>")?;
+    }
+    writeln!(out, "</tbody>\n</table>")?;
+
+    // Shared words with their counts in every dataset
+    let mut shared_words: Vec<&str> = shared.into_iter().collect();
+    shared_words.sort_by_key(|word| (std::cmp::Reverse(counts.iter().map(|c| c[word]).sum::<usize>()), *word));
+    shared_words.truncate(COMPARISON_ROWS);
+    if !shared_words.is_empty() {
+        writeln!(out, "<h3>Most frequent shared words</h3>")?;
+        writeln!(out, "<table class=\"sortable\">\n<thead><tr><th>Word</th>")?;
+        for dataset in context.datasets {
+            writeln!(out, "<th class=\"num\">{}</th>", escape_xml(&dataset.name))?;
+        }
+        writeln!(out, "</tr></thead>\n<tbody>")?;
+        for word in shared_words {
+            write!(out, "<tr><td>{}</td>", escape_xml(word))?;
+            for dataset_counts in &counts {
+                write!(out, "{}", number_cell(dataset_counts[word]))?;
+            }
+            writeln!(out, "</tr>")?;
+        }
+        writeln!(out, "</tbody>\n</table>")?;
+    }
+
+    Ok(())
+}
+
+fn write_frequency_sections(out: &mut impl Write, context: &ReportContext) -> Result<()> {
+    for (dataset, words) in context.datasets.iter().zip(context.words) {
+        let rows = &words[..context.top.unwrap_or(usize::MAX).min(words.len())];
+
+        writeln!(out, "<h2>Frequencies: {}</h2>", escape_xml(&dataset.name))?;
+        writeln!(
+            out,
+            "<p class=\"muted\">Showing {} of {} words; click a column header to sort.</p>",
+            rows.len(),
+            words.len()
+        )?;
+        writeln!(out, "<div class=\"scroll\"><table class=\"sortable\">")?;
+        writeln!(out, "<thead><tr><th class=\"num\">Rank</th><th>Word</th><th class=\"num\">Count</th><th class=\"num\">%</th><th>Tags</th></tr></thead>\n<tbody>")?;
+
+        for wc in rows {
+            let percentage = match dataset.percentage(wc.count) {
+                Some(percentage) => format!("<td class=\"num\" data-value=\"{:.6}\">{:.3}</td>", percentage, percentage),
+                None => "<td class=\"num\" data-value=\"0\">–</td>".to_string(),
+            };
+            let mut tags: Vec<&Tag> = wc.tags.iter().collect();
+            tags.sort_by_key(|tag| tag.letter);
+            let tags: String = tags
+                .iter()
+                .map(|tag| {
+                    format!(
+                        "<span class=\"tag\" style=\"background:{}\" title=\"{}\">{}</span>",
+                        tag.css_color(),
+                        escape_xml(&tag.name),
+                        escape_xml(&tag.letter.to_string())
+                    )
+                })
+                .collect();
+
+            writeln!(
+                out,
+                "<tr>{}<td>{}</td>{}{}<td>{}</td></tr>",
+                number_cell(wc.rank),
+                escape_xml(&wc.word),
+                number_cell(wc.count),
+                percentage,
+                tags
+            )?;
+        }
+
+        writeln!(out, "</tbody>\n</table></div>")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segment::segment;
+    use crate::tokens::TokenOptions;
+
+    fn dataset(name: &str, text: &str) -> Dataset {
+        Dataset { segments: Some(segment(text, &TokenOptions::default())), ..Dataset::from_text(name, text) }
+    }
+
+    #[test]
+    fn test_report_is_self_contained() {
+        let datasets = vec![dataset("<alice>", "the the the cat cat dog"), dataset("bob", "the the cat bird")];
+        let words: Vec<Vec<WordCount>> = datasets.iter().map(|d| d.word_counts.clone()).collect();
+        let mut filter_set = FilterSet::new();
+        filter_set.exclude_single = true;
+        let context = ReportContext { datasets: &datasets, words: &words, filter_set: &filter_set, top: None };
+
+        let mut out = Vec::new();
+        write_report(&mut out, &context).unwrap();
+        let html = String::from_utf8(out).unwrap();
+
+        assert!(html.contains("&lt;alice&gt;"));
+        assert!(html.contains("<svg"));
+        assert!(html.contains("Excluding words that occur only once"));
+        assert!(html.contains("2 words appear in every dataset"));
+        assert!(html.contains("<h3>Sentence lengths: bob</h3>"));
+        assert!(html.contains("<th class=\"num\">bob: tokens</th>"));
+        assert!(!html.contains("src=\"http") && !html.contains("href=\"http"));
+    }
+}
@@ -0,0 +1,150 @@
+use clap::ValueEnum;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// How `--sample-tokens` picks its tokens.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SampleMode {
+    #[default]
+    Random,     // N tokens drawn without replacement, kept in text order
+    Contiguous, // One run of N consecutive tokens from a random start
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SampleOptions {
+    pub tokens: usize,
+    pub mode: SampleMode,
+    pub seed: Option<u64>, // Random when None
+}
+
+/// A generator seeded with `seed`, or from the operating system when None.
+pub fn seeded_rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_os_rng(),
+    }
+}
+
+/// Keep `options.tokens` of the tokens. Texts no longer than the sample are kept whole.
+pub fn sample_tokens(tokens: Vec<String>, options: &SampleOptions) -> Vec<String> {
+    let size = options.tokens;
+    if tokens.len() <= size {
+        return tokens;
+    }
+    let mut rng = seeded_rng(options.seed);
+
+    match options.mode {
+        SampleMode::Contiguous => {
+            let start = rng.random_range(0..=tokens.len() - size);
+            tokens.into_iter().skip(start).take(size).collect()
+        }
+        SampleMode::Random => {
+            let mut keep = rand::seq::index::sample(&mut rng, tokens.len(), size).into_vec();
+            keep.sort_unstable();
+            let mut keep = keep.into_iter().peekable();
+            tokens
+                .into_iter()
+                .enumerate()
+                .filter(|&(index, _)| keep.next_if_eq(&index).is_some())
+                .map(|(_, token)| token)
+                .collect()
+        }
+    }
+}
+
+/// One point of a rarefaction curve.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct RarefactionPoint {
+    pub tokens: usize,
+    pub expected_types: f64,
+}
+
+/// Sample sizes for rarefaction: 100, 200, 500, 1000, ... below `total`, then `total` itself.
+pub fn sample_sizes(total: usize) -> Vec<usize> {
+    let mut sizes: Vec<usize> = (2..)
+        .map(|exponent| 10usize.pow(exponent))
+        .take_while(|&decade| decade < total)
+        .flat_map(|decade| [decade, 2 * decade, 5 * decade])
+        .filter(|&size| size < total)
+        .collect();
+    if total > 0 {
+        sizes.push(total);
+    }
+    sizes
+}
+
+/// Expected number of distinct words in a sample of each size drawn without replacement from
+/// the counted tokens (Hurlbert's rarefaction). Sizes beyond the total give every type.
+pub fn rarefaction(counts: impl IntoIterator<Item = usize>, sizes: &[usize]) -> Vec<RarefactionPoint> {
+    // Words with the same count are equally likely to be missed, so work per count
+    let mut spectrum: BTreeMap<usize, usize> = BTreeMap::new();
+    for count in counts.into_iter().filter(|&count| count > 0) {
+        *spectrum.entry(count).or_default() += 1;
+    }
+    let total: usize = spectrum.iter().map(|(count, words)| count * words).sum();
+    let types: usize = spectrum.values().sum();
+
+    sizes
+        .iter()
+        .map(|&size| {
+            let expected_types = if size >= total {
+                types as f64
+            } else {
+                spectrum
+                    .iter()
+                    .map(|(&count, &words)| words as f64 * (1.0 - missed(total, size, count)))
+                    .sum()
+            };
+            RarefactionPoint { tokens: size, expected_types }
+        })
+        .collect()
+}
+
+/// Probability that a sample of `size` of `total` tokens misses all `count` tokens of a word:
+/// C(total − size, count) / C(total, count).
+fn missed(total: usize, size: usize, count: usize) -> f64 {
+    let mut probability = 1.0;
+    for j in 0..count {
+        if total - size <= j {
+            return 0.0;
+        }
+        probability *= (total - size - j) as f64 / (total - j) as f64;
+        if probability < 1e-300 {
+            return 0.0;
+        }
+    }
+    probability
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sampling_and_rarefaction() {
+        let tokens: Vec<String> = (0..100).map(|i| format!("w{}", i)).collect();
+        let options = SampleOptions { tokens: 10, mode: SampleMode::Random, seed: Some(3) };
+        let sample = sample_tokens(tokens.clone(), &options);
+        assert_eq!(sample.len(), 10);
+        assert!(sample.windows(2).all(|pair| tokens.iter().position(|t| *t == pair[0]) < tokens.iter().position(|t| *t == pair[1])));
+        assert_eq!(sample_tokens(tokens.clone(), &options), sample);
+
+        let run = sample_tokens(tokens.clone(), &SampleOptions { mode: SampleMode::Contiguous, ..options });
+        let start = tokens.iter().position(|t| *t == run[0]).unwrap();
+        assert_eq!(run, tokens[start..start + 10]);
+        assert_eq!(sample_tokens(tokens[..5].to_vec(), &options).len(), 5);
+
+        assert_eq!(sample_sizes(1_500), vec![100, 200, 500, 1000, 1500]);
+        assert_eq!(sample_sizes(50), vec![50]);
+
+        // "a" ×2, "b" ×1, "c" ×1: one token misses "a" with probability C(2,1)/C(4,1) = 1/2
+        let curve = rarefaction([2, 1, 1], &[1, 2, 4, 10]);
+        let expected: Vec<f64> = curve.iter().map(|point| point.expected_types).collect();
+        assert!((expected[0] - 1.0).abs() < 1e-12);
+        // Two tokens: "a" is missed with C(2,2)/C(4,2) = 1/6, "b" and "c" with 1/2 each
+        assert!((expected[1] - (5.0 / 6.0 + 0.5 + 0.5)).abs() < 1e-12);
+        assert_eq!(expected[2..], [3.0, 3.0]);
+    }
+}
@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::tokens::{extract_tokens, TokenOptions};
+
+/// Lowercased abbreviations (without dots) whose period does not end a sentence.
+/// Words that also end sentences on their own ("no", "sat") are left out on purpose.
+const ABBREVIATIONS: &[&str] = &[
+    "mr", "mrs", "ms", "dr", "prof", "sr", "jr", "st", "mt", "ft", "vs", "etc", "eg", "ie", "cf", "al", "vol", "vols",
+    "fig", "figs", "pp", "ed", "eds", "ch", "approx", "dept", "est", "inc", "ltd", "co", "corp", "gen", "gov", "sen",
+    "rep", "rev", "lt", "col", "sgt", "capt", "jan", "feb", "apr", "jun", "jul", "aug", "sep", "sept", "oct", "nov",
+];
+
+const TERMINALS: &[char] = &['.', '!', '?', '…'];
+
+/// Closing quotes and brackets that belong to the sentence before them.
+const CLOSERS: &[char] = &['"', '\'', '”', '’', ')', ']', '»'];
+
+/// Opening quotes and brackets that may start a sentence.
+const OPENERS: &[char] = &['"', '\'', '“', '‘', '(', '[', '«', '¿', '¡'];
+
+/// Sentence and paragraph counts of a text, with sentence lengths in words.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SegmentStats {
+    pub paragraphs: usize,
+    pub sentence_lengths: BTreeMap<usize, usize>, // Words per sentence → number of sentences
+}
+
+impl SegmentStats {
+    pub fn sentences(&self) -> usize {
+        self.sentence_lengths.values().sum()
+    }
+
+    pub fn mean_length(&self) -> Option<f64> {
+        let sentences = self.sentences();
+        let words: usize = self.sentence_lengths.iter().map(|(length, count)| length * count).sum();
+        (sentences > 0).then(|| words as f64 / sentences as f64)
+    }
+
+    pub fn median_length(&self) -> Option<f64> {
+        let sentences = self.sentences();
+        if sentences == 0 {
+            return None;
+        }
+        // Lengths at the two middle positions (the same one for an odd count)
+        let nth = |position: usize| {
+            let mut seen = 0;
+            self.sentence_lengths
+                .iter()
+                .find(|(_, count)| {
+                    seen += **count;
+                    seen > position
+                })
+                .map_or(0, |(length, _)| *length)
+        };
+        Some((nth((sentences - 1) / 2) + nth(sentences / 2)) as f64 / 2.0)
+    }
+
+    pub fn max_length(&self) -> usize {
+        self.sentence_lengths.keys().next_back().copied().unwrap_or(0)
+    }
+
+    /// Sentences per length bin, at most `max_bins` bins of equal width starting at length 1.
+    /// Returns (first length in bin, last length in bin, sentences).
+    pub fn histogram(&self, max_bins: usize) -> Vec<(usize, usize, usize)> {
+        let max_length = self.max_length();
+        if max_length == 0 || max_bins == 0 {
+            return Vec::new();
+        }
+        let width = max_length.div_ceil(max_bins);
+        let mut bins: Vec<(usize, usize, usize)> = (0..max_length.div_ceil(width))
+            .map(|bin| (bin * width + 1, (bin + 1) * width, 0))
+            .collect();
+        for (length, count) in &self.sentence_lengths {
+            bins[(length - 1) / width].2 += count;
+        }
+        bins
+    }
+
+    pub fn merge(&mut self, other: &SegmentStats) {
+        self.paragraphs += other.paragraphs;
+        for (length, count) in &other.sentence_lengths {
+            *self.sentence_lengths.entry(*length).or_default() += count;
+        }
+    }
+
+    fn add_paragraph(&mut self, paragraph: &str, options: &TokenOptions) {
+        let mut has_words = false;
+        for sentence in split_sentences(paragraph) {
+            let length = extract_tokens(sentence, options).len();
+            if length > 0 {
+                *self.sentence_lengths.entry(length).or_default() += 1;
+                has_words = true;
+            }
+        }
+        if has_words {
+            self.paragraphs += 1;
+        }
+    }
+}
+
+/// Count paragraphs (blocks separated by blank lines) and the sentences inside them.
+/// Sentences never span a paragraph break, and their lengths are in tokens as the word
+/// tokenizer counts them with `options`.
+pub fn segment(text: &str, options: &TokenOptions) -> SegmentStats {
+    let mut stats = SegmentStats::default();
+    let mut paragraph_start = None;
+    let mut offset = 0;
+
+    for line in text.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            if let Some(start) = paragraph_start.take() {
+                stats.add_paragraph(&text[start..offset], options);
+            }
+        } else if paragraph_start.is_none() {
+            paragraph_start = Some(offset);
+        }
+        offset += line.len();
+    }
+    if let Some(start) = paragraph_start {
+        stats.add_paragraph(&text[start..], options);
+    }
+
+    stats
+}
+
+/// Split a paragraph after `.`, `!`, `?` or `…` (with any closing quotes) when the next word
+/// starts a new sentence: a capital, digit or opening quote. A period after a known
+/// abbreviation, an initial ("J. R. R.") or a dotted form ("U.S.", "e.g.") does not split.
+pub fn split_sentences(paragraph: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = paragraph.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        if !TERMINALS.contains(&c) {
+            continue;
+        }
+        let mut end = index + c.len_utf8();
+        while let Some(&(next_index, next)) = chars.peek() {
+            if !(TERMINALS.contains(&next) || CLOSERS.contains(&next)) {
+                break;
+            }
+            end = next_index + next.len_utf8();
+            chars.next();
+        }
+
+        let rest = &paragraph[end..];
+        let Some(next) = rest.trim_start().chars().next() else {
+            break; // End of paragraph
+        };
+        let starts_sentence = rest.starts_with(char::is_whitespace)
+            && (next.is_uppercase() || next.is_numeric() || OPENERS.contains(&next));
+        if !starts_sentence || (c == '.' && is_abbreviation(&paragraph[start..index])) {
+            continue;
+        }
+
+        sentences.push(paragraph[start..end].trim());
+        start = end;
+    }
+
+    let last = paragraph[start..].trim();
+    if !last.is_empty() {
+        sentences.push(last);
+    }
+    sentences
+}
+
+/// Whether the word ending `text` (just before a period) is an abbreviation or initial.
+fn is_abbreviation(text: &str) -> bool {
+    let word = text.rsplit(char::is_whitespace).next().unwrap_or("");
+    let word = word.trim_start_matches(OPENERS);
+    if word.contains('.') {
+        return true;
+    }
+    let mut letters = word.chars();
+    match (letters.next(), letters.next()) {
+        (Some(initial), None) => initial.is_uppercase(),
+        _ => ABBREVIATIONS.contains(&word.to_lowercase().as_str()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sentences_and_paragraphs() {
+        let paragraph = "Dr. Smith met J. R. R. Tolkien in the U.S. in 1950. \"Really?\" she asked. He paid 3.50 dollars, e.g. for tea!";
+        assert_eq!(
+            split_sentences(paragraph),
+            vec![
+                "Dr. Smith met J. R. R. Tolkien in the U.S. in 1950.",
+                "\"Really?\" she asked.",
+                "He paid 3.50 dollars, e.g. for tea!",
+            ]
+        );
+        assert_eq!(split_sentences("The cat sat. Then it ran"), vec!["The cat sat.", "Then it ran"]);
+
+        let stats = segment("One two three. Four five.\n\n\nSix seven\neight nine. 42.\n\n   \n", &TokenOptions::WORDS_ONLY);
+        assert_eq!(stats.paragraphs, 2);
+        assert_eq!(stats.sentences(), 3);
+        assert_eq!(stats.sentence_lengths, BTreeMap::from([(2, 1), (3, 1), (4, 1)]));
+        assert_eq!(stats.mean_length(), Some(3.0));
+        assert_eq!(stats.median_length(), Some(3.0));
+        assert_eq!(stats.histogram(2), vec![(1, 2, 1), (3, 4, 2)]);
+
+        let mut merged = stats.clone();
+        merged.merge(&segment("Ten eleven.", &TokenOptions::WORDS_ONLY));
+        assert_eq!((merged.paragraphs, merged.sentences(), merged.median_length()), (3, 4, Some(2.5)));
+        assert_eq!(SegmentStats::default().mean_length(), None);
+    }
+
+    #[test]
+    fn test_sentence_lengths_count_numbers_like_tokens() {
+        let text = "In 2024 we sold 3 cats. See https://example.com now.";
+        let options = TokenOptions::default();
+        let tokens: usize = text.lines().map(|line| extract_tokens(line, &options).len()).sum();
+        let stats = segment(text, &options);
+        assert_eq!(stats.sentence_lengths, BTreeMap::from([(2, 1), (6, 1)]));
+        assert_eq!(stats.sentence_lengths.iter().map(|(length, count)| length * count).sum::<usize>(), tokens);
+
+        let words_only = segment(text, &TokenOptions::WORDS_ONLY);
+        assert_eq!(words_only.sentence_lengths, BTreeMap::from([(2, 1), (4, 1)]));
+    }
+}
@@ -0,0 +1,310 @@
+use crate::analyzer::Dataset;
+use crate::language::Language;
+use crate::segment::SegmentStats;
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Identifies snapshot files; bump `SNAPSHOT_VERSION` on incompatible changes.
+pub const SNAPSHOT_SCHEMA: &str = "zipfr-snapshot";
+pub const SNAPSHOT_VERSION: u32 = 1;
+pub const SNAPSHOT_EXTENSION: &str = "zipfr";
+
+/// An analyzed dataset saved to disk so large corpora need not be re-parsed.
+/// Tags are not stored; they are re-applied from the current tag configuration on load.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub schema: String,
+    pub version: u32,
+    pub name: String,
+    pub source: SourceInfo,
+    pub settings: ParseSettings,
+    pub language: Option<Language>,
+    pub total_words: usize,
+    pub unique_words: usize,
+    pub parse_seconds: f64,
+    pub analyze_seconds: f64,
+    pub words: Vec<(String, usize)>, // (word, count) in rank order
+    #[serde(default)]
+    pub segments: Option<SegmentStats>,
+}
+
+/// The text file a snapshot was built from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceInfo {
+    pub path: String, // Relative to the snapshot's directory unless absolute
+    pub size: u64,
+    pub sha256: String,
+}
+
+/// Settings that change the counts, checked against the current run on load. Enums are stored
+/// by their option values (`--tokenizer code` as `code`). Settings missing from older
+/// snapshots take their defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ParseSettings {
+    pub ngram_size: usize,
+    pub unit: String,   // Counted unit (`word`, `char`, `charngram:2`, ...)
+    pub tokens: String, // Token classes counted, e.g. `numbers=keep`; empty when all are dropped, as before token classes
+    pub encoding: String,
+    pub lossy: bool,
+    pub format: String,
+    pub epub_chapters: bool,
+    pub skip_code: bool,
+    pub xml_elements: Vec<String>,
+    pub pages: Option<String>,
+    pub strip_headers: bool,
+    pub column: Option<String>,
+    pub field: Option<String>,
+    pub group_by: Option<String>,
+    pub date_field: Option<String>,
+    pub period: String,
+    pub segments: Option<usize>,
+    pub split_on: Option<String>,
+    pub tokenizer: String,
+    pub code_language: String,
+    pub skip_strings: bool,
+    pub skip_comments: bool,
+    pub whole_identifiers: bool,
+}
+
+impl Default for ParseSettings {
+    fn default() -> Self {
+        Self {
+            ngram_size: 1,
+            unit: "word".to_string(),
+            tokens: String::new(),
+            encoding: "auto".to_string(),
+            lossy: false,
+            format: "auto".to_string(),
+            epub_chapters: false,
+            skip_code: false,
+            xml_elements: Vec::new(),
+            pages: None,
+            strip_headers: false,
+            column: None,
+            field: None,
+            group_by: None,
+            date_field: None,
+            period: "month".to_string(),
+            segments: None,
+            split_on: None,
+            tokenizer: "words".to_string(),
+            code_language: "auto".to_string(),
+            skip_strings: false,
+            skip_comments: false,
+            whole_identifiers: false,
+        }
+    }
+}
+
+impl ParseSettings {
+    /// The first setting that differs from `current`, as (option, saved value, current value).
+    pub fn mismatch(&self, current: &Self) -> Option<(&'static str, String, String)> {
+        self.options()
+            .into_iter()
+            .zip(current.options())
+            .find(|((_, saved), (_, now))| saved != now)
+            .map(|((option, saved), (_, now))| (option, saved, now))
+    }
+
+    /// Every setting as (option, value), with "none" for unset ones.
+    fn options(&self) -> Vec<(&'static str, String)> {
+        let optional = |value: Option<String>| value.unwrap_or_else(|| "none".to_string());
+        vec![
+            ("n-gram size", self.ngram_size.to_string()),
+            ("--unit", self.unit.clone()),
+            ("token classes", optional(Some(self.tokens.clone()).filter(|tokens| !tokens.is_empty()))),
+            ("--encoding", self.encoding.clone()),
+            ("--lossy", self.lossy.to_string()),
+            ("--input-format", self.format.clone()),
+            ("--epub-chapters", self.epub_chapters.to_string()),
+            ("--skip-code", self.skip_code.to_string()),
+            ("--xml-element", optional(Some(self.xml_elements.join(",")).filter(|elements| !elements.is_empty()))),
+            ("--pages", optional(self.pages.clone())),
+            ("--strip-headers", self.strip_headers.to_string()),
+            ("--column", optional(self.column.clone())),
+            ("--field", optional(self.field.clone())),
+            ("--group-by", optional(self.group_by.clone())),
+            ("--date-field", optional(self.date_field.as_ref().map(|field| format!("{} by {}", field, self.period)))),
+            ("--segments", optional(self.segments.map(|segments| segments.to_string()))),
+            ("--split-on", optional(self.split_on.clone())),
+            ("--tokenizer", self.tokenizer.clone()),
+            ("--code-language", self.code_language.clone()),
+            ("--skip-strings", self.skip_strings.to_string()),
+            ("--skip-comments", self.skip_comments.to_string()),
+            ("--whole-identifiers", self.whole_identifiers.to_string()),
+        ]
+    }
+}
+
+/// Whether a snapshot still matches its source file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SourceStatus {
+    Fresh,
+    Changed(PathBuf), // Source exists but its contents differ; re-analyze from this path
+    Missing,          // Source is gone; the snapshot is all we have
+}
+
+impl Snapshot {
+    pub fn from_dataset(dataset: &Dataset, source_path: &str, settings: ParseSettings) -> Result<Self> {
+        let (size, sha256) = hash_file(Path::new(source_path))
+            .with_context(|| format!("Failed to hash {}", source_path))?;
+
+        // Snapshots are saved next to their source, so the file name is enough to find it again
+        let path = Path::new(source_path)
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(source_path)
+            .to_string();
+
+        Ok(Self {
+            schema: SNAPSHOT_SCHEMA.to_string(),
+            version: SNAPSHOT_VERSION,
+            name: dataset.name.clone(),
+            source: SourceInfo { path, size, sha256 },
+            settings,
+            language: dataset.language.clone(),
+            total_words: dataset.total_words,
+            unique_words: dataset.unique_words,
+            parse_seconds: dataset.parse_duration.as_secs_f64(),
+            analyze_seconds: dataset.analyze_duration.as_secs_f64(),
+            words: dataset.word_counts.iter().map(|wc| (wc.word.clone(), wc.count)).collect(),
+            segments: dataset.segments.clone(),
+        })
+    }
+
+    pub fn load(path: &str) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("Failed to open snapshot {}", path))?;
+        let snapshot: Self = serde_json::from_reader(BufReader::new(file))
+            .with_context(|| format!("{} is not a valid zipfr snapshot", path))?;
+
+        if snapshot.schema != SNAPSHOT_SCHEMA {
+            bail!("{} is not a zipfr snapshot (schema '{}')", path, snapshot.schema);
+        }
+        if snapshot.version != SNAPSHOT_VERSION {
+            bail!(
+                "Snapshot {} has version {}, but this zipfr reads version {}; re-create it with --save-snapshot",
+                path, snapshot.version, SNAPSHOT_VERSION
+            );
+        }
+
+        Ok(snapshot)
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        let file = File::create(path).with_context(|| format!("Failed to create snapshot {}", path))?;
+        let mut out = BufWriter::new(file);
+        serde_json::to_writer(&mut out, self)?;
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Compare the recorded source size and hash with the file on disk.
+    pub fn source_status(&self, snapshot_path: &str) -> Result<SourceStatus> {
+        let source = Path::new(snapshot_path)
+            .parent()
+            .unwrap_or(Path::new(""))
+            .join(&self.source.path);
+
+        if !source.exists() {
+            return Ok(SourceStatus::Missing);
+        }
+
+        let (size, sha256) = hash_file(&source)
+            .with_context(|| format!("Failed to hash {}", source.display()))?;
+        if size == self.source.size && sha256 == self.source.sha256 {
+            Ok(SourceStatus::Fresh)
+        } else {
+            Ok(SourceStatus::Changed(source))
+        }
+    }
+}
+
+/// Snapshot inputs are recognized by their `.zipfr` extension.
+pub fn is_snapshot_path(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case(SNAPSHOT_EXTENSION))
+}
+
+/// Where `--save-snapshot` writes the snapshot for a source file.
+pub fn snapshot_path_for(source_path: &str) -> String {
+    format!("{}.{}", source_path, SNAPSHOT_EXTENSION)
+}
+
+/// Size and hex SHA-256 of a file, read in chunks.
+fn hash_file(path: &Path) -> io::Result<(u64, String)> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut hasher = Sha256::new();
+    let mut buffer = [0u8; 64 * 1024];
+    let mut size = 0u64;
+
+    loop {
+        let read = reader.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+        size += read as u64;
+    }
+
+    let digest = hasher.finalize();
+    Ok((size, digest.iter().map(|byte| format!("{:02x}", byte)).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_round_trip_and_staleness() {
+        let dir = std::env::temp_dir().join(format!("zipfr-snapshot-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("corpus.txt");
+        std::fs::write(&source, "the cat the dog").unwrap();
+        let source = source.to_str().unwrap();
+
+        let dataset = Dataset::from_text("corpus", "the cat the dog");
+
+        let snapshot_path = snapshot_path_for(source);
+        assert!(is_snapshot_path(&snapshot_path));
+        Snapshot::from_dataset(&dataset, source, ParseSettings::default())
+            .unwrap()
+            .save(&snapshot_path)
+            .unwrap();
+
+        let snapshot = Snapshot::load(&snapshot_path).unwrap();
+        assert_eq!(snapshot.words[0], ("the".to_string(), 2));
+        assert_eq!(snapshot.source.path, "corpus.txt");
+        assert_eq!(snapshot.source_status(&snapshot_path).unwrap(), SourceStatus::Fresh);
+
+        std::fs::write(source, "the cat the dog and more").unwrap();
+        assert!(matches!(snapshot.source_status(&snapshot_path).unwrap(), SourceStatus::Changed(_)));
+
+        std::fs::remove_file(source).unwrap();
+        assert_eq!(snapshot.source_status(&snapshot_path).unwrap(), SourceStatus::Missing);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_settings_mismatch() {
+        let saved = ParseSettings { encoding: "latin1".to_string(), ..ParseSettings::default() };
+        assert_eq!(saved.mismatch(&saved.clone()), None);
+        assert_eq!(
+            saved.mismatch(&ParseSettings::default()),
+            Some(("--encoding", "latin1".to_string(), "auto".to_string()))
+        );
+
+        let grouped = ParseSettings { group_by: Some("author".to_string()), ..ParseSettings::default() };
+        assert_eq!(ParseSettings::default().mismatch(&grouped).unwrap().0, "--group-by");
+
+        // Snapshots from before a setting was recorded were made with its default
+        let old: ParseSettings = serde_json::from_str(r#"{"ngram_size": 1}"#).unwrap();
+        assert_eq!(old, ParseSettings::default());
+    }
+}
@@ -0,0 +1,241 @@
+use crate::analyzer::WordCount;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Least-squares fit of `log(count) = log(C) - s * log(rank)`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ZipfFit {
+    pub exponent: f64,  // s; classic Zipf is ~1.0
+    pub constant: f64,  // C, the predicted count at rank 1
+    pub r_squared: f64, // Goodness of fit in log-log space
+    pub points: usize,
+}
+
+impl ZipfFit {
+    /// Predicted count at `rank` under the fitted power law.
+    pub fn predict(&self, rank: usize) -> f64 {
+        self.constant / (rank as f64).powf(self.exponent)
+    }
+}
+
+/// Fit a power law to ranked word counts. Needs at least two distinct ranks.
+pub fn fit_zipf(word_counts: &[WordCount]) -> Option<ZipfFit> {
+    fit_ranked(word_counts.iter().map(|wc| (wc.rank, wc.count)))
+}
+
+/// Fit a power law to counts sorted in descending order, ranked 1, 2, 3, ...
+pub fn fit_counts(sorted_counts: &[usize]) -> Option<ZipfFit> {
+    fit_ranked(sorted_counts.iter().enumerate().map(|(index, &count)| (index + 1, count)))
+}
+
+fn fit_ranked(ranked: impl Iterator<Item = (usize, usize)>) -> Option<ZipfFit> {
+    let points: Vec<(f64, f64)> = ranked
+        .filter(|&(_, count)| count > 0)
+        .map(|(rank, count)| ((rank as f64).ln(), (count as f64).ln()))
+        .collect();
+
+    let n = points.len() as f64;
+    if points.len() < 2 {
+        return None;
+    }
+
+    let mean_x = points.iter().map(|(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|(_, y)| y).sum::<f64>() / n;
+    let sxx: f64 = points.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+    let sxy: f64 = points.iter().map(|(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let syy: f64 = points.iter().map(|(_, y)| (y - mean_y).powi(2)).sum();
+
+    if sxx == 0.0 {
+        return None;
+    }
+
+    let slope = sxy / sxx;
+    let intercept = mean_y - slope * mean_x;
+    // A perfectly flat distribution is explained entirely by the intercept
+    let r_squared = if syy == 0.0 { 1.0 } else { (sxy * sxy) / (sxx * syy) };
+
+    Some(ZipfFit {
+        exponent: -slope,
+        constant: intercept.exp(),
+        r_squared,
+        points: points.len(),
+    })
+}
+
+/// Vocabulary diversity of a word list. Type-token ratio and Yule's K depend on text length,
+/// so only compare them between texts of similar size.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diversity {
+    pub types: usize,
+    pub type_token_ratio: f64,
+    pub hapax_ratio: f64, // Share of types that occur once
+    pub entropy: f64,     // Shannon entropy of the word distribution, in bits
+    pub yules_k: f64,     // Repetitiveness: 10⁴ · (Σ count² − N) / N²; lower is more diverse
+}
+
+/// Diversity of word counts (in any order; zero counts are ignored).
+pub fn diversity(counts: impl IntoIterator<Item = usize>) -> Diversity {
+    let (mut types, mut hapaxes, mut tokens, mut squares, mut count_log_count) = (0usize, 0usize, 0.0, 0.0, 0.0);
+    for count in counts.into_iter().filter(|&count| count > 0) {
+        let count = count as f64;
+        types += 1;
+        hapaxes += usize::from(count == 1.0);
+        tokens += count;
+        squares += count * count;
+        count_log_count += count * count.log2();
+    }
+    if types == 0 {
+        return Diversity { types: 0, type_token_ratio: 0.0, hapax_ratio: 0.0, entropy: 0.0, yules_k: 0.0 };
+    }
+
+    Diversity {
+        types,
+        type_token_ratio: types as f64 / tokens,
+        hapax_ratio: hapaxes as f64 / types as f64,
+        // −Σ p·log₂ p with p = count / N
+        entropy: tokens.log2() - count_log_count / tokens,
+        yules_k: 10_000.0 * (squares - tokens) / (tokens * tokens),
+    }
+}
+
+/// Word-length distributions of a ranked word list, for testing Zipf's law of abbreviation
+/// (frequent words tend to be shorter). Lengths are in characters.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LengthStats {
+    pub by_type: BTreeMap<usize, usize>,  // Length → distinct words
+    pub by_token: BTreeMap<usize, usize>, // Length → occurrences
+    pub mean_type_length: Option<f64>,
+    pub mean_token_length: Option<f64>,
+    pub rank_correlation: Option<f64>, // Spearman's ρ of rank and length; positive when frequent words are shorter
+}
+
+pub fn word_lengths(word_counts: &[WordCount]) -> LengthStats {
+    let mut by_type = BTreeMap::new();
+    let mut by_token = BTreeMap::new();
+    let lengths: Vec<f64> = word_counts
+        .iter()
+        .map(|wc| {
+            let length = wc.word.chars().count();
+            *by_type.entry(length).or_default() += 1;
+            *by_token.entry(length).or_default() += wc.count;
+            length as f64
+        })
+        .collect();
+
+    let mean = |distribution: &BTreeMap<usize, usize>| {
+        let total: usize = distribution.values().sum();
+        let sum: usize = distribution.iter().map(|(length, n)| length * n).sum();
+        (total > 0).then(|| sum as f64 / total as f64)
+    };
+    // Ranking by descending count averages the ranks of tied words
+    let counts: Vec<f64> = word_counts.iter().map(|wc| -(wc.count as f64)).collect();
+
+    LengthStats {
+        mean_type_length: mean(&by_type),
+        mean_token_length: mean(&by_token),
+        rank_correlation: spearman(&counts, &lengths),
+        by_type,
+        by_token,
+    }
+}
+
+/// Spearman's rank correlation: Pearson's r of the values' ranks, with ties sharing their mean rank.
+/// None with fewer than two pairs or when either side is constant.
+pub fn spearman(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    pearson(&average_ranks(xs), &average_ranks(ys))
+}
+
+pub fn pearson(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    let n = xs.len().min(ys.len());
+    if n < 2 {
+        return None;
+    }
+    let mean_x = xs[..n].iter().sum::<f64>() / n as f64;
+    let mean_y = ys[..n].iter().sum::<f64>() / n as f64;
+    let (mut sxx, mut syy, mut sxy) = (0.0, 0.0, 0.0);
+    for (x, y) in xs.iter().zip(ys) {
+        sxx += (x - mean_x).powi(2);
+        syy += (y - mean_y).powi(2);
+        sxy += (x - mean_x) * (y - mean_y);
+    }
+    (sxx > 0.0 && syy > 0.0).then(|| sxy / (sxx * syy).sqrt())
+}
+
+/// 1-based ranks of `values` in ascending order; tied values get the mean of their ranks.
+fn average_ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        // Positions start..end hold ranks start + 1 ..= end
+        let rank = (start + 1 + end) as f64 / 2.0;
+        for &index in &order[start..end] {
+            ranks[index] = rank;
+        }
+        start = end;
+    }
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analyzer::ranked;
+
+    #[test]
+    fn test_fit_zipf_ideal() {
+        let counts: Vec<usize> = (1..=50).map(|rank| 10_000 / rank).collect();
+        let fit = fit_zipf(&ranked(&counts)).unwrap();
+
+        assert!((fit.exponent - 1.0).abs() < 0.01);
+        assert!((fit.constant - 10_000.0).abs() / 10_000.0 < 0.05);
+        assert!(fit.r_squared > 0.99);
+        assert_eq!(fit.points, 50);
+    }
+
+    #[test]
+    fn test_word_lengths() {
+        let mut words = ranked(&[10, 5, 5, 1]);
+        for (wc, word) in words.iter_mut().zip(["a", "to", "cat", "elephant"]) {
+            wc.word = word.to_string();
+        }
+        let stats = word_lengths(&words);
+
+        assert_eq!(stats.by_type, BTreeMap::from([(1, 1), (2, 1), (3, 1), (8, 1)]));
+        assert_eq!(stats.by_token, BTreeMap::from([(1, 10), (2, 5), (3, 5), (8, 1)]));
+        assert_eq!(stats.mean_type_length, Some(3.5));
+        assert_eq!(stats.mean_token_length, Some(43.0 / 21.0));
+        // Ranks 1, 2.5, 2.5, 4 against lengths 1, 2, 3, 4
+        assert!((stats.rank_correlation.unwrap() - 0.9487).abs() < 1e-3);
+
+        assert_eq!(word_lengths(&[]).mean_type_length, None);
+        assert_eq!(spearman(&[1.0, 1.0], &[1.0, 2.0]), None);
+    }
+
+    #[test]
+    fn test_diversity() {
+        let stats = diversity([4, 2, 1, 1, 0]);
+        assert_eq!(stats.types, 4);
+        assert_eq!(stats.type_token_ratio, 0.5);
+        assert_eq!(stats.hapax_ratio, 0.5);
+        // p = 1/2, 1/4, 1/8, 1/8
+        assert!((stats.entropy - 1.75).abs() < 1e-12);
+        assert!((stats.yules_k - 10_000.0 * (22.0 - 8.0) / 64.0).abs() < 1e-9);
+
+        assert_eq!(diversity([3, 3]).entropy, 1.0);
+        assert_eq!(diversity([]).types, 0);
+        assert_eq!(fit_counts(&[100, 50, 25]).unwrap().exponent, fit_zipf(&ranked(&[100, 50, 25])).unwrap().exponent);
+    }
+
+    #[test]
+    fn test_fit_zipf_too_few_points() {
+        assert!(fit_zipf(&ranked(&[5])).is_none());
+        assert!(fit_zipf(&[]).is_none());
+    }
+}
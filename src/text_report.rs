@@ -0,0 +1,337 @@
+use crate::analyzer::{Dataset, WordCount};
+use crate::bootstrap::Interval;
+use crate::combine::RATIO_SCALE;
+use crate::sample::{rarefaction, sample_sizes};
+use crate::segment::SegmentStats;
+use crate::stats::{diversity, fit_zipf, word_lengths, LengthStats};
+use std::collections::{BTreeMap, HashSet};
+use std::io::{self, Write};
+use std::time::Duration;
+
+/// The plain-text analysis report: per dataset its totals, sentence and word lengths,
+/// bootstrap statistics and top `top` words, with intervals when bootstrapped.
+pub fn write_multi_results(
+    out: &mut impl Write,
+    datasets: &[Dataset],
+    words: &[Vec<WordCount>],
+    top: usize,
+    total_duration: Duration,
+) -> io::Result<()> {
+    writeln!(out, "Zipfian Multi-Dataset Analysis Results")?;
+    writeln!(out, "=====================================")?;
+    writeln!(out, "Datasets analyzed: {}", datasets.len())?;
+    writeln!(out, "Total processing time: {:.2?}", total_duration)?;
+    writeln!(out)?;
+
+    for (i, dataset) in datasets.iter().enumerate() {
+        writeln!(out, "Dataset {}: {}", i + 1, dataset.name)?;
+        if dataset.is_ratio() {
+            // Scores are not counts, so only the ranking applies
+            writeln!(out, "  Ratio of relative frequencies (x{}); 100 = equally frequent", RATIO_SCALE)?;
+            writeln!(out, "  Unique words: {}", dataset.unique_words)?;
+            if let Some(unscored) = dataset.unscored_words.filter(|&unscored| unscored > 0) {
+                writeln!(out, "  Not listed: {} words found only in the divisor", unscored)?;
+            }
+            writeln!(out)?;
+            writeln!(out, "  {:>4} | {:20} | {:>8}", "Rank", "Word", "Ratio")?;
+            writeln!(out, "  {:->4}-+-{:->20}-+-{:->8}", "", "", "")?;
+            for word_count in words[i].iter().take(top) {
+                writeln!(out, "  {:>4} | {:20} | {:>8}", word_count.rank, word_count.word, word_count.count)?;
+            }
+            writeln!(out)?;
+            continue;
+        }
+        writeln!(out, "  Total words: {}", dataset.total_words)?;
+        if let Some(available) = dataset.sampled_from {
+            writeln!(out, "  Sampled: {} of {} tokens", dataset.total_words, available)?;
+        }
+        writeln!(out, "  Unique words: {}", dataset.unique_words)?;
+        if let Some(ref language) = dataset.language {
+            writeln!(out, "  Language: {} ({:.0}% confidence)", language.name, language.confidence * 100.0)?;
+        }
+        writeln!(out, "  Parse time: {:.2?}", dataset.parse_duration)?;
+        writeln!(out, "  Analysis time: {:.2?}", dataset.analyze_duration)?;
+        writeln!(out, "  Words per second: {:.0}", dataset.total_words as f64 / (dataset.parse_duration + dataset.analyze_duration).as_secs_f64())?;
+        if let Some(segments) = &dataset.segments {
+            write_sentence_lengths(out, segments)?;
+        }
+        write_word_lengths(out, &word_lengths(&words[i]))?;
+        write_diversity(out, dataset)?;
+        writeln!(out)?;
+
+        // With --bootstrap, each word's share of the dataset and rank among all its words, with intervals
+        let Some(intervals) = &dataset.bootstrap else {
+            writeln!(out, "  {:>4} | {:20} | {:>8}", "Rank", "Word", "Count")?;
+            writeln!(out, "  {:->4}-+-{:->20}-+-{:->8}", "", "", "")?;
+            for word_count in words[i].iter().take(top) {
+                writeln!(
+                    out,
+                    "  {:>4} | {:20} | {:>8}",
+                    word_count.rank, word_count.word, word_count.count
+                )?;
+            }
+            writeln!(out)?;
+            continue;
+        };
+        let level = format!("{}%", intervals.confidence * 100.0);
+        writeln!(out, "  {:>4} | {:20} | {:>8} | {:>7} | {:>18} | {:>12}", "Rank", "Word", "Count", "%", format!("{} interval", level), "Rank range")?;
+        writeln!(out, "  {:->4}-+-{:->20}-+-{:->8}-+-{:->7}-+-{:->18}-+-{:->12}", "", "", "", "", "", "")?;
+        for word_count in words[i].iter().take(top) {
+            let percentage = word_count.count as f64 / dataset.total_words.max(1) as f64 * 100.0;
+            let (percentage_range, rank_range) = match intervals.word(&word_count.word) {
+                Some(word) => (format_interval(&word.percentage, 3), format_interval(&word.rank, 0)),
+                None => (String::new(), String::new()),
+            };
+            writeln!(
+                out,
+                "  {:>4} | {:20} | {:>8} | {:>7.3} | {:>18} | {:>12}",
+                word_count.rank, word_count.word, word_count.count, percentage, percentage_range, rank_range
+            )?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// Sentence and paragraph counts, and the sentence-length distribution as a bar per length bin.
+fn write_sentence_lengths(out: &mut impl Write, segments: &SegmentStats) -> io::Result<()> {
+    const BINS: usize = 12;
+    const BAR_WIDTH: usize = 40;
+
+    writeln!(out, "  Paragraphs: {}", segments.paragraphs)?;
+    let (Some(mean), Some(median)) = (segments.mean_length(), segments.median_length()) else {
+        return writeln!(out, "  Sentences: 0");
+    };
+    writeln!(out, "  Sentences: {} (mean {:.1} words, median {})", segments.sentences(), mean, median)?;
+    writeln!(out, "  Sentence lengths (words):")?;
+
+    let bins = segments.histogram(BINS);
+    let most = bins.iter().map(|&(_, _, count)| count).max().unwrap_or(1).max(1);
+    for (first, last, count) in bins {
+        let label = if first == last { first.to_string() } else { format!("{}-{}", first, last) };
+        let bar = "█".repeat((count * BAR_WIDTH).div_ceil(most));
+        writeln!(out, "  {:>9} | {:>6} {}", label, count, bar)?;
+    }
+    Ok(())
+}
+
+/// Word-length distribution by type and token of the filtered words, and the rank–length correlation.
+fn write_word_lengths(out: &mut impl Write, lengths: &LengthStats) -> io::Result<()> {
+    const LONGEST_ROW: usize = 20; // Longer words share the last row
+
+    let (Some(per_type), Some(per_token)) = (lengths.mean_type_length, lengths.mean_token_length) else {
+        return Ok(());
+    };
+    writeln!(out, "  Word length: mean {:.2} characters per type, {:.2} per token", per_type, per_token)?;
+    if let Some(correlation) = lengths.rank_correlation {
+        writeln!(out, "  Rank–length correlation (Spearman): {:.3}", correlation)?;
+    }
+
+    writeln!(out, "  {:>6} | {:>8} | {:>9}", "Length", "Types", "Tokens")?;
+    let mut rows: BTreeMap<usize, (usize, usize)> = BTreeMap::new();
+    for (length, types) in &lengths.by_type {
+        let row = rows.entry((*length).min(LONGEST_ROW + 1)).or_default();
+        row.0 += types;
+        row.1 += lengths.by_token[length];
+    }
+    for (length, (types, tokens)) in rows {
+        let label = if length > LONGEST_ROW { format!(">{}", LONGEST_ROW) } else { length.to_string() };
+        writeln!(out, "  {:>6} | {:>8} | {:>9}", label, types, tokens)?;
+    }
+    Ok(())
+}
+
+/// Zipf exponent and vocabulary diversity of all the dataset's words with their bootstrap
+/// intervals; only for datasets resampled with --bootstrap.
+fn write_diversity(out: &mut impl Write, dataset: &Dataset) -> io::Result<()> {
+    let Some(intervals) = &dataset.bootstrap else {
+        return Ok(());
+    };
+    let stats = diversity(dataset.word_counts.iter().map(|wc| wc.count));
+    if stats.types == 0 {
+        return Ok(());
+    }
+    let range = |interval: &Interval, precision: usize| format!(" {}", format_interval(interval, precision));
+
+    let units = match intervals.chunk_size {
+        Some(size) => format!("{}-token chunks", size),
+        None => "tokens".to_string(),
+    };
+    writeln!(out, "  Bootstrap: {} resamples of {}, {}% intervals", intervals.samples, units, intervals.confidence * 100.0)?;
+    if let Some(fit) = fit_zipf(&dataset.word_counts) {
+        writeln!(out, "  Zipf exponent: {:.3}{}", fit.exponent, intervals.exponent.as_ref().map(|interval| range(interval, 3)).unwrap_or_default())?;
+    }
+    let ranges = &intervals.diversity;
+    writeln!(out, "  Types: {}{}", stats.types, range(&ranges.types, 0))?;
+    writeln!(out, "  Type-token ratio: {:.4}{}", stats.type_token_ratio, range(&ranges.type_token_ratio, 4))?;
+    writeln!(out, "  Hapax ratio: {:.3}{}", stats.hapax_ratio, range(&ranges.hapax_ratio, 3))?;
+    writeln!(out, "  Entropy: {:.3} bits{}", stats.entropy, range(&ranges.entropy, 3))?;
+    writeln!(out, "  Yule's K: {:.1}{}", stats.yules_k, range(&ranges.yules_k, 1))
+}
+
+/// An interval as `[low, high]` with `precision` decimals.
+pub fn format_interval(interval: &Interval, precision: usize) -> String {
+    format!("[{:.*}, {:.*}]", precision, interval.low, precision, interval.high)
+}
+
+/// Shared and unique vocabulary of the datasets, then their top `top` words side by side.
+pub fn write_comparison(out: &mut impl Write, datasets: &[Dataset], top: usize) -> io::Result<()> {
+    const COLUMN_WIDTH: usize = 24;
+
+    let vocabularies: Vec<HashSet<&str>> = datasets
+        .iter()
+        .map(|dataset| dataset.word_counts.iter().map(|wc| wc.word.as_str()).collect())
+        .collect();
+    let shared = vocabularies
+        .first()
+        .map(|first| {
+            first
+                .iter()
+                .filter(|word| vocabularies[1..].iter().all(|vocabulary| vocabulary.contains(*word)))
+                .count()
+        })
+        .unwrap_or(0);
+
+    writeln!(out, "Zipfian Dataset Comparison")?;
+    writeln!(out, "==========================")?;
+    writeln!(out, "Datasets compared: {}", datasets.len())?;
+    writeln!(out, "Shared vocabulary: {} words appear in every dataset", shared)?;
+    for (dataset, vocabulary) in datasets.iter().zip(&vocabularies) {
+        let unique = vocabulary
+            .iter()
+            .filter(|word| vocabularies.iter().filter(|other| other.contains(*word)).count() == 1)
+            .count();
+        let total = if dataset.is_ratio() { "ratio".to_string() } else { format!("{} total", dataset.total_words) };
+        writeln!(
+            out,
+            "  {}: {}, {} unique, {} only in this dataset",
+            dataset.name, total, dataset.unique_words, unique
+        )?;
+    }
+    writeln!(out)?;
+
+    write!(out, "{:>4}", "Rank")?;
+    for dataset in datasets {
+        let name: String = dataset.name.chars().take(COLUMN_WIDTH).collect();
+        write!(out, " | {:width$}", name, width = COLUMN_WIDTH)?;
+    }
+    writeln!(out)?;
+    write!(out, "{:->4}", "")?;
+    for _ in datasets {
+        write!(out, "-+-{:->width$}", "", width = COLUMN_WIDTH)?;
+    }
+    writeln!(out)?;
+
+    let rows = datasets.iter().map(|d| d.word_counts.len()).max().unwrap_or(0).min(top);
+    for row in 0..rows {
+        write!(out, "{:>4}", row + 1)?;
+        for dataset in datasets {
+            let cell = dataset
+                .word_counts
+                .get(row)
+                .map(|wc| format!("{} ({})", wc.word, wc.count))
+                .unwrap_or_default();
+            write!(out, " | {:width$}", cell, width = COLUMN_WIDTH)?;
+        }
+        writeln!(out)?;
+    }
+    writeln!(out)
+}
+
+/// Expected unique words of every dataset at common sample sizes and at each dataset's full size.
+/// Vocabularies only compare fairly at equal sizes, i.e. along a row.
+pub fn write_rarefaction(out: &mut impl Write, datasets: &[Dataset]) -> io::Result<()> {
+    const COLUMN_WIDTH: usize = 12;
+
+    let datasets: Vec<&Dataset> = datasets.iter().filter(|dataset| !dataset.is_ratio()).collect();
+    let largest = datasets.iter().map(|dataset| dataset.total_words).max().unwrap_or(0);
+    let mut sizes = sample_sizes(largest);
+    sizes.extend(datasets.iter().map(|dataset| dataset.total_words).filter(|&total| total > 0));
+    sizes.sort_unstable();
+    sizes.dedup();
+    if sizes.is_empty() {
+        return Ok(());
+    }
+    let curves: Vec<_> = datasets
+        .iter()
+        .map(|dataset| rarefaction(dataset.word_counts.iter().map(|wc| wc.count), &sizes))
+        .collect();
+
+    writeln!(out, "Rarefaction: expected unique words in a random sample")?;
+    write!(out, "  {:>9}", "Tokens")?;
+    for dataset in &datasets {
+        let name: String = dataset.name.chars().take(COLUMN_WIDTH).collect();
+        write!(out, " | {:>width$}", name, width = COLUMN_WIDTH)?;
+    }
+    writeln!(out)?;
+    write!(out, "  {:->9}", "")?;
+    for _ in &datasets {
+        write!(out, "-+-{:->width$}", "", width = COLUMN_WIDTH)?;
+    }
+    writeln!(out)?;
+
+    for (row, size) in sizes.iter().enumerate() {
+        write!(out, "  {:>9}", size)?;
+        for (dataset, curve) in datasets.iter().zip(&curves) {
+            // Larger samples than the dataset itself are left blank
+            let cell = if *size <= dataset.total_words { format!("{:.1}", curve[row].expected_types) } else { "-".to_string() };
+            write!(out, " | {:>width$}", cell, width = COLUMN_WIDTH)?;
+        }
+        writeln!(out)?;
+    }
+    writeln!(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combine::ratio_dataset;
+    use crate::segment::segment;
+    use crate::tokens::TokenOptions;
+
+    fn text(write: impl FnOnce(&mut Vec<u8>) -> io::Result<()>) -> String {
+        let mut out = Vec::new();
+        write(&mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_multi_results() {
+        let source = "The cat sat. The cat ran far away.";
+        let a = Dataset { segments: Some(segment(source, &TokenOptions::default())), ..Dataset::from_text("a", "the the the cat cat sat") };
+        let b = Dataset::from_text("b", "the cat dog");
+        let datasets = vec![ratio_dataset(&a, &b), a];
+        let words: Vec<Vec<WordCount>> = datasets.iter().map(|dataset| dataset.word_counts.clone()).collect();
+        let report = text(|out| write_multi_results(out, &datasets, &words, 2, Duration::ZERO));
+        let lines: Vec<&str> = report.lines().collect();
+
+        // Ratio datasets list scores by rank, without token totals
+        assert_eq!(lines[5], "Dataset 1: a / b");
+        assert_eq!(lines[8], "  Not listed: 1 words found only in the divisor");
+        assert_eq!(lines[12], "     1 | the                  |      150");
+        assert!(!lines[5..15].iter().any(|line| line.contains("Total words")));
+
+        assert_eq!(lines[15], "Dataset 2: a");
+        assert_eq!(lines[16], "  Total words: 6");
+        assert!(lines.contains(&"  Sentences: 2 (mean 4.0 words, median 4)"));
+        assert!(lines.contains(&"          5 |      1 ████████████████████████████████████████"));
+        assert!(lines.contains(&"       3 |        3 |         6"));
+        assert_eq!(lines[lines.len() - 3..], ["     1 | the                  |        3", "     2 | cat                  |        2", ""]);
+    }
+
+    #[test]
+    fn test_comparison_and_rarefaction() {
+        let datasets = vec![Dataset::from_text("a", "the the cat"), Dataset::from_text("b", "the dog")];
+        let comparison = text(|out| write_comparison(out, &datasets, 5));
+        assert!(comparison.contains("Shared vocabulary: 1 words appear in every dataset\n"));
+        assert!(comparison.contains("  a: 3 total, 2 unique, 1 only in this dataset\n"));
+        assert!(comparison.contains("   1 | the (2)                  | dog (1)                 \n"));
+
+        let rarefaction = text(|out| write_rarefaction(out, &datasets));
+        let lines: Vec<&str> = rarefaction.lines().collect();
+        assert_eq!(lines[1], "     Tokens |            a |            b");
+        // b has only two tokens, so it has no value at three
+        assert_eq!(lines[3], "          2 |          1.7 |          2.0");
+        assert_eq!(lines[4], "          3 |          2.0 |            -");
+    }
+}
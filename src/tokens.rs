@@ -0,0 +1,341 @@
+use clap::ValueEnum;
+use std::fmt;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Tokens that are not plain words. Each class can be kept, dropped or normalized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenClass {
+    Number,  // 2024, 3.14, 1,000, -5%
+    Url,     // https://…, www.…
+    Email,   // name@example.org
+    Mention, // @name
+    Hashtag, // #topic
+    Emoji,
+}
+
+impl TokenClass {
+    pub const ALL: [TokenClass; 6] = [
+        TokenClass::Number,
+        TokenClass::Url,
+        TokenClass::Email,
+        TokenClass::Mention,
+        TokenClass::Hashtag,
+        TokenClass::Emoji,
+    ];
+
+    /// What every token of the class is counted as when normalized.
+    pub fn placeholder(self) -> &'static str {
+        match self {
+            TokenClass::Number => "<NUM>",
+            TokenClass::Url => "<URL>",
+            TokenClass::Email => "<EMAIL>",
+            TokenClass::Mention => "<MENTION>",
+            TokenClass::Hashtag => "<HASHTAG>",
+            TokenClass::Emoji => "<EMOJI>",
+        }
+    }
+
+    /// Name of the built-in tag for kept tokens of the class.
+    pub fn tag_name(self) -> &'static str {
+        match self {
+            TokenClass::Number => "Numbers",
+            TokenClass::Url => "URLs",
+            TokenClass::Email => "Emails",
+            TokenClass::Mention => "Mentions",
+            TokenClass::Hashtag => "Hashtags",
+            TokenClass::Emoji => "Emoji",
+        }
+    }
+
+    pub fn tag_color(self) -> &'static str {
+        match self {
+            TokenClass::Number => "light blue",
+            TokenClass::Url => "blue",
+            TokenClass::Email => "light cyan",
+            TokenClass::Mention => "light green",
+            TokenClass::Hashtag => "light yellow",
+            TokenClass::Emoji => "light magenta",
+        }
+    }
+
+    fn option_name(self) -> &'static str {
+        match self {
+            TokenClass::Number => "numbers",
+            TokenClass::Url => "urls",
+            TokenClass::Email => "emails",
+            TokenClass::Mention => "mentions",
+            TokenClass::Hashtag => "hashtags",
+            TokenClass::Emoji => "emoji",
+        }
+    }
+}
+
+/// What happens to tokens of a class.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ClassAction {
+    #[default]
+    Drop,      // Not counted
+    Keep,      // Counted as written (lowercased, except URLs)
+    Normalize, // Counted as the class placeholder, e.g. `<NUM>`
+}
+
+/// Per-class actions for the word tokenizer. Numbers are kept by default, so `2024` and
+/// `covid19` are counted as written; every other class is dropped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenOptions {
+    pub numbers: ClassAction,
+    pub urls: ClassAction,
+    pub emails: ClassAction,
+    pub mentions: ClassAction,
+    pub hashtags: ClassAction,
+    pub emoji: ClassAction,
+}
+
+impl Default for TokenOptions {
+    fn default() -> Self {
+        Self { numbers: ClassAction::Keep, ..Self::WORDS_ONLY }
+    }
+}
+
+impl TokenOptions {
+    /// Every class dropped: plain words only, with digits stripped from them.
+    pub const WORDS_ONLY: Self = Self {
+        numbers: ClassAction::Drop,
+        urls: ClassAction::Drop,
+        emails: ClassAction::Drop,
+        mentions: ClassAction::Drop,
+        hashtags: ClassAction::Drop,
+        emoji: ClassAction::Drop,
+    };
+
+    pub fn action(&self, class: TokenClass) -> ClassAction {
+        match class {
+            TokenClass::Number => self.numbers,
+            TokenClass::Url => self.urls,
+            TokenClass::Email => self.emails,
+            TokenClass::Mention => self.mentions,
+            TokenClass::Hashtag => self.hashtags,
+            TokenClass::Emoji => self.emoji,
+        }
+    }
+
+    /// Classes whose tokens end up in the counts, kept or normalized.
+    pub fn counted_classes(&self) -> Vec<TokenClass> {
+        TokenClass::ALL.into_iter().filter(|&class| self.action(class) != ClassAction::Drop).collect()
+    }
+}
+
+/// Classes that are not dropped, e.g. `numbers=keep,urls=normalize`; empty when all are.
+impl fmt::Display for TokenOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let settings: Vec<String> = self
+            .counted_classes()
+            .into_iter()
+            .map(|class| {
+                let action = if self.action(class) == ClassAction::Keep { "keep" } else { "normalize" };
+                format!("{}={}", class.option_name(), action)
+            })
+            .collect();
+        write!(f, "{}", settings.join(","))
+    }
+}
+
+/// Leading and trailing characters that wrap a token without belonging to it.
+const LEADING: &[char] = &['(', '[', '{', '<', '"', '\'', '“', '‘', '«', '¿', '¡', '$', '€', '£', '¥'];
+const TRAILING: &[char] = &['.', ',', ';', ':', '!', '?', ')', ']', '}', '>', '"', '\'', '”', '’', '»', '…'];
+
+/// Words and class tokens of a line. Words keep only their letters, lowercased; digits inside
+/// words (covid19, mp3) are kept unless numbers are dropped.
+pub fn extract_tokens(text: &str, options: &TokenOptions) -> Vec<String> {
+    let mut tokens = Vec::new();
+    for chunk in text.split_whitespace() {
+        let trimmed = chunk.trim_start_matches(LEADING).trim_end_matches(TRAILING);
+        let class = if is_url(trimmed) {
+            Some(TokenClass::Url)
+        } else if is_email(trimmed) {
+            Some(TokenClass::Email)
+        } else if is_number(trimmed) {
+            Some(TokenClass::Number)
+        } else {
+            None
+        };
+        match class {
+            Some(class) => push_token(&mut tokens, class, trimmed, options),
+            None => extract_words(chunk, options, &mut tokens),
+        }
+    }
+    tokens
+}
+
+/// The class of a counted token or placeholder, for tagging. Plain words have none.
+pub fn classify(token: &str) -> Option<TokenClass> {
+    if let Some(class) = TokenClass::ALL.into_iter().find(|class| class.placeholder() == token) {
+        return Some(class);
+    }
+    if is_url(token) {
+        Some(TokenClass::Url)
+    } else if is_email(token) {
+        Some(TokenClass::Email)
+    } else if is_number(token) {
+        Some(TokenClass::Number)
+    } else if token.starts_with('@') && token.len() > 1 {
+        Some(TokenClass::Mention)
+    } else if token.starts_with('#') && token.len() > 1 {
+        Some(TokenClass::Hashtag)
+    } else if is_emoji(token) {
+        Some(TokenClass::Emoji)
+    } else {
+        None
+    }
+}
+
+fn push_token(tokens: &mut Vec<String>, class: TokenClass, token: &str, options: &TokenOptions) {
+    match options.action(class) {
+        ClassAction::Drop => {}
+        ClassAction::Normalize => tokens.push(class.placeholder().to_string()),
+        // URL paths are case-sensitive
+        ClassAction::Keep if class == TokenClass::Url => tokens.push(token.to_string()),
+        ClassAction::Keep => tokens.push(token.to_lowercase()),
+    }
+}
+
+/// Split a chunk into words, @mentions, #hashtags and emoji.
+fn extract_words(chunk: &str, options: &TokenOptions, tokens: &mut Vec<String>) {
+    let keep_digits = options.numbers != ClassAction::Drop;
+    let mut word = String::new();
+    let mut graphemes = chunk.graphemes(true).peekable();
+
+    let flush = |word: &mut String, tokens: &mut Vec<String>| {
+        if word.chars().any(char::is_alphabetic) {
+            tokens.push(word.to_lowercase());
+        } else if is_number(word) {
+            push_token(tokens, TokenClass::Number, word, options); // `#1`
+        }
+        word.clear();
+    };
+
+    while let Some(grapheme) = graphemes.next() {
+        if is_emoji(grapheme) {
+            flush(&mut word, tokens);
+            push_token(tokens, TokenClass::Emoji, grapheme, options);
+            continue;
+        }
+
+        // A sigil at the start of a word begins a mention or hashtag
+        let sigil = match grapheme {
+            "@" => Some(TokenClass::Mention),
+            "#" => Some(TokenClass::Hashtag),
+            _ => None,
+        };
+        if let Some(class) = sigil.filter(|_| word.is_empty()) {
+            let mut name = String::new();
+            while let Some(next) = graphemes.peek().filter(|next| next.chars().all(|c| c.is_alphanumeric() || c == '_')) {
+                name.push_str(next);
+                graphemes.next();
+            }
+            if name.chars().any(char::is_alphabetic) {
+                push_token(tokens, class, &format!("{}{}", grapheme, name), options);
+            } else {
+                word.push_str(&name);
+            }
+            continue;
+        }
+
+        let c = grapheme.chars().next().unwrap_or(' ');
+        if c.is_alphabetic() || (keep_digits && c.is_numeric()) {
+            word.push_str(grapheme);
+        }
+    }
+    flush(&mut word, tokens);
+}
+
+fn is_url(token: &str) -> bool {
+    let lower = token.to_ascii_lowercase();
+    let rest = ["https://", "http://", "ftp://", "www."].iter().find_map(|prefix| lower.strip_prefix(prefix));
+    rest.is_some_and(|rest| rest.contains(|c: char| c.is_alphanumeric()))
+}
+
+fn is_email(token: &str) -> bool {
+    let Some((local, domain)) = token.split_once('@') else {
+        return false;
+    };
+    let valid = |part: &str, extra: &[char]| !part.is_empty() && part.chars().all(|c| c.is_alphanumeric() || extra.contains(&c));
+    valid(local, &['.', '_', '%', '+', '-'])
+        && valid(domain, &['.', '-'])
+        && domain.split('.').count() > 1
+        && domain.split('.').all(|label| !label.is_empty())
+}
+
+/// Digits with optional sign, separators between digit groups (`1,000.50`, `12:30`, `2024-05-01`)
+/// and a trailing percent sign.
+fn is_number(token: &str) -> bool {
+    let body = token.strip_prefix(['+', '-', '−']).unwrap_or(token);
+    let body = body.strip_suffix('%').unwrap_or(body);
+    let mut previous_digit = false;
+    for c in body.chars() {
+        if c.is_ascii_digit() || c.is_numeric() {
+            previous_digit = true;
+        } else if previous_digit && matches!(c, '.' | ',' | ':' | '/' | '-') {
+            previous_digit = false;
+        } else {
+            return false;
+        }
+    }
+    previous_digit
+}
+
+/// Pictographic emoji, flags and keycaps (an approximation of Unicode's Extended_Pictographic).
+fn is_emoji(grapheme: &str) -> bool {
+    let mut chars = grapheme.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    let pictographic = matches!(first as u32,
+        0x1F000..=0x1FAFF | 0x2600..=0x27BF | 0x2B00..=0x2BFF | 0x2300..=0x23FF | 0x3030 | 0x303D | 0x3297 | 0x3299);
+    // Keycaps: a digit, # or * followed by U+20E3
+    pictographic || grapheme.contains('\u{20E3}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_classes() {
+        let text = "In 2024, covid19 hit 3.5% (see https://Example.org/A.) — mail bob@example.com, @Alice #Rust2024 🎉great!";
+        assert_eq!(extract_tokens(text, &TokenOptions::WORDS_ONLY), vec!["in", "covid", "hit", "see", "mail", "great"]);
+        assert_eq!(
+            extract_tokens(text, &TokenOptions::default()),
+            vec!["in", "2024", "covid19", "hit", "3.5%", "see", "mail", "great"]
+        );
+
+        let keep = TokenOptions {
+            numbers: ClassAction::Keep,
+            urls: ClassAction::Keep,
+            emails: ClassAction::Keep,
+            mentions: ClassAction::Keep,
+            hashtags: ClassAction::Keep,
+            emoji: ClassAction::Keep,
+        };
+        assert_eq!(
+            extract_tokens(text, &keep),
+            vec!["in", "2024", "covid19", "hit", "3.5%", "see", "https://Example.org/A", "mail", "bob@example.com", "@alice", "#rust2024", "🎉", "great"]
+        );
+
+        let normalize = TokenOptions { numbers: ClassAction::Normalize, urls: ClassAction::Normalize, ..TokenOptions::WORDS_ONLY };
+        assert_eq!(
+            extract_tokens(text, &normalize),
+            vec!["in", "<NUM>", "covid19", "hit", "<NUM>", "see", "<URL>", "mail", "great"]
+        );
+        assert_eq!(normalize.to_string(), "numbers=normalize,urls=normalize");
+        assert_eq!(TokenOptions::default().to_string(), "numbers=keep");
+        assert_eq!(TokenOptions::WORDS_ONLY.to_string(), "");
+
+        assert_eq!(classify("<NUM>"), Some(TokenClass::Number));
+        assert_eq!(classify("#rust2024"), Some(TokenClass::Hashtag));
+        assert_eq!(classify("1,000"), Some(TokenClass::Number));
+        assert_eq!(classify("🎉"), Some(TokenClass::Emoji));
+        assert_eq!(classify("word"), None);
+        assert!(!is_number("1,") && !is_number("-") && !is_email("@alice"));
+    }
+}
@@ -1,124 +1,36 @@
-use crate::analyzer::{WordCount, Tag, Dataset};
+use crate::analyzer::{WordCount, Tag, TagMatcher, Dataset, STOPWORDS_TAG_NAME};
+use crate::combine::{difference_dataset, merge_datasets, ratio_dataset};
+pub use crate::filter::{CrossDatasetFilter, FilterSet};
+use crate::export::{write_export, ExportContext, ExportFormat, ExportView};
+use crate::filter::{filter_words, CrossDatasetWords};
+use crate::plot::{write_chart, ChartFormat, ChartOptions, ChartSeries};
+use crate::sample::{rarefaction, sample_sizes};
+use crate::stats::fit_zipf;
+use crate::tui::chart::SeriesLine;
 use crate::tui::ChartWidget;
+use crate::view::{ChartScope, NormalizationMode, ZipfBasis, ZipfReference, ZipfState};
 use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
-use std::collections::HashSet;
-
-#[derive(Debug, Clone, PartialEq)]
-pub struct ZipfState {
-    pub enabled: bool,
-    pub basis: ZipfBasis,      // Filtered vs Unfiltered (persistent across scope changes)
-    pub reference: ZipfReference, // Absolute vs Relative (scope-dependent)
-}
-
-#[derive(Debug, Clone, PartialEq)]
-pub enum ZipfBasis {
-    Filtered,    // Use filtered dataset
-    Unfiltered,  // Use original dataset
-}
-
-#[derive(Debug, Clone, PartialEq)]
-pub enum ZipfReference {
-    Absolute,    // Global reference point
-    Relative,    // Local/visible reference point
-}
-
-impl ZipfState {
-    pub fn new() -> Self {
-        Self {
-            enabled: false,
-            basis: ZipfBasis::Unfiltered, // Default to unfiltered
-            reference: ZipfReference::Absolute, // Default to absolute
-        }
-    }
-    
 
+/// What the chart panel shows; `V` cycles through the views.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ChartView {
+    Zipf,            // Rank / frequency of the word list
+    WordLengths,     // Word length / log frequency of the word list
+    SentenceLengths, // Sentence-length distribution of the dataset
+    Series,          // Selected and pinned words, and the Zipf exponent, across all datasets
+    Rarefaction,     // Expected unique words by sample size, for every dataset
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum ChartScope {
-    Relative,  // Show only visible list range
-    Absolute,  // Show entire dataset
-}
-
-#[derive(Debug, Clone, PartialEq)]
-pub enum CrossDatasetFilter {
-    None,        // No cross-dataset filtering
-    CommonOnly,  // Show only words that appear in ALL datasets
-    UniqueOnly,  // Show only words unique to each dataset
-}
-
-#[derive(Debug, Clone, PartialEq)]
-pub struct FilterSet {
-    pub exclude_tags: Vec<Tag>,
-    pub include_only_tags: Vec<Tag>,  // OR logic - match ANY of these
-    pub exclude_single: bool,
-    pub cross_dataset: CrossDatasetFilter,
-}
-
-impl FilterSet {
-    fn new() -> Self {
-        Self {
-            exclude_tags: Vec::new(),
-            include_only_tags: Vec::new(),
-            exclude_single: false,
-            cross_dataset: CrossDatasetFilter::None,
-        }
-    }
-    
-    fn is_empty(&self) -> bool {
-        self.exclude_tags.is_empty() && 
-        self.include_only_tags.is_empty() && 
-        !self.exclude_single &&
-        matches!(self.cross_dataset, CrossDatasetFilter::None)
-    }
-    
-    fn matches(&self, word_count: &WordCount) -> bool {
-        // 1. Exclude singles check
-        if self.exclude_single && word_count.count == 1 {
-            return false;
-        }
-        
-        // 2. Exclude tags check (exclude if word has ANY excluded tag)
-        if self.exclude_tags.iter().any(|tag| word_count.tags.contains(tag)) {
-            return false;
-        }
-        
-        // 3. Include only tags check (OR logic - include if word has ANY include tag, or if no include filters)
-        if !self.include_only_tags.is_empty() {
-            return self.include_only_tags.iter().any(|tag| word_count.tags.contains(tag));
-        }
-        
-        // Note: Cross-dataset filtering is handled separately in apply_current_filter_to_all_datasets
-        // because it requires knowledge of which dataset we're filtering
-        
-        true
-    }
-    
-    // Conflict prevention methods
-    fn add_exclude_tag(&mut self, tag: Tag) {
-        // Remove from include list if present (prevent conflicts)
-        self.include_only_tags.retain(|t| t != &tag);
-        // Add to exclude list if not already present
-        if !self.exclude_tags.contains(&tag) {
-            self.exclude_tags.push(tag);
+impl ChartView {
+    fn next(self) -> Self {
+        match self {
+            ChartView::Zipf => ChartView::WordLengths,
+            ChartView::WordLengths => ChartView::SentenceLengths,
+            ChartView::SentenceLengths => ChartView::Series,
+            ChartView::Series => ChartView::Rarefaction,
+            ChartView::Rarefaction => ChartView::Zipf,
         }
     }
-    
-    fn add_include_tag(&mut self, tag: Tag) {
-        // Remove from exclude list if present (prevent conflicts)
-        self.exclude_tags.retain(|t| t != &tag);
-        // Add to include list if not already present
-        if !self.include_only_tags.contains(&tag) {
-            self.include_only_tags.push(tag);
-        }
-    }
-    
-    
-    fn clear(&mut self) {
-        self.exclude_tags.clear();
-        self.include_only_tags.clear();
-        self.exclude_single = false;
-    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -133,13 +45,9 @@ pub enum InputMode {
     Search,
     NumberInput,
     Filter,
+    Command, // `:` command line (export)
 }
 
-#[derive(Debug, Clone, PartialEq)]
-pub enum NormalizationMode {
-    Raw,        // Show raw counts (default)
-    Percentage, // Show as percentage of total words
-}
 use ratatui::{
     backend::Backend,
     layout::{Constraint, Direction, Layout, Rect},
@@ -148,7 +56,8 @@ use ratatui::{
     widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
     Frame, Terminal,
 };
-use std::io;
+use std::collections::HashMap;
+use std::io::{self, Write};
 use std::time::Duration;
 
 pub struct App {
@@ -171,8 +80,10 @@ pub struct App {
     pub number_input: String,
     pub list_state: ListState, // Active dataset's list state (reference to per_dataset_list_states)
     pub log_scale: bool,
+    pub whiskers: bool, // Draw bootstrap intervals in the Zipf chart and `:chart` exports
     pub zipf_state: ZipfState,
     pub chart_scope: ChartScope,
+    pub chart_view: ChartView,
     pub normalization_mode: NormalizationMode,
     // Global filter state that applies to all datasets
     pub filter_set: FilterSet,
@@ -185,9 +96,22 @@ pub struct App {
     pub search_results: Vec<usize>,
     pub current_search_index: usize,
     // Cross-dataset word sets for efficient filtering
-    pub common_words: HashSet<String>,      // Words that appear in ALL datasets
-    pub unique_words_per_dataset: Vec<HashSet<String>>, // Words unique to each dataset
+    pub cross_dataset_words: CrossDatasetWords,
     pub cross_dataset_cache_dirty: bool,
+    // Command line state (`:w file.csv`) and the result of the last command
+    pub command_input: String,
+    pub status_message: Option<String>,
+    // Datasets marked with `m` for merge/difference/ratio, in marking order
+    pub marked_datasets: Vec<usize>,
+    pub tag_matcher: Option<TagMatcher>, // Tags words of datasets merged with `:merge`
+    // Series view: words pinned with `p`, fitted Zipf exponents of the filtered words, and
+    // word → count lookups by dataset index, built the first time a dataset is plotted
+    // (datasets are only ever appended, so an index always names the same dataset)
+    pub pinned_words: Vec<String>,
+    pub per_dataset_exponents: Vec<Option<f64>>,
+    pub series_index: HashMap<usize, HashMap<String, usize>>,
+    // Rarefaction view: (name, points) per dataset, built the first time the view is shown
+    pub rarefaction_curves: Vec<(String, Vec<(f64, f64)>)>,
 }
 
 impl App {
@@ -238,6 +162,8 @@ impl App {
                 }
             }
         }
+        // Stable order (by indicator letter) for the filter menu and legend
+        available_tags.sort_by_key(|tag| tag.letter);
 
         let chart_mode = datasets.len() == 1; // Default to chart mode for single dataset
         
@@ -261,8 +187,10 @@ impl App {
             number_input: String::new(),
             list_state,
             log_scale: false,
+            whiskers: false,
             zipf_state: ZipfState::new(),
             chart_scope: ChartScope::Relative,
+            chart_view: ChartView::Zipf,
             normalization_mode: NormalizationMode::Raw,
             filter_set: FilterSet::new(),
             filter_dirty: false,
@@ -272,9 +200,16 @@ impl App {
             search_query: String::new(),
             search_results: Vec::new(),
             current_search_index: 0,
-            common_words: HashSet::new(),
-            unique_words_per_dataset: Vec::new(),
+            cross_dataset_words: CrossDatasetWords::default(),
             cross_dataset_cache_dirty: true,
+            command_input: String::new(),
+            status_message: None,
+            marked_datasets: Vec::new(),
+            tag_matcher: None,
+            pinned_words: Vec::new(),
+            per_dataset_exponents: Vec::new(),
+            series_index: HashMap::new(),
+            rarefaction_curves: Vec::new(),
         };
         
         // Initialize all datasets with no filter (synchronized state)
@@ -283,6 +218,20 @@ impl App {
         app
     }
 
+    /// Start with `filter_set` applied (e.g. from command-line filter options).
+    pub fn with_filter_set(mut self, filter_set: FilterSet) -> Self {
+        self.filter_set = filter_set;
+        self.cross_dataset_cache_dirty = true;
+        self.apply_current_filter_to_all_datasets();
+        self
+    }
+
+    /// Tag the words of merged datasets with `tag_matcher`.
+    pub fn with_tag_matcher(mut self, tag_matcher: Option<TagMatcher>) -> Self {
+        self.tag_matcher = tag_matcher;
+        self
+    }
+
     fn update_selection(&mut self, new_index: usize) {
         self.selected_index = new_index;
         self.list_state.select(Some(new_index));
@@ -425,18 +374,30 @@ impl App {
     }
 
     fn calculate_zipf_fit(&self, word_count: &WordCount, visible_words: &[WordCount]) -> Option<f64> {
-        if !self.zipf_state.enabled {
+        if self.datasets[self.active_dataset_index].is_ratio() {
             return None;
         }
-        
         // Choose reference dataset based on basis
         let reference_words = match self.zipf_state.basis {
             ZipfBasis::Filtered => &self.filtered_word_counts,
             ZipfBasis::Unfiltered => &self.word_counts,
         };
+
+        Self::zipf_fit_ratio(&self.zipf_state, word_count, reference_words, visible_words)
+    }
+
+    fn zipf_fit_ratio(
+        zipf_state: &ZipfState,
+        word_count: &WordCount,
+        reference_words: &[WordCount],
+        visible_words: &[WordCount],
+    ) -> Option<f64> {
+        if !zipf_state.enabled {
+            return None;
+        }
         
         // Calculate fit based on reference type
-        match &self.zipf_state.reference {
+        match &zipf_state.reference {
             ZipfReference::Absolute => {
                 // Compare to global Zipf from reference dataset
                 if let Some(global_first) = reference_words.first() {
@@ -466,10 +427,10 @@ impl App {
 
     fn deviation_to_color(ratio: f64) -> Color {
         match ratio {
-            r if r >= 0.9 && r <= 1.1 => Color::Green,      // Perfect fit (±10%)
-            r if r >= 0.7 && r < 0.9 => Color::Yellow,       // Good fit (underperforming)
+            r if (0.9..=1.1).contains(&r) => Color::Green,   // Perfect fit (±10%)
+            r if (0.7..0.9).contains(&r) => Color::Yellow,    // Good fit (underperforming)
             r if r > 1.1 && r <= 1.3 => Color::Yellow,       // Good fit (overperforming)
-            r if r >= 0.5 && r < 0.7 => Color::Cyan,         // Moderate underperforming
+            r if (0.5..0.7).contains(&r) => Color::Cyan,      // Moderate underperforming
             r if r > 1.3 && r <= 2.0 => Color::Magenta,      // Moderate overperforming
             r if r < 0.5 => Color::Blue,                     // Extreme underperforming
             r if r > 2.0 => Color::Red,                      // Extreme overperforming
@@ -483,72 +444,24 @@ impl App {
             return; // Cache is still valid
         }
 
-        // Only compute if we have multiple datasets
-        if self.datasets.len() <= 1 {
-            self.common_words.clear();
-            self.unique_words_per_dataset.clear();
-            self.cross_dataset_cache_dirty = false;
-            return;
-        }
-
-        // Collect word sets for each dataset
-        let mut dataset_word_sets: Vec<HashSet<String>> = Vec::new();
-        for dataset in &self.datasets {
-            let word_set: HashSet<String> = dataset.word_counts
-                .iter()
-                .map(|wc| wc.word.clone())
-                .collect();
-            dataset_word_sets.push(word_set);
-        }
-
-        // Compute common words (intersection of all datasets)
-        self.common_words = dataset_word_sets[0].clone();
-        for word_set in &dataset_word_sets[1..] {
-            self.common_words = self.common_words.intersection(word_set).cloned().collect();
-        }
-
-        // Compute unique words for each dataset
-        self.unique_words_per_dataset.clear();
-        for (i, dataset_words) in dataset_word_sets.iter().enumerate() {
-            let mut unique_words = dataset_words.clone();
-            
-            // Remove words that appear in any other dataset
-            for (j, other_words) in dataset_word_sets.iter().enumerate() {
-                if i != j {
-                    unique_words = unique_words.difference(other_words).cloned().collect();
-                }
-            }
-            
-            self.unique_words_per_dataset.push(unique_words);
-        }
-
+        self.cross_dataset_words = CrossDatasetWords::compute(&self.datasets);
         self.cross_dataset_cache_dirty = false;
     }
 
-    fn should_include_word_cross_dataset(&self, word: &str, dataset_index: usize) -> bool {
-        match &self.filter_set.cross_dataset {
-            CrossDatasetFilter::None => true,
-            CrossDatasetFilter::CommonOnly => self.common_words.contains(word),
-            CrossDatasetFilter::UniqueOnly => {
-                if dataset_index < self.unique_words_per_dataset.len() {
-                    self.unique_words_per_dataset[dataset_index].contains(word)
-                } else {
-                    false
-                }
-            }
-        }
-    }
-
     pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> io::Result<()> {
         loop {
             terminal.draw(|f| self.ui(f))?;
 
             if let Event::Key(key) = event::read()? {
                 if key.kind == KeyEventKind::Press {
+                    // Status messages stay up until the next key press
+                    self.status_message = None;
+
                     match self.input_mode {
                         InputMode::Search => self.handle_search_input(key),
                         InputMode::NumberInput => self.handle_number_input(key),
                         InputMode::Filter => self.handle_filter_input(key),
+                        InputMode::Command => self.handle_command_input(key),
                         InputMode::Normal => self.handle_normal_input(key),
                     }
                     
@@ -587,6 +500,345 @@ impl App {
         }
     }
 
+    fn handle_command_input(&mut self, key: crossterm::event::KeyEvent) {
+        match key.code {
+            KeyCode::Enter => {
+                let command = std::mem::take(&mut self.command_input);
+                self.input_mode = InputMode::Normal;
+                self.execute_command(command.trim());
+            }
+            KeyCode::Esc => {
+                self.command_input.clear();
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Backspace if self.command_input.is_empty() => {
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Backspace => {
+                self.command_input.pop();
+            }
+            KeyCode::Char(c) => {
+                self.command_input.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    fn execute_command(&mut self, command: &str) {
+        let (name, argument) = match command.split_once(char::is_whitespace) {
+            Some((name, argument)) => (name, argument.trim()),
+            None => (command, ""),
+        };
+
+        match name {
+            "" => {}
+            "q" | "quit" => self.should_quit = true,
+            "w" | "wa" if argument.is_empty() => {
+                self.status_message = Some(format!("Usage: :{} <file.csv|.tsv|.json|.ndjson|.md|.markdown|.tex|.latex>", name));
+            }
+            "w" | "wa" => {
+                let all_datasets = name == "wa";
+                self.status_message = Some(match self.export_view(argument, all_datasets) {
+                    Ok(rows) => format!("Exported {} words to {}", rows, argument),
+                    Err(e) => format!("Export failed: {:#}", e),
+                });
+            }
+            "chart" if argument.is_empty() => {
+                self.status_message = Some("Usage: :chart <file.svg|.png>".to_string());
+            }
+            "chart" => {
+                self.status_message = Some(match self.export_chart(argument) {
+                    Ok(()) => format!("Chart written to {}", argument),
+                    Err(e) => format!("Chart export failed: {:#}", e),
+                });
+            }
+            "merge" => self.merge_marked_datasets(argument),
+            "diff" | "ratio" => self.derive_from_marked_datasets(name == "ratio"),
+            _ => self.status_message = Some(format!("Unknown command: {}", name)),
+        }
+    }
+
+    fn toggle_dataset_mark(&mut self) {
+        let index = self.active_dataset_index;
+        if let Some(position) = self.marked_datasets.iter().position(|&i| i == index) {
+            self.marked_datasets.remove(position);
+        } else {
+            self.marked_datasets.push(index);
+        }
+    }
+
+    /// Sum the marked datasets into a new virtual dataset (named `name`, or the joined names).
+    fn merge_marked_datasets(&mut self, name: &str) {
+        if self.marked_datasets.len() < 2 {
+            self.status_message = Some("Mark at least two datasets with 'm' to merge them".to_string());
+            return;
+        }
+        if self.reject_marked_ratio() {
+            return;
+        }
+
+        let sources: Vec<&Dataset> = self.marked_datasets.iter().map(|&i| &self.datasets[i]).collect();
+        let name = if name.is_empty() {
+            sources.iter().map(|d| d.name.as_str()).collect::<Vec<_>>().join("+")
+        } else {
+            name.to_string()
+        };
+        let merged = merge_datasets(&name, &sources, self.tag_matcher.as_ref());
+        self.add_dataset(merged);
+    }
+
+    /// Difference or ratio of the first two marked datasets (first marked minus/over second).
+    fn derive_from_marked_datasets(&mut self, ratio: bool) {
+        if self.marked_datasets.len() != 2 {
+            self.status_message = Some("Mark exactly two datasets with 'm' (first minus/over second)".to_string());
+            return;
+        }
+        if self.reject_marked_ratio() {
+            return;
+        }
+
+        let (a, b) = (&self.datasets[self.marked_datasets[0]], &self.datasets[self.marked_datasets[1]]);
+        let derived = if ratio { ratio_dataset(a, b) } else { difference_dataset(a, b) };
+        self.add_dataset(derived);
+    }
+
+    /// Ratio scores are not counts, so ratio datasets cannot be merged, subtracted or divided.
+    /// Returns whether a marked dataset is one, with a status message naming it.
+    fn reject_marked_ratio(&mut self) -> bool {
+        let Some(dataset) = self.marked_datasets.iter().map(|&i| &self.datasets[i]).find(|dataset| dataset.is_ratio()) else {
+            return false;
+        };
+        self.status_message = Some(format!("'{}' is a ratio dataset: its scores cannot be combined as counts", dataset.name));
+        true
+    }
+
+    /// Switch bootstrap whiskers in the Zipf chart and chart exports on or off; datasets need
+    /// --bootstrap intervals.
+    fn toggle_whiskers(&mut self) {
+        if !self.datasets.iter().any(|dataset| dataset.bootstrap.is_some()) {
+            self.status_message = Some("No bootstrap intervals to draw: run with --bootstrap N".to_string());
+            return;
+        }
+        self.whiskers = !self.whiskers;
+        let state = if self.whiskers { "on" } else { "off" };
+        self.status_message = Some(format!("Whiskers in the chart and chart exports {}", state));
+    }
+
+    /// Pin the selected word to the series view, or unpin it.
+    fn toggle_pinned_word(&mut self) {
+        let Some(word) = self.filtered_word_counts.get(self.selected_index).map(|wc| wc.word.clone()) else {
+            return;
+        };
+        let message = match self.pinned_words.iter().position(|pinned| *pinned == word) {
+            Some(index) => {
+                self.pinned_words.remove(index);
+                format!("Unpinned '{}'", word)
+            }
+            None => {
+                self.pinned_words.push(word.clone());
+                format!("Pinned '{}' ({} pinned)", word, self.pinned_words.len())
+            }
+        };
+        self.status_message = Some(message);
+    }
+
+    /// Datasets in the active dataset's series, in load order; empty when it is not part of one.
+    /// Merged, difference and ratio datasets never belong to a series.
+    fn series_members(&self) -> Vec<usize> {
+        let Some(series) = self.datasets[self.active_dataset_index].series.as_ref() else {
+            return Vec::new();
+        };
+        (0..self.datasets.len()).filter(|&i| self.datasets[i].series.as_ref() == Some(series)).collect()
+    }
+
+    /// Relative frequency (% of each dataset's words) of the selected and pinned words in
+    /// every member of the series, in order.
+    fn series_lines(&mut self, members: &[usize]) -> Vec<SeriesLine> {
+        for &i in members {
+            self.series_index
+                .entry(i)
+                .or_insert_with(|| self.datasets[i].word_counts.iter().map(|wc| (wc.word.clone(), wc.count)).collect());
+        }
+
+        let selected = self.filtered_word_counts.get(self.selected_index).map(|wc| wc.word.clone());
+        let mut words: Vec<String> = selected.into_iter().collect();
+        words.extend(self.pinned_words.iter().filter(|word| !words.contains(word)).cloned().collect::<Vec<_>>());
+
+        words
+            .into_iter()
+            .map(|word| {
+                let points = members
+                    .iter()
+                    .enumerate()
+                    .map(|(position, &i)| {
+                        let count = self.series_index[&i].get(&word).copied().unwrap_or(0);
+                        ((position + 1) as f64, self.datasets[i].percentage(count).unwrap_or(0.0))
+                    })
+                    .collect();
+                let pinned = self.pinned_words.contains(&word);
+                SeriesLine { word, pinned, points }
+            })
+            .collect()
+    }
+
+    /// Append a virtual dataset, apply the current filters to it and switch to it.
+    fn add_dataset(&mut self, dataset: Dataset) {
+        self.status_message = Some(match dataset.unscored_words {
+            Some(unscored) => format!(
+                "Added ratio dataset '{}' ({} words; {} found only in the divisor are not listed)",
+                dataset.name, dataset.unique_words, unscored
+            ),
+            None => format!("Added dataset '{}' ({} words, {} unique)", dataset.name, dataset.total_words, dataset.unique_words),
+        });
+
+        // Merged words may carry tags no other dataset has, like a language's stop words
+        for tag in dataset.word_counts.iter().flat_map(|wc| &wc.tags) {
+            if !self.available_tags.contains(tag) {
+                self.available_tags.push(tag.clone());
+            }
+        }
+        self.available_tags.sort_by_key(|tag| tag.letter);
+
+        let mut list_state = ListState::default();
+        list_state.select(Some(0));
+        self.per_dataset_list_states.push(list_state);
+        self.datasets.push(dataset);
+        self.marked_datasets.clear();
+
+        self.cross_dataset_cache_dirty = true;
+        self.apply_current_filter_to_all_datasets();
+        self.switch_to_dataset(self.datasets.len() - 1);
+        if !self.chart_mode {
+            self.update_visible_datasets_for_tab();
+        }
+    }
+
+    /// Column/header title for a dataset, with a marker when it is marked for merging.
+    fn dataset_title(&self, dataset_index: usize, max_chars: usize) -> String {
+        let name = Self::truncate_string(&self.datasets[dataset_index].name, max_chars);
+        if self.marked_datasets.contains(&dataset_index) {
+            format!("● {}", name)
+        } else {
+            name
+        }
+    }
+
+    /// Write the filtered words of the active dataset (or all datasets) to `path`,
+    /// along with the filter, normalization and Zipf fit ratios shown on screen.
+    fn export_view(&self, path: &str, all_datasets: bool) -> anyhow::Result<usize> {
+        let range = if all_datasets {
+            0..self.datasets.len()
+        } else {
+            self.active_dataset_index..self.active_dataset_index + 1
+        };
+        let words = &self.per_dataset_filtered_words[range.clone()];
+
+        let fit_ratios = range
+            .clone()
+            .map(|dataset_index| {
+                // Relative fits use the window currently scrolled into view
+                let reference_words = self.zipf_reference_words(dataset_index);
+                let visible_words = self.visible_words(dataset_index);
+
+                self.per_dataset_filtered_words[dataset_index]
+                    .iter()
+                    .map(|wc| Self::zipf_fit_ratio(&self.zipf_state, wc, reference_words, visible_words))
+                    .collect()
+            })
+            .collect();
+
+        let context = ExportContext {
+            datasets: &self.datasets[range],
+            words,
+            filter_set: &self.filter_set,
+            top: None,
+            view: Some(ExportView {
+                normalization: &self.normalization_mode,
+                chart_scope: &self.chart_scope,
+                log_scale: self.log_scale,
+                zipf: &self.zipf_state,
+                fit_ratios,
+            }),
+            pgfplots: false,
+            rarefaction: false,
+        };
+
+        let format = ExportFormat::from_path(path).unwrap_or(ExportFormat::Csv);
+        let file = std::fs::File::create(path)?;
+        let mut out = io::BufWriter::new(file);
+        write_export(&mut out, format, &context)?;
+        out.flush()?;
+
+        Ok(words.iter().map(Vec::len).sum())
+    }
+
+    /// Render the chart as shown (active dataset in chart mode, all datasets overlaid
+    /// otherwise) to an SVG or PNG file.
+    fn export_chart(&self, path: &str) -> anyhow::Result<()> {
+        let dataset_indices: Vec<usize> = if self.chart_mode {
+            vec![self.active_dataset_index]
+        } else {
+            (0..self.datasets.len()).collect()
+        };
+
+        let series: Vec<ChartSeries> = dataset_indices
+            .iter()
+            .map(|&dataset_index| {
+                let dataset = &self.datasets[dataset_index];
+                let visible_words = self.visible_words(dataset_index);
+                let words = match self.chart_scope {
+                    ChartScope::Relative => visible_words,
+                    ChartScope::Absolute => &self.per_dataset_filtered_words[dataset_index],
+                };
+
+                // Same constant as the TUI's Zipf line: relative only applies to the visible scope
+                let zipf_constant = if !self.zipf_state.enabled || dataset.is_ratio() {
+                    None
+                } else if self.zipf_state.reference == ZipfReference::Relative && self.chart_scope == ChartScope::Relative {
+                    visible_words.first().map(|wc| wc.count as f64 * wc.rank as f64)
+                } else {
+                    self.zipf_reference_words(dataset_index).first().map(|wc| wc.count as f64)
+                };
+
+                ChartSeries { name: &dataset.name, words, total_words: dataset.total_words, zipf_constant, whiskers: dataset.bootstrap.as_ref().filter(|_| self.whiskers) }
+            })
+            .collect();
+
+        let options = ChartOptions {
+            log_scale: self.log_scale,
+            percentage: self.normalization_mode == NormalizationMode::Percentage,
+            title: self.chart_mode.then(|| self.datasets[self.active_dataset_index].name.clone()),
+            ..ChartOptions::default()
+        };
+
+        let format = ChartFormat::from_path(path).unwrap_or(ChartFormat::Svg);
+        let file = std::fs::File::create(path)?;
+        let mut out = io::BufWriter::new(file);
+        write_chart(&mut out, format, &series, &options)?;
+        out.flush()?;
+        Ok(())
+    }
+
+    /// The slice of a dataset's filtered words currently scrolled into view.
+    fn visible_words(&self, dataset_index: usize) -> &[WordCount] {
+        let filtered = &self.per_dataset_filtered_words[dataset_index];
+        let offset = if dataset_index == self.active_dataset_index {
+            self.list_state.offset()
+        } else {
+            self.per_dataset_list_states[dataset_index].offset()
+        };
+        let visible_start = offset.min(filtered.len());
+        let visible_end = (visible_start + self.visible_area_height).min(filtered.len());
+        &filtered[visible_start..visible_end]
+    }
+
+    fn zipf_reference_words(&self, dataset_index: usize) -> &[WordCount] {
+        match self.zipf_state.basis {
+            ZipfBasis::Filtered => &self.per_dataset_filtered_words[dataset_index],
+            ZipfBasis::Unfiltered => &self.datasets[dataset_index].word_counts,
+        }
+    }
+
     fn handle_number_input(&mut self, key: crossterm::event::KeyEvent) {
         match key.code {
             KeyCode::Char(c) if c.is_ascii_digit() => {
@@ -620,20 +872,16 @@ impl App {
                         // Exit filter mode
                         self.input_mode = InputMode::Normal;
                     }
-                    KeyCode::Char('c') => {
+                    KeyCode::Backspace => {
                         // Clear all filters and exit
                         self.filter_set.clear();
                         self.apply_current_filter_to_all_datasets();
                         self.input_mode = InputMode::Normal;
                     }
-                    KeyCode::Char(c) if c.is_ascii_digit() => {
-                        // Select tag by number - move to step 2
-                        if let Some(digit) = c.to_digit(10) {
-                            let index = (digit as usize).saturating_sub(1);
-                            if index < self.available_tags.len() {
-                                let tag = self.available_tags[index].clone();
-                                self.filter_input_state = FilterInputState::SelectingAction(tag);
-                            }
+                    KeyCode::Char(c) => {
+                        // Select a tag by its own letter - move to step 2
+                        if let Some(tag) = self.available_tags.iter().find(|tag| tag.letter.to_lowercase().eq(c.to_lowercase())) {
+                            self.filter_input_state = FilterInputState::SelectingAction(tag.clone());
                         }
                     }
                     _ => {}
@@ -663,6 +911,9 @@ impl App {
         }
     }
 
+    // A key whose arm does nothing in the current state is swallowed here rather than
+    // falling through to a later arm, so the `if`s stay inside the arms
+    #[allow(clippy::collapsible_match)]
     fn handle_normal_input(&mut self, key: crossterm::event::KeyEvent) {
         match (key.code, key.modifiers) {
                         (KeyCode::Char('q'), _) => {
@@ -761,6 +1012,21 @@ impl App {
                             let new_index = self.selected_index.saturating_sub(full_page);
                             self.update_selection(new_index);
                         }
+                        // Command line (export)
+                        (KeyCode::Char(':'), _) => {
+                            self.input_mode = InputMode::Command;
+                            self.command_input.clear();
+                        }
+                        (KeyCode::Char('E'), _) => {
+                            // Pre-fill the chart export command with an editable default file name
+                            let name = if self.chart_mode {
+                                self.datasets[self.active_dataset_index].name.as_str()
+                            } else {
+                                "zipfr"
+                            };
+                            self.input_mode = InputMode::Command;
+                            self.command_input = format!("chart {}-chart.svg", name);
+                        }
                         // Search mode
                         (KeyCode::Char('/'), _) => {
                             self.input_mode = InputMode::Search;
@@ -794,6 +1060,9 @@ impl App {
                         (KeyCode::Char('L'), _) => {
                             self.log_scale = !self.log_scale;
                         }
+                        (KeyCode::Char('W'), _) => {
+                            self.toggle_whiskers();
+                        }
                         (KeyCode::Char('Z'), _) => {
                             // Z: Toggle Zipf line on/off
                             self.zipf_state.enabled = !self.zipf_state.enabled;
@@ -843,6 +1112,12 @@ impl App {
                                 ChartScope::Absolute => ChartScope::Relative,
                             };
                         }
+                        (KeyCode::Char('V'), _) => {
+                            self.chart_view = self.chart_view.next();
+                        }
+                        (KeyCode::Char('p'), _) => {
+                            self.toggle_pinned_word();
+                        }
                         (KeyCode::Char('%'), _) => {
                             self.normalization_mode = match self.normalization_mode {
                                 NormalizationMode::Raw => NormalizationMode::Percentage,
@@ -853,6 +1128,13 @@ impl App {
                         (KeyCode::Char('C'), _) => {
                             self.toggle_chart_mode();
                         }
+                        // Dataset algebra
+                        (KeyCode::Char('m'), _) => {
+                            self.toggle_dataset_mark();
+                        }
+                        (KeyCode::Char('M'), _) => {
+                            self.merge_marked_datasets("");
+                        }
                         (KeyCode::Tab, _) => {
                             self.next_dataset();
                         }
@@ -898,6 +1180,11 @@ impl App {
         // Always show navigation line
         footer_height += 1;
         
+        // Tag legend line
+        if !self.available_tags.is_empty() {
+            footer_height += 1;
+        }
+        
         // Chart/status line (when any chart mode is active OR filter is active)
         if self.log_scale || self.zipf_state.enabled || self.chart_scope != ChartScope::Relative || !self.filter_set.is_empty() {
             footer_height += 1;
@@ -919,8 +1206,14 @@ impl App {
                     FilterInputState::SelectingAction(_) => footer_height += 1,
                 }
             },
+            InputMode::Command => footer_height += 1,
             InputMode::Normal => {},
         }
+
+        // Result of the last command
+        if self.status_message.is_some() {
+            footer_height += 1;
+        }
         
         let main_chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -960,7 +1253,7 @@ impl App {
         let title = if self.datasets.len() > 1 {
             if self.chart_mode {
                 format!("Zipfr - {} (Dataset {} of {})", 
-                    self.datasets[self.active_dataset_index].name,
+                    self.dataset_title(self.active_dataset_index, usize::MAX),
                     self.active_dataset_index + 1,
                     self.datasets.len())
             } else {
@@ -979,7 +1272,9 @@ impl App {
         let filtered_unique_words = self.filtered_word_counts.len();
         
         // Format displays based on filtering state
-        let total_display = if !self.filter_set.is_empty() {
+        let total_display = if self.datasets[self.active_dataset_index].is_ratio() {
+            "– (ratio)".to_string()
+        } else if !self.filter_set.is_empty() {
             let percentage = if original_total_words > 0 {
                 (filtered_total_words as f64 / original_total_words as f64) * 100.0
             } else {
@@ -1005,6 +1300,14 @@ impl App {
         let analysis_line = vec![
             Span::styled("Zipfian Text Analysis", Style::default().fg(Color::Gray)),
             Span::raw(" | "),
+            Span::styled(
+                format!("Language: {}", self.datasets[self.active_dataset_index].language
+                    .as_ref()
+                    .map(|language| language.name.as_str())
+                    .unwrap_or("Unknown")),
+                Style::default().fg(Color::Cyan),
+            ),
+            Span::raw(" | "),
             Span::styled(
                 format!("Total Words: {}", total_display),
                 Style::default().fg(Color::Yellow),
@@ -1054,7 +1357,7 @@ impl App {
         visible_words: &[WordCount],
         zipf_state: &ZipfState,
         normalization_mode: &NormalizationMode,
-        total_words: usize,
+        total_words: Option<usize>, // None for ratio datasets, which have no percentages
         calculate_zipf_fit: impl Fn(&WordCount, &[WordCount]) -> Option<f64>,
     ) -> Vec<ListItem<'static>> {
         words
@@ -1073,11 +1376,9 @@ impl App {
                 let count_display = match normalization_mode {
                     NormalizationMode::Raw => format!("{:6}", word_count.count),
                     NormalizationMode::Percentage => {
-                        if total_words > 0 {
-                            let percentage = (word_count.count as f64 / total_words as f64) * 100.0;
-                            format!("{:5.1}%", percentage)
-                        } else {
-                            format!("{:6}", word_count.count)
+                        match total_words.filter(|&total| total > 0) {
+                            Some(total) => format!("{:5.1}%", word_count.count as f64 / total as f64 * 100.0),
+                            None => format!("{:6}", word_count.count),
                         }
                     }
                 };
@@ -1111,22 +1412,16 @@ impl App {
 
                 // Add tag indicators
                 if !word_count.tags.is_empty() {
+                    // Sort by letter so indicators keep a stable order between rows
+                    let mut tags: Vec<&Tag> = word_count.tags.iter().collect();
+                    tags.sort_by_key(|tag| tag.letter);
+                    
                     spans.push(Span::raw(" ["));
-                    for (i, tag) in word_count.tags.iter().enumerate() {
+                    for (i, tag) in tags.into_iter().enumerate() {
                         if i > 0 { spans.push(Span::raw(",")); }
-                        let tag_color = match tag.color.as_deref() {
-                            Some("gray") => Color::Gray,
-                            Some("green") => Color::Green,
-                            Some("red") => Color::Red,
-                            Some("blue") => Color::Blue,
-                            Some("yellow") => Color::Yellow,
-                            Some("cyan") => Color::Cyan,
-                            _ => Color::Gray,
-                        };
-                        let first_char = tag.name.chars().next().unwrap_or('?');
                         spans.push(Span::styled(
-                            first_char.to_string(),
-                            Style::default().fg(tag_color)
+                            tag.letter.to_string(),
+                            Style::default().fg(tag.display_color())
                         ));
                     }
                     spans.push(Span::raw("]"));
@@ -1165,7 +1460,7 @@ impl App {
             visible_words,
             &zipf_state,
             &self.normalization_mode,
-            self.total_words,
+            (!self.datasets[self.active_dataset_index].is_ratio()).then_some(self.total_words),
             |word_count, visible_words| self.calculate_zipf_fit(word_count, visible_words),
         );
 
@@ -1196,25 +1491,12 @@ impl App {
         
         // Apply the current filter to all datasets and cache the results
         for (dataset_index, dataset) in self.datasets.iter().enumerate() {
-            let filtered_words = if self.filter_set.is_empty() {
-                dataset.word_counts.clone()
-            } else {
-                dataset.word_counts.iter()
-                    .filter(|wc| {
-                        // Apply regular filters
-                        self.filter_set.matches(wc) &&
-                        // Apply cross-dataset filter
-                        self.should_include_word_cross_dataset(&wc.word, dataset_index)
-                    })
-                    .cloned()
-                    .collect()
-            };
-
-            // Re-rank the filtered words
-            let mut ranked_words = filtered_words;
-            for (index, word_count) in ranked_words.iter_mut().enumerate() {
-                word_count.rank = index + 1;
-            }
+            let ranked_words = filter_words(
+                &dataset.word_counts,
+                &self.filter_set,
+                &self.cross_dataset_words,
+                dataset_index,
+            );
 
             // Store in cache
             if dataset_index < self.per_dataset_filtered_words.len() {
@@ -1224,6 +1506,13 @@ impl App {
             }
         }
 
+        self.per_dataset_exponents = self
+            .per_dataset_filtered_words
+            .iter()
+            .zip(&self.datasets)
+            .map(|(words, dataset)| fit_zipf(words).filter(|_| !dataset.is_ratio()).map(|fit| fit.exponent))
+            .collect();
+
         // Update current dataset's filtered words
         if self.active_dataset_index < self.per_dataset_filtered_words.len() {
             self.filtered_word_counts = self.per_dataset_filtered_words[self.active_dataset_index].clone();
@@ -1239,7 +1528,7 @@ impl App {
     }
 
     fn toggle_stopword_filter(&mut self) {
-        if let Some(stopword_tag) = self.available_tags.iter().find(|tag| tag.name == "Stop Words") {
+        if let Some(stopword_tag) = self.available_tags.iter().find(|tag| tag.name == STOPWORDS_TAG_NAME) {
             if self.filter_set.exclude_tags.contains(stopword_tag) {
                 self.filter_set.exclude_tags.retain(|t| t != stopword_tag);
             } else {
@@ -1292,21 +1581,19 @@ impl App {
             let is_active = dataset_index == self.active_dataset_index;
             
             if is_active {
-                // Clone the dataset to avoid borrow checker issues
-                let dataset = self.datasets[dataset_index].clone();
-                self.render_active_dataset_column(f, dataset_chunks[i], &dataset);
+                self.render_active_dataset_column(f, dataset_chunks[i], dataset_index);
             } else {
                 self.render_inactive_dataset_column(f, dataset_chunks[i], dataset_index);
             }
         }
     }
     
-    fn render_active_dataset_column(&mut self, f: &mut Frame, area: Rect, dataset: &Dataset) {
+    fn render_active_dataset_column(&mut self, f: &mut Frame, area: Rect, dataset_index: usize) {
         // Filtered words should already be up to date from global filter management
         
         let border_style = Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD);
         
-        let title = Self::truncate_string(&dataset.name, 15);
+        let title = self.dataset_title(dataset_index, 15);
         
         let block = Block::default()
             .borders(Borders::ALL)
@@ -1332,7 +1619,7 @@ impl App {
             visible_words,
             &zipf_state,
             &self.normalization_mode,
-            self.total_words,
+            (!self.datasets[self.active_dataset_index].is_ratio()).then_some(self.total_words),
             |_, _| None, // No fit calculations in comparison view
         );
         
@@ -1347,7 +1634,7 @@ impl App {
         let dataset = &self.datasets[dataset_index];
         let border_style = Style::default().fg(Color::Gray);
         
-        let title = Self::truncate_string(&dataset.name, 15);
+        let title = self.dataset_title(dataset_index, 15);
         
         let block = Block::default()
             .borders(Borders::ALL)
@@ -1374,7 +1661,7 @@ impl App {
             visible_words,
             &zipf_state,
             &self.normalization_mode,
-            dataset.total_words,
+            (!dataset.is_ratio()).then_some(dataset.total_words),
             |_, _| None, // No fit calculations in comparison view
         );
         
@@ -1390,6 +1677,40 @@ impl App {
     }
 
     fn render_chart(&mut self, f: &mut Frame, area: Rect) {
+        if self.chart_view == ChartView::Rarefaction {
+            // Datasets only change by being added, so curves are built once per dataset
+            for dataset in &self.datasets[self.rarefaction_curves.len()..] {
+                // Ratio datasets keep an empty curve, so curves stay aligned with datasets
+                let sizes = if dataset.is_ratio() { Vec::new() } else { sample_sizes(dataset.total_words) };
+                let points = rarefaction(dataset.word_counts.iter().map(|wc| wc.count), &sizes)
+                    .iter()
+                    .map(|point| ((point.tokens as f64).log10(), point.expected_types))
+                    .collect();
+                self.rarefaction_curves.push((dataset.name.clone(), points));
+            }
+            ChartWidget::render_rarefaction(f, area, &self.rarefaction_curves, self.active_dataset_index);
+            return;
+        }
+        if self.chart_view == ChartView::Series {
+            let members = self.series_members();
+            let lines = self.series_lines(&members);
+            let exponents: Vec<(f64, f64)> = members
+                .iter()
+                .enumerate()
+                .filter_map(|(position, &i)| {
+                    self.per_dataset_exponents.get(i).copied().flatten().map(|exponent| ((position + 1) as f64, exponent))
+                })
+                .collect();
+            let active = members.iter().position(|&i| i == self.active_dataset_index).unwrap_or(0);
+            ChartWidget::render_series(f, area, &lines, &exponents, members.len(), active);
+            return;
+        }
+        if self.chart_view == ChartView::SentenceLengths {
+            let dataset = &self.datasets[self.active_dataset_index];
+            ChartWidget::render_sentence_lengths(f, area, &dataset.name, dataset.segments.as_ref());
+            return;
+        }
+
         // Filtered words should already be up to date from global filter management
         
         // Use the exact same bounds as the visible list
@@ -1407,6 +1728,20 @@ impl App {
             &[]
         };
         
+        if self.chart_view == ChartView::WordLengths {
+            let chart_words = match self.chart_scope {
+                ChartScope::Relative => visible_words,
+                ChartScope::Absolute => &self.filtered_word_counts,
+            };
+            let selected = self.filtered_word_counts.get(self.selected_index);
+            ChartWidget::render_word_lengths(f, area, chart_words, selected);
+            return;
+        }
+
+        // Ratio scores follow no Zipf curve, so their chart has no ideal line
+        let dataset = &self.datasets[self.active_dataset_index];
+        let zipf_state = if dataset.is_ratio() { ZipfState::new() } else { self.zipf_state.clone() };
+
         // Calculate fit ratio for the selected word if in Zipf mode
         let selected_fit_ratio = if self.selected_index < self.filtered_word_counts.len() {
             let selected_word = &self.filtered_word_counts[self.selected_index];
@@ -1422,29 +1757,50 @@ impl App {
             &self.filtered_word_counts, // Pass filtered word counts
             &self.word_counts,          // Pass original word counts
             self.log_scale, 
-            &self.zipf_state,
+            &zipf_state,
             &self.chart_scope,
             self.selected_index,
             visible_start,
-            selected_fit_ratio
+            selected_fit_ratio,
+            dataset.bootstrap.as_ref().filter(|_| self.whiskers).map(|intervals| (intervals, dataset.total_words)),
         );
     }
 
     fn render_footer(&self, f: &mut Frame, area: Rect) {
         let navigation_line = if self.datasets.len() > 1 {
             if self.chart_mode {
-                "Navigation: j/k | g/G/[num]g | Ctrl+u/d/b/f | Chart: L(log) Z(zipf on/off) z(zipf mode) A(scope) %(normalize) | Datasets: [/] | Mode: C(multi) | Filter: S(stopwords) U(single) F(filter) X(cross-dataset) | /(search) n/N | q(quit)"
+                "Navigation: j/k | g/G/[num]g | Ctrl+u/d/b/f | Chart: V(view) p(pin) L(log) Z(zipf on/off) z(zipf mode) A(scope) %(normalize) | Datasets: [/] m(mark) M(merge) | Mode: C(multi) | Filter: S(stopwords) U(single) F(filter) X(cross-dataset) | /(search) n/N | :w(export) E(chart) | q(quit)"
             } else {
-                "Navigation: j/k | g/G/[num]g | Ctrl+u/d/b/f | Datasets: Tab/Shift+Tab | Mode: C(chart) | Display: %(normalize) | Chart: L(log) Z(zipf on/off) z(zipf mode) A(scope) | Filter: S(stopwords) U(single) F(filter) X(cross-dataset) | /(search) n/N | q(quit)"
+                "Navigation: j/k | g/G/[num]g | Ctrl+u/d/b/f | Datasets: Tab/Shift+Tab m(mark) M(merge) | Mode: C(chart) | Display: %(normalize) | Chart: L(log) Z(zipf on/off) z(zipf mode) A(scope) | Filter: S(stopwords) U(single) F(filter) X(cross-dataset) | /(search) n/N | :w(export) E(chart) | q(quit)"
             }
         } else {
-            "Navigation: j/k | g/G/[num]g | Ctrl+u/d/b/f | Chart: L(log) Z(zipf on/off) z(zipf mode) A(scope) %(normalize) | Filter: S(stopwords) U(single) F(filter) X(cross-dataset) | /(search) n/N | q(quit)"
+            "Navigation: j/k | g/G/[num]g | Ctrl+u/d/b/f | Chart: V(view) p(pin) L(log) Z(zipf on/off) z(zipf mode) A(scope) %(normalize) | Filter: S(stopwords) U(single) F(filter) X(cross-dataset) | /(search) n/N | :w(export) E(chart) | q(quit)"
+        };
+        // Whiskers only apply to datasets with bootstrap intervals
+        let navigation_line = if self.datasets[self.active_dataset_index].bootstrap.is_some() {
+            navigation_line.replace("E(chart)", "E(chart) W(whiskers)")
+        } else {
+            navigation_line.to_string()
         };
         
         let mut lines = vec![
             Line::from(navigation_line)
         ];
         
+        // Tag legend: letter and color of every available tag
+        if !self.available_tags.is_empty() {
+            let mut legend = vec![Span::styled("Tags: ", Style::default().fg(Color::Gray))];
+            for (i, tag) in self.available_tags.iter().enumerate() {
+                if i > 0 { legend.push(Span::raw("  ")); }
+                legend.push(Span::styled(
+                    format!("[{}]", tag.letter),
+                    Style::default().fg(tag.display_color()).add_modifier(Modifier::BOLD),
+                ));
+                legend.push(Span::styled(format!(" {}", tag.name), Style::default().fg(tag.display_color())));
+            }
+            lines.push(Line::from(legend));
+        }
+        
         // Show current chart modes and filter status on one line
         let mut chart_status = Vec::new();
         if self.log_scale {
@@ -1485,6 +1841,18 @@ impl App {
             chart_status.push(Span::styled(label, Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)));
         }
         
+        let view_label = match self.chart_view {
+            ChartView::Zipf => None,
+            ChartView::WordLengths => Some("WORD-LENGTHS"),
+            ChartView::SentenceLengths => Some("SENTENCES"),
+            ChartView::Series => Some("SERIES"),
+            ChartView::Rarefaction => Some("RAREFACTION"),
+        };
+        if let Some(label) = view_label {
+            if !chart_status.is_empty() { chart_status.push(Span::raw(" | ")); }
+            chart_status.push(Span::styled(label, Style::default().fg(Color::Blue).add_modifier(Modifier::BOLD)));
+        }
+
         // Add normalization mode indicator
         match self.normalization_mode {
             NormalizationMode::Percentage => {
@@ -1593,20 +1961,20 @@ impl App {
                                 Span::styled("Filter Tags: ", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
                             ];
                             
-                            for (i, tag) in self.available_tags.iter().take(9).enumerate() {
+                            // Every tag is picked by its own letter, so none falls off the menu
+                            for (i, tag) in self.available_tags.iter().enumerate() {
                                 if i > 0 { filter_line.push(Span::raw(" | ")); }
-                                let tag_name = if tag.name.len() > 10 {
-                                    format!("{}:{}", i + 1, &tag.name[..10])
-                                } else {
-                                    format!("{}:{}", i + 1, tag.name)
-                                };
                                 filter_line.push(Span::styled(
-                                    tag_name,
+                                    format!("[{}]", tag.letter),
+                                    Style::default().fg(tag.display_color()).add_modifier(Modifier::BOLD)
+                                ));
+                                filter_line.push(Span::styled(
+                                    Self::truncate_string(&tag.name, 10),
                                     Style::default().fg(Color::Cyan)
                                 ));
                             }
                             
-                            filter_line.push(Span::styled(" | c:clear | Esc:cancel", Style::default().fg(Color::Gray)));
+                            filter_line.push(Span::styled(" | Backspace:clear | Esc:cancel", Style::default().fg(Color::Gray)));
                             lines.push(Line::from(filter_line));
                         }
                     }
@@ -1614,6 +1982,7 @@ impl App {
                         // Step 2: Show include/exclude options for selected tag
                         lines.push(Line::from(vec![
                             Span::styled("Filter \"", Style::default().fg(Color::Yellow)),
+                            Span::styled(format!("[{}]", tag.letter), Style::default().fg(tag.display_color()).add_modifier(Modifier::BOLD)),
                             Span::styled(&tag.name, Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
                             Span::styled("\": ", Style::default().fg(Color::Yellow)),
                             Span::styled("e", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
@@ -1625,11 +1994,23 @@ impl App {
                     }
                 }
             },
+            InputMode::Command => {
+                lines.push(Line::from(vec![
+                    Span::styled(":", Style::default().fg(Color::Yellow)),
+                    Span::styled(&self.command_input, Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+                    Span::raw("_"), // Cursor
+                    Span::styled(" | w <file>(export dataset) wa <file>(export all) chart <file.svg|png> merge [name] diff ratio | Enter(run) Esc(cancel)", Style::default().fg(Color::Gray)),
+                ]));
+            },
             InputMode::Normal => {},
         }
+
+        if let Some(message) = &self.status_message {
+            lines.push(Line::from(Span::styled(message.as_str(), Style::default().fg(Color::Yellow))));
+        }
         
         let footer = Paragraph::new(lines)
             .block(Block::default().borders(Borders::ALL).title("Controls"));
         f.render_widget(footer, area);
     }
-}
\ No newline at end of file
+}
@@ -1,22 +1,38 @@
 use crate::analyzer::WordCount;
-use crate::tui::app::{ZipfState, ZipfBasis, ZipfReference, ChartScope};
+use crate::bootstrap::Bootstrap;
+use crate::segment::SegmentStats;
+use crate::stats::spearman;
+use crate::view::{ZipfState, ZipfBasis, ZipfReference, ChartScope};
 use ratatui::{
-    layout::Rect,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     symbols,
-    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType},
+    widgets::{Axis, BarChart, Block, Borders, Chart, Dataset, GraphType, Paragraph},
     Frame,
 };
+use std::collections::BTreeMap;
 
 pub struct ChartWidget;
 
+/// One word's relative frequency (%) per dataset of a series, as (position, percentage) points.
+pub struct SeriesLine {
+    pub word: String,
+    pub pinned: bool,
+    pub points: Vec<(f64, f64)>,
+}
+
+/// Line colors of the series view; the first goes to the selected word.
+const SERIES_COLORS: [Color; 8] = [
+    Color::Yellow, Color::Cyan, Color::Green, Color::Magenta, Color::LightRed, Color::LightBlue, Color::White, Color::LightGreen,
+];
+
 impl ChartWidget {
     fn deviation_to_color(ratio: f64) -> Color {
         match ratio {
-            r if r >= 0.9 && r <= 1.1 => Color::Green,      // Perfect fit (±10%)
-            r if r >= 0.7 && r < 0.9 => Color::Yellow,       // Good fit (underperforming)
+            r if (0.9..=1.1).contains(&r) => Color::Green,   // Perfect fit (±10%)
+            r if (0.7..0.9).contains(&r) => Color::Yellow,    // Good fit (underperforming)
             r if r > 1.1 && r <= 1.3 => Color::Yellow,       // Good fit (overperforming)
-            r if r >= 0.5 && r < 0.7 => Color::Cyan,         // Moderate underperforming
+            r if (0.5..0.7).contains(&r) => Color::Cyan,      // Moderate underperforming
             r if r > 1.3 && r <= 2.0 => Color::Magenta,      // Moderate overperforming
             r if r < 0.5 => Color::Blue,                     // Extreme underperforming
             r if r > 2.0 => Color::Red,                      // Extreme overperforming
@@ -25,9 +41,268 @@ impl ChartWidget {
     }
     pub fn render(f: &mut Frame, area: Rect, word_counts: &[WordCount], max_items: usize) {
         let visible_words = &word_counts[..max_items.min(word_counts.len())];
-        Self::render_enhanced(f, area, visible_words, word_counts, word_counts, false, &ZipfState::new(), &ChartScope::Relative, 0, 0, None);
+        Self::render_enhanced(f, area, visible_words, word_counts, word_counts, false, &ZipfState::new(), &ChartScope::Relative, 0, 0, None, None);
     }
 
+    /// Scatter of word length against log frequency, with the mean log frequency per length as a
+    /// line and the selected word highlighted. The title shows the rank–length correlation.
+    pub fn render_word_lengths(f: &mut Frame, area: Rect, chart_words: &[WordCount], selected: Option<&WordCount>) {
+        if chart_words.is_empty() {
+            return;
+        }
+        let point = |wc: &WordCount| (wc.word.chars().count() as f64, (wc.count as f64).ln());
+        let data: Vec<(f64, f64)> = chart_words.iter().map(point).collect();
+
+        let mut by_length: BTreeMap<usize, (f64, usize)> = BTreeMap::new();
+        for &(length, log_count) in &data {
+            let entry = by_length.entry(length as usize).or_default();
+            entry.0 += log_count;
+            entry.1 += 1;
+        }
+        let means: Vec<(f64, f64)> = by_length.iter().map(|(&length, &(sum, n))| (length as f64, sum / n as f64)).collect();
+
+        let selected_data: Vec<(f64, f64)> = selected.map(point).into_iter().collect();
+        let max_length = data.iter().chain(&selected_data).map(|(x, _)| *x).fold(1.0, f64::max) + 1.0;
+        let max_log = data.iter().chain(&selected_data).map(|(_, y)| *y).fold(0.0, f64::max).max(1.0);
+
+        let counts: Vec<f64> = chart_words.iter().map(|wc| -(wc.count as f64)).collect();
+        let lengths: Vec<f64> = data.iter().map(|(x, _)| *x).collect();
+        let title = match spearman(&counts, &lengths) {
+            Some(rho) => format!("Word length vs log frequency | rank–length ρ = {:.3}", rho),
+            None => "Word length vs log frequency".to_string(),
+        };
+
+        let mut datasets = vec![
+            Dataset::default()
+                .name("Words")
+                .marker(symbols::Marker::Braille)
+                .style(Style::default().fg(Color::Cyan))
+                .graph_type(GraphType::Scatter)
+                .data(&data),
+            Dataset::default()
+                .name("Mean per length")
+                .marker(symbols::Marker::Braille)
+                .style(Style::default().fg(Color::Red))
+                .graph_type(GraphType::Line)
+                .data(&means),
+        ];
+        if let Some(selected) = selected {
+            datasets.push(
+                Dataset::default()
+                    .name(format!("Selected: {}", selected.word))
+                    .marker(symbols::Marker::Block)
+                    .style(Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+                    .graph_type(GraphType::Scatter)
+                    .data(&selected_data),
+            );
+        }
+
+        let chart = Chart::new(datasets)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .x_axis(
+                Axis::default()
+                    .title("Length (characters)")
+                    .style(Style::default().fg(Color::Gray))
+                    .bounds([0.0, max_length])
+                    .labels(vec![
+                        "0".into(),
+                        format!("{}", (max_length / 2.0) as usize).into(),
+                        format!("{}", max_length as usize).into(),
+                    ]),
+            )
+            .y_axis(
+                Axis::default()
+                    .title("Log Frequency")
+                    .style(Style::default().fg(Color::Gray))
+                    .bounds([0.0, max_log])
+                    .labels(vec!["0".into(), format!("{:.1}", max_log / 2.0).into(), format!("{:.1}", max_log).into()]),
+            );
+        f.render_widget(chart, area);
+    }
+
+    /// Relative frequency of the selected and pinned words across the datasets of one series (top),
+    /// and the fitted Zipf exponent of each one's filtered words (bottom). `active` is the active
+    /// dataset's position in the series, marked on both charts.
+    pub fn render_series(f: &mut Frame, area: Rect, lines: &[SeriesLine], exponents: &[(f64, f64)], dataset_count: usize, active: usize) {
+        if dataset_count < 2 {
+            let message = Paragraph::new("The active dataset is not part of a series: split a text with --segments N or --split-on REGEX")
+                .alignment(Alignment::Center)
+                .block(Block::default().title("Series").borders(Borders::ALL));
+            f.render_widget(message, area);
+            return;
+        }
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Percentage(65), Constraint::Percentage(35)])
+            .split(area);
+        let x_bounds = [1.0, dataset_count as f64];
+        let x_labels = vec!["1".into(), format!("{}", dataset_count.div_ceil(2)).into(), format!("{}", dataset_count).into()];
+        let active_x = (active + 1) as f64;
+
+        // Relative frequencies
+        let max_percentage = lines.iter().flat_map(|line| &line.points).map(|(_, y)| *y).fold(0.0, f64::max);
+        let max_percentage = if max_percentage > 0.0 { max_percentage * 1.1 } else { 1.0 };
+        let active_marker = [(active_x, 0.0), (active_x, max_percentage)];
+        let mut datasets: Vec<Dataset> = lines
+            .iter()
+            .take(SERIES_COLORS.len())
+            .enumerate()
+            .map(|(i, line)| {
+                let name = if line.pinned { format!("{} (pinned)", line.word) } else { line.word.clone() };
+                Dataset::default()
+                    .name(name)
+                    .marker(symbols::Marker::Braille)
+                    .style(Style::default().fg(SERIES_COLORS[i]))
+                    .graph_type(GraphType::Line)
+                    .data(&line.points)
+            })
+            .collect();
+        datasets.push(
+            Dataset::default()
+                .marker(symbols::Marker::Dot)
+                .style(Style::default().fg(Color::DarkGray))
+                .graph_type(GraphType::Line)
+                .data(&active_marker),
+        );
+        let frequencies = Chart::new(datasets)
+            .block(Block::default().title(format!("Relative frequency across {} datasets | p: pin word", dataset_count)).borders(Borders::ALL))
+            .x_axis(
+                Axis::default()
+                    .title("Dataset")
+                    .style(Style::default().fg(Color::Gray))
+                    .bounds(x_bounds)
+                    .labels(x_labels.clone()),
+            )
+            .y_axis(
+                Axis::default()
+                    .title("%")
+                    .style(Style::default().fg(Color::Gray))
+                    .bounds([0.0, max_percentage])
+                    .labels(vec!["0".into(), format!("{:.2}", max_percentage / 2.0).into(), format!("{:.2}", max_percentage).into()]),
+            );
+        f.render_widget(frequencies, chunks[0]);
+
+        // Zipf exponents
+        let (low, high) = exponents.iter().fold((f64::MAX, f64::MIN), |(low, high), (_, y)| (low.min(*y), high.max(*y)));
+        let (low, high) = if low <= high { (low - 0.05, high + 0.05) } else { (0.0, 2.0) };
+        let exponent_data = vec![Dataset::default()
+                .name("Zipf exponent")
+                .marker(symbols::Marker::Braille)
+                .style(Style::default().fg(Color::Red))
+                .graph_type(GraphType::Line)
+                .data(exponents)];
+        let title = match exponents.iter().find(|(x, _)| *x == active_x) {
+            Some((_, exponent)) => format!("Zipf exponent per dataset | active: {:.3}", exponent),
+            None => "Zipf exponent per dataset".to_string(),
+        };
+        let exponent_chart = Chart::new(exponent_data)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .x_axis(Axis::default().style(Style::default().fg(Color::Gray)).bounds(x_bounds).labels(x_labels))
+            .y_axis(
+                Axis::default()
+                    .title("s")
+                    .style(Style::default().fg(Color::Gray))
+                    .bounds([low, high])
+                    .labels(vec![format!("{:.2}", low).into(), format!("{:.2}", high).into()]),
+            );
+        f.render_widget(exponent_chart, chunks[1]);
+    }
+
+    /// Expected unique words against sample size (log scale) for every dataset, one line each;
+    /// the active dataset's line is bold. Curves are (log10 tokens, expected types) points.
+    pub fn render_rarefaction(f: &mut Frame, area: Rect, curves: &[(String, Vec<(f64, f64)>)], active: usize) {
+        let points = || curves.iter().flat_map(|(_, points)| points);
+        let (min_x, max_x) = points().fold((f64::MAX, f64::MIN), |(low, high), (x, _)| (low.min(*x), high.max(*x)));
+        if min_x > max_x {
+            return;
+        }
+        let (min_x, max_x) = (min_x.floor(), max_x.ceil().max(min_x.floor() + 1.0));
+        let max_types = points().map(|(_, y)| *y).fold(1.0, f64::max) * 1.05;
+
+        let datasets: Vec<Dataset> = curves
+            .iter()
+            .enumerate()
+            .map(|(i, (name, points))| {
+                let style = Style::default().fg(SERIES_COLORS[i % SERIES_COLORS.len()]);
+                let style = if i == active { style.add_modifier(Modifier::BOLD) } else { style };
+                Dataset::default()
+                    .name(name.as_str())
+                    .marker(symbols::Marker::Braille)
+                    .style(style)
+                    .graph_type(GraphType::Line)
+                    .data(points)
+            })
+            .collect();
+
+        let format_tokens = |exponent: f64| match 10f64.powf(exponent).round() as u64 {
+            tokens if tokens >= 1_000_000 => format!("{}M", tokens / 1_000_000),
+            tokens if tokens >= 1_000 => format!("{}k", tokens / 1_000),
+            tokens => tokens.to_string(),
+        };
+        let title = match curves.get(active).and_then(|(name, points)| Some((name, points.last()?))) {
+            Some((name, (_, types))) => format!("Rarefaction: expected unique words by sample size | {}: {:.0} types", name, types),
+            None => "Rarefaction: expected unique words by sample size".to_string(),
+        };
+        let chart = Chart::new(datasets)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .x_axis(
+                Axis::default()
+                    .title("Tokens (log)")
+                    .style(Style::default().fg(Color::Gray))
+                    .bounds([min_x, max_x])
+                    .labels(vec![
+                        format_tokens(min_x).into(),
+                        format_tokens((min_x + max_x) / 2.0).into(),
+                        format_tokens(max_x).into(),
+                    ]),
+            )
+            .y_axis(
+                Axis::default()
+                    .title("Types")
+                    .style(Style::default().fg(Color::Gray))
+                    .bounds([0.0, max_types])
+                    .labels(vec!["0".into(), format!("{:.0}", max_types / 2.0).into(), format!("{:.0}", max_types).into()]),
+            );
+        f.render_widget(chart, area);
+    }
+
+    /// Sentences per length bin, with as many bins as fit the panel (bars labeled by their first length).
+    pub fn render_sentence_lengths(f: &mut Frame, area: Rect, name: &str, segments: Option<&SegmentStats>) {
+        const BAR_WIDTH: u16 = 4;
+
+        let Some(segments) = segments.filter(|segments| segments.sentences() > 0) else {
+            let message = Paragraph::new("No sentences: segmentation needs prose read with the word tokenizer")
+                .alignment(Alignment::Center)
+                .block(Block::default().title(format!("Sentence lengths - {}", name)).borders(Borders::ALL));
+            f.render_widget(message, area);
+            return;
+        };
+
+        let max_bins = (area.width.saturating_sub(2) / (BAR_WIDTH + 1)).max(1) as usize;
+        let bins = segments.histogram(max_bins);
+        let labels: Vec<String> = bins.iter().map(|(first, _, _)| first.to_string()).collect();
+        let data: Vec<(&str, u64)> = labels.iter().zip(&bins).map(|(label, (_, _, count))| (label.as_str(), *count as u64)).collect();
+
+        let title = format!(
+            "Sentence lengths (words) - {} | {} sentences, mean {:.1}, median {}, {} paragraphs",
+            name,
+            segments.sentences(),
+            segments.mean_length().unwrap_or(0.0),
+            segments.median_length().unwrap_or(0.0),
+            segments.paragraphs
+        );
+        let chart = BarChart::default()
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .data(&data)
+            .bar_width(BAR_WIDTH)
+            .bar_gap(1)
+            .bar_style(Style::default().fg(Color::Cyan))
+            .value_style(Style::default().fg(Color::Black).bg(Color::Cyan));
+        f.render_widget(chart, area);
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn render_enhanced(
         f: &mut Frame, 
         area: Rect, 
@@ -39,7 +314,8 @@ impl ChartWidget {
         chart_scope: &ChartScope,
         selected_index: usize,
         _visible_start: usize,
-        selected_fit_ratio: Option<f64>
+        selected_fit_ratio: Option<f64>,
+        whiskers: Option<(&Bootstrap, usize)>, // Intervals and the dataset's total words, to draw each word's interval
     ) {
         if visible_words.is_empty() {
             return;
@@ -168,12 +444,40 @@ impl ChartWidget {
             }
         };
 
-        let mut datasets = vec![Dataset::default()
+        // Bootstrap intervals as vertical segments, one per word, in counts like the plotted data
+        let whisker_data: Vec<[(f64, f64); 2]> = match whiskers {
+            Some((intervals, total_words)) => chart_words
+                .iter()
+                .filter_map(|wc| {
+                    let interval = intervals.word(&wc.word)?.percentage;
+                    let rank = wc.rank as f64;
+                    let x = if log_scale { rank.ln().max(0.1) } else { rank };
+                    let y = |percentage: f64| {
+                        let count = percentage * total_words as f64 / 100.0;
+                        if log_scale { count.ln().max(0.1) } else { count }
+                    };
+                    Some([(x, y(interval.low)), (x, y(interval.high))])
+                })
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let mut datasets: Vec<Dataset> = whisker_data
+            .iter()
+            .map(|segment| {
+                Dataset::default()
+                    .marker(symbols::Marker::Braille)
+                    .style(Style::default().fg(Color::DarkGray))
+                    .graph_type(GraphType::Line)
+                    .data(segment)
+            })
+            .collect();
+        datasets.push(Dataset::default()
             .name("Actual Frequency")
             .marker(symbols::Marker::Braille)
             .style(Style::default().fg(Color::Cyan))
             .graph_type(GraphType::Line)
-            .data(&data)];
+            .data(&data));
 
         // Add highlighted point for currently selected word
         let selected_data: Vec<(f64, f64)> = if selected_index < filtered_words.len() {
@@ -244,10 +548,13 @@ impl ChartWidget {
             let max_count = chart_words.iter().map(|wc| wc.count).max().unwrap_or(1) as f64;
             (0.0, max_count)
         };
+        // Whiskers may reach above the highest count
+        let max_freq = whisker_data.iter().map(|[_, (_, high)]| *high).fold(max_freq, f64::max);
 
         // Create title with current mode indicators
         let mut title = "Zipf Distribution".to_string();
         if log_scale { title.push_str(" (Log-Log Scale)"); }
+        if !whisker_data.is_empty() { title.push_str(" + Intervals"); }
         match chart_scope {
             ChartScope::Absolute => title.push_str(" [All Data]"),
             ChartScope::Relative => title.push_str(" [Visible Range]"),
@@ -0,0 +1,52 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ZipfState {
+    pub enabled: bool,
+    pub basis: ZipfBasis,      // Filtered vs Unfiltered (persistent across scope changes)
+    pub reference: ZipfReference, // Absolute vs Relative (scope-dependent)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZipfBasis {
+    Filtered,    // Use filtered dataset
+    Unfiltered,  // Use original dataset
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ZipfReference {
+    Absolute,    // Global reference point
+    Relative,    // Local/visible reference point
+}
+
+impl ZipfState {
+    pub fn new() -> Self {
+        Self {
+            enabled: false,
+            basis: ZipfBasis::Unfiltered, // Default to unfiltered
+            reference: ZipfReference::Absolute, // Default to absolute
+        }
+    }
+}
+
+impl Default for ZipfState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChartScope {
+    Relative,  // Show only visible list range
+    Absolute,  // Show entire dataset
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NormalizationMode {
+    Raw,        // Show raw counts (default)
+    Percentage, // Show as percentage of total words
+}